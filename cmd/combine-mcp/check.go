@@ -0,0 +1,94 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"os"
+	"os/signal"
+	"syscall"
+	"text/tabwriter"
+
+	"github.com/nazar256/combine-mcp/pkg/aggregator"
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// check runs the --check mode and returns the exit code
+func check(opts *options) int {
+	return runOneShot(opts, func(ctx context.Context, cfg *config.Config) bool {
+		return runCheck(ctx, cfg, os.Stdout)
+	})
+}
+
+// runOneShot loads the config and sets up logging for a mode that starts the servers, runs once and exits,
+// then runs it and returns the exit code
+func runOneShot(opts *options, run func(ctx context.Context, cfg *config.Config) bool) int {
+	cfg, err := loadConfig(opts)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "Error loading configuration: %v\n", err)
+		if hint := initHint(err); hint != "" {
+			fmt.Fprintln(os.Stderr, hint)
+		}
+		return 1
+	}
+	if err := logger.Init(cfg.LogLevel, cfg.LogFile, cfg.LogFileOptions()); err != nil {
+		fmt.Fprintf(os.Stderr, "Error initializing logger: %v\n", err)
+		return 1
+	}
+	logger.SetServerLevels(cfg.ServerLogLevels)
+	defer logger.Close()
+	// Messages logged while servers start would end up between the rows or in the JSON on stdout
+	logger.SetConsoleOutput(os.Stderr)
+
+	for _, warning := range cfg.Warnings {
+		fmt.Fprintf(os.Stderr, "Warning: %s\n", warning)
+	}
+
+	ctx, cancel := signal.NotifyContext(context.Background(), syscall.SIGINT, syscall.SIGTERM)
+	defer cancel()
+	if !run(ctx, cfg) {
+		return 1
+	}
+	return 0
+}
+
+// startEagerly returns a copy of the config that starts every server right away, lazy ones included,
+// instead of serving their tools from the tool cache. The pid file is left to the aggregator serving clients.
+func startEagerly(cfg *config.Config) *config.Config {
+	eager := *cfg
+	eager.ToolCacheFile = ""
+	eager.PidFile = ""
+	eager.Servers = append([]config.ServerConfig(nil), cfg.Servers...)
+	for i := range eager.Servers {
+		eager.Servers[i].Lazy = false
+	}
+	return &eager
+}
+
+// runCheck starts every configured server, lazy ones included, and writes a table of the servers with
+// their tool counts or the error they failed with to out. It reports whether every server started.
+func runCheck(ctx context.Context, cfg *config.Config, out io.Writer) bool {
+	agg := aggregator.NewMCPAggregator()
+	defer agg.Close()
+	initErr := agg.Initialize(ctx, startEagerly(cfg))
+
+	ok := true
+	table := tabwriter.NewWriter(out, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(table, "SERVER\tSTATUS\tTOOLS\tERROR")
+	for _, status := range agg.Status() {
+		state := "ok"
+		if !status.Running {
+			state = "failed"
+			ok = false
+		}
+		fmt.Fprintf(table, "%s\t%s\t%d\t%s\n", status.Name, state, status.Tools, status.LastError)
+	}
+	table.Flush()
+
+	if initErr != nil {
+		fmt.Fprintf(out, "\nError: %v\n", initErr)
+		ok = false
+	}
+	return ok
+}
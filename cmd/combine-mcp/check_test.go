@@ -0,0 +1,184 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"encoding/json"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// TestHelperProcess isn't a real test, it's the MCP server started by helperServer
+func TestHelperProcess(t *testing.T) {
+	if os.Getenv("GO_WANT_HELPER_PROCESS") != "1" {
+		return
+	}
+	runToolServer(os.Getenv("HELPER_TOOL"))
+	os.Exit(0)
+}
+
+// helperServer returns the config of a server that runs this test binary and lists a single tool named tool
+func helperServer(name, tool string) config.ServerConfig {
+	return config.ServerConfig{
+		Name:    name,
+		Command: os.Args[0],
+		Args:    []string{"-test.run=TestHelperProcess", "--"},
+		Env:     map[string]string{"GO_WANT_HELPER_PROCESS": "1", "HELPER_TOOL": tool},
+	}
+}
+
+// runToolServer answers MCP requests on stdin until it closes, listing a single tool that answers every call with "ok"
+func runToolServer(tool string) {
+	encoder := json.NewEncoder(os.Stdout)
+	scanner := bufio.NewScanner(os.Stdin)
+	for scanner.Scan() {
+		var request struct {
+			ID     json.RawMessage `json:"id"`
+			Method string          `json:"method"`
+		}
+		if err := json.Unmarshal(scanner.Bytes(), &request); err != nil || len(request.ID) == 0 {
+			continue // Notifications need no answer
+		}
+
+		var result interface{}
+		switch request.Method {
+		case "initialize":
+			result = map[string]interface{}{
+				"protocolVersion": "2024-11-05",
+				"capabilities":    map[string]interface{}{"tools": map[string]interface{}{}},
+				"serverInfo":      map[string]interface{}{"name": "helper", "version": "1.0.0"},
+			}
+		case "tools/list":
+			result = map[string]interface{}{"tools": []interface{}{
+				map[string]interface{}{"name": tool, "inputSchema": map[string]interface{}{"type": "object"}},
+			}}
+		case "tools/call":
+			result = map[string]interface{}{"content": []interface{}{map[string]interface{}{"type": "text", "text": "ok"}}}
+		default:
+			result = map[string]interface{}{}
+		}
+		encoder.Encode(map[string]interface{}{"jsonrpc": "2.0", "id": request.ID, "result": result})
+	}
+}
+
+func TestRunCheck(t *testing.T) {
+	cfg := &config.Config{Servers: []config.ServerConfig{
+		{Name: "broken", Command: "/nonexistent/combine-mcp-test-server", Lazy: true},
+	}}
+
+	var out bytes.Buffer
+	if runCheck(context.Background(), cfg, &out) {
+		t.Errorf("runCheck() = true with a server that can't start, want false")
+	}
+	var row string
+	for _, line := range strings.Split(out.String(), "\n") {
+		if strings.HasPrefix(line, "broken") {
+			row = line
+		}
+	}
+	if !strings.Contains(row, "failed") {
+		t.Errorf("runCheck() output %q has no failed row for the lazy server", out.String())
+	}
+	if !cfg.Servers[0].Lazy {
+		t.Errorf("runCheck() changed the config it was given")
+	}
+}
+
+func TestRunListTools(t *testing.T) {
+	cfg := &config.Config{Servers: []config.ServerConfig{
+		{Name: "broken", Command: "/nonexistent/combine-mcp-test-server", Lazy: true},
+	}}
+
+	var out, errOut bytes.Buffer
+	if runListTools(context.Background(), cfg, &out, &errOut, true) {
+		t.Errorf("runListTools() = true with a server that can't start, want false")
+	}
+	var listed []listedTool
+	if err := json.Unmarshal(out.Bytes(), &listed); err != nil || len(listed) != 0 {
+		t.Errorf("runListTools() JSON output = %q, want an empty list", out.String())
+	}
+	if !strings.Contains(errOut.String(), "server broken failed") {
+		t.Errorf("runListTools() errors %q don't report the failed server", errOut.String())
+	}
+
+	out.Reset()
+	runListTools(context.Background(), cfg, &out, io.Discard, false)
+	if !strings.HasPrefix(out.String(), "TOOL") {
+		t.Errorf("runListTools() table output = %q, want a header row", out.String())
+	}
+}
+
+func TestListToolsHidesShadowedTools(t *testing.T) {
+	prefixTools := false
+	for _, precedence := range []string{config.ToolPrecedenceRoutedFirst, config.ToolPrecedenceBuiltinFirst} {
+		t.Run(precedence, func(t *testing.T) {
+			cfg := &config.Config{
+				Servers:        []config.ServerConfig{helperServer("helper", "combine_mcp_status")},
+				PrefixTools:    &prefixTools,
+				ToolPrecedence: precedence,
+			}
+
+			var out bytes.Buffer
+			if !runListTools(context.Background(), cfg, &out, io.Discard, true) {
+				t.Fatalf("runListTools() = false, want every server started")
+			}
+			var listed []listedTool
+			if err := json.Unmarshal(out.Bytes(), &listed); err != nil {
+				t.Fatalf("runListTools() JSON output %q doesn't decode: %v", out.String(), err)
+			}
+			// The built-in tool hides the server's tool of its name, which can't be called then
+			wantListed := precedence == config.ToolPrecedenceRoutedFirst
+			if got := len(listed) == 1 && listed[0].Name == "combine_mcp_status"; got != wantListed {
+				t.Errorf("runListTools() listed %+v, want the server's combine_mcp_status listed %v", listed, wantListed)
+			}
+		})
+	}
+}
+
+func TestOneShotLogsStayOffStdout(t *testing.T) {
+	cfg := config.Config{Servers: []config.ServerConfig{helperServer("helper", "echo")}}
+	data, err := json.Marshal(cfg)
+	if err != nil {
+		t.Fatalf("Marshal() error = %v", err)
+	}
+	configPath := filepath.Join(t.TempDir(), "config.json")
+	if err := os.WriteFile(configPath, data, 0644); err != nil {
+		t.Fatalf("WriteFile() error = %v", err)
+	}
+
+	stdout, err := os.Create(filepath.Join(t.TempDir(), "stdout"))
+	if err != nil {
+		t.Fatalf("Create() error = %v", err)
+	}
+	defer stdout.Close()
+	realStdout := os.Stdout
+	os.Stdout = stdout
+	defer func() { os.Stdout = realStdout }()
+
+	// The logger writes info messages, such as about servers starting, to stdout until told otherwise
+	if err := logger.Init(config.LogLevelInfo, "", config.LogFileOptions{}); err != nil {
+		t.Fatalf("logger.Init() error = %v", err)
+	}
+	logger.SetLevel(config.LogLevelInfo)
+	logger.SetConsoleOutput(stdout)
+	defer logger.SetConsoleOutput(os.Stderr)
+
+	if code := listTools(&options{configPath: configPath, listTools: true, jsonOutput: true}); code != 0 {
+		t.Fatalf("listTools() = %d, want 0", code)
+	}
+	output, err := os.ReadFile(stdout.Name())
+	if err != nil {
+		t.Fatalf("ReadFile() error = %v", err)
+	}
+	var listed []listedTool
+	if err := json.Unmarshal(output, &listed); err != nil || len(listed) != 1 {
+		t.Errorf("--list-tools --json output = %q, want just the JSON list of the helper's tool", output)
+	}
+}
@@ -0,0 +1,135 @@
+package main
+
+import (
+	"flag"
+	"fmt"
+	"io"
+	"net"
+	"strconv"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// Transports MCP is served to clients over
+const (
+	// transportStdio serves a single client on stdin and stdout (default)
+	transportStdio = "stdio"
+	// transportHTTP serves any number of clients over HTTP with server-sent events
+	transportHTTP = "http"
+)
+
+const (
+	// defaultHTTPHost is the address the HTTP transport listens on, reachable only from this machine
+	defaultHTTPHost = "127.0.0.1"
+	// defaultHTTPPort is the port the HTTP transport listens on
+	defaultHTTPPort = 8080
+)
+
+// options holds the settings given on the command line, which take precedence over environment variables
+type options struct {
+	configPath  string
+	logLevel    string
+	logFile     string
+	showVersion bool
+	check       bool
+	listTools   bool
+	jsonOutput  bool
+	initConfig  bool
+	transport   string
+	host        string
+	port        int
+}
+
+// servesHTTP reports whether MCP is served over HTTP instead of stdio
+func (o *options) servesHTTP() bool {
+	return o.transport == transportHTTP
+}
+
+// httpAddr returns the address MCP is served on over HTTP
+func (o *options) httpAddr() string {
+	host, port := o.host, o.port
+	if host == "" {
+		host = defaultHTTPHost
+	}
+	if port == 0 {
+		port = defaultHTTPPort
+	}
+	return net.JoinHostPort(host, strconv.Itoa(port))
+}
+
+// parseFlags parses the command line arguments, without the program name.
+// Errors and usage are written to output.
+func parseFlags(args []string, output io.Writer) (*options, error) {
+	opts := &options{}
+	flags := flag.NewFlagSet("combine-mcp", flag.ContinueOnError)
+	flags.SetOutput(output)
+	flags.Func("config", "path to the config file, repeatable or comma-separated to merge several files (default: $"+config.DefaultEnvVar+")", func(value string) error {
+		if opts.configPath != "" {
+			opts.configPath += config.ConfigPathSeparator
+		}
+		opts.configPath += value
+		return nil
+	})
+	flags.StringVar(&opts.logLevel, "log-level", "", "log level: error, info, debug or trace, optionally followed by server=level directives (default: $"+config.LogLevelEnvVar+" or info)")
+	flags.StringVar(&opts.logFile, "log-file", "", "path to the log file (default: $"+config.LogToFileEnvVar+")")
+	flags.BoolVar(&opts.showVersion, "version", false, "print the version and exit")
+	flags.StringVar(&opts.transport, "transport", "", "how MCP is served: stdio or http (default: stdio)")
+	flags.StringVar(&opts.host, "host", "", "address the http transport listens on (default: "+defaultHTTPHost+")")
+	flags.IntVar(&opts.port, "port", 0, "port the http transport listens on (default: "+strconv.Itoa(defaultHTTPPort)+")")
+	flags.BoolVar(&opts.initConfig, "init", false, "write an example config file to the config path and exit")
+	flags.BoolVar(&opts.check, "check", false, "start every server, print its tool count or error and exit, non-zero if any server failed")
+	flags.BoolVar(&opts.listTools, "list-tools", false, "start every server, print the tools clients are offered and exit, non-zero if any server failed")
+	flags.BoolVar(&opts.jsonOutput, "json", false, "print --list-tools as JSON instead of a table")
+	flags.Usage = func() {
+		fmt.Fprint(output, "Usage: combine-mcp [flags]\n\nCombines multiple MCP servers into one, served over stdin and stdout or HTTP.\n\nFlags:\n")
+		flags.PrintDefaults()
+	}
+
+	// Parse reports its errors itself, the checks below do the same
+	if err := flags.Parse(args); err != nil {
+		return nil, err
+	}
+	var err error
+	switch {
+	case flags.NArg() > 0:
+		err = fmt.Errorf("unexpected arguments: %v", flags.Args())
+	case opts.transport != "" && opts.transport != transportStdio && opts.transport != transportHTTP:
+		err = fmt.Errorf("invalid transport %q: expected %s or %s", opts.transport, transportStdio, transportHTTP)
+	case opts.jsonOutput && !opts.listTools:
+		err = fmt.Errorf("--json requires --list-tools")
+	case opts.port < 0 || opts.port > 65535:
+		err = fmt.Errorf("invalid port %d: expected 1 to 65535", opts.port)
+	case opts.logLevel != "":
+		_, _, err = config.ParseLogLevels(opts.logLevel)
+	}
+	if err != nil {
+		fmt.Fprintln(output, err)
+		flags.Usage()
+		return nil, err
+	}
+	return opts, nil
+}
+
+// loadConfig loads the config file given on the command line or in the environment and applies
+// the command line overrides
+func loadConfig(opts *options) (*config.Config, error) {
+	var cfg *config.Config
+	var err error
+	if opts.configPath != "" {
+		cfg, err = config.LoadConfigFile(opts.configPath)
+	} else {
+		cfg, err = config.LoadConfig("")
+	}
+	if err != nil {
+		return nil, err
+	}
+
+	if opts.logLevel != "" {
+		// Already validated by parseFlags
+		cfg.LogLevel, cfg.ServerLogLevels, _ = config.ParseLogLevels(opts.logLevel)
+	}
+	if opts.logFile != "" {
+		cfg.LogFile = opts.logFile
+	}
+	return cfg, nil
+}
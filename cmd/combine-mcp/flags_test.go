@@ -0,0 +1,110 @@
+package main
+
+import (
+	"errors"
+	"flag"
+	"io"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestParseFlags(t *testing.T) {
+	tests := []struct {
+		name    string
+		args    []string
+		want    options
+		wantErr bool
+	}{
+		{name: "No flags", args: nil, want: options{}},
+		{
+			name: "All flags",
+			args: []string{"--config", "/path/to/config.json", "--log-level", "debug", "--log-file", "/tmp/mcp.log"},
+			want: options{configPath: "/path/to/config.json", logLevel: "debug", logFile: "/tmp/mcp.log"},
+		},
+		{name: "Version", args: []string{"--version"}, want: options{showVersion: true}},
+		{name: "Check", args: []string{"--check"}, want: options{check: true}},
+		{name: "List tools", args: []string{"--list-tools", "--json"}, want: options{listTools: true, jsonOutput: true}},
+		{name: "JSON without list tools", args: []string{"--json"}, wantErr: true},
+		{
+			name: "HTTP transport",
+			args: []string{"--transport", "http", "--host", "0.0.0.0", "--port", "3000"},
+			want: options{transport: "http", host: "0.0.0.0", port: 3000},
+		},
+		{name: "Invalid transport", args: []string{"--transport", "websocket"}, wantErr: true},
+		{name: "Invalid port", args: []string{"--transport", "http", "--port", "70000"}, wantErr: true},
+		{
+			name: "Repeated config",
+			args: []string{"--config", "base.json", "--config", "local.yaml"},
+			want: options{configPath: "base.json,local.yaml"},
+		},
+		{
+			name: "Server log levels",
+			args: []string{"--log-level", "error,github=trace"},
+			want: options{logLevel: "error,github=trace"},
+		},
+		{name: "Invalid log level", args: []string{"--log-level", "verbose"}, wantErr: true},
+		{name: "Invalid server log level", args: []string{"--log-level", "info,github="}, wantErr: true},
+		{name: "Unknown flag", args: []string{"--verbose"}, wantErr: true},
+		{name: "Positional argument", args: []string{"config.json"}, wantErr: true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got, err := parseFlags(tt.args, io.Discard)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("parseFlags() error = %v, wantErr %v", err, tt.wantErr)
+			}
+			if !tt.wantErr && *got != tt.want {
+				t.Errorf("parseFlags() = %+v, want %+v", *got, tt.want)
+			}
+		})
+	}
+
+	if _, err := parseFlags([]string{"--help"}, io.Discard); !errors.Is(err, flag.ErrHelp) {
+		t.Errorf("parseFlags(--help) error = %v, want flag.ErrHelp", err)
+	}
+}
+
+func TestLoadConfigPrecedence(t *testing.T) {
+	tempDir := t.TempDir()
+	writeConfig := func(name string) string {
+		configPath := filepath.Join(tempDir, name)
+		if err := os.WriteFile(configPath, []byte(`{"mcpServers": {"`+name+`": {"command": "/path/to/server"}}}`), 0644); err != nil {
+			t.Fatalf("Failed to write config file: %v", err)
+		}
+		return configPath
+	}
+	envConfig := writeConfig("from-env")
+	flagConfig := writeConfig("from-flag")
+	t.Setenv(config.DefaultEnvVar, envConfig)
+	t.Setenv(config.LogLevelEnvVar, "error")
+	t.Setenv(config.LogToFileEnvVar, "")
+
+	cfg, err := loadConfig(&options{})
+	if err != nil {
+		t.Fatalf("loadConfig() error = %v", err)
+	}
+	if cfg.Servers[0].Name != "from-env" || cfg.LogLevel != config.LogLevelError {
+		t.Errorf("loadConfig() without flags = server %s, level %v, want the environment's", cfg.Servers[0].Name, cfg.LogLevel)
+	}
+
+	cfg, err = loadConfig(&options{configPath: flagConfig, logLevel: "trace", logFile: "/tmp/mcp.log"})
+	if err != nil {
+		t.Fatalf("loadConfig() error = %v", err)
+	}
+	if cfg.Servers[0].Name != "from-flag" || cfg.LogLevel != config.LogLevelTrace || cfg.LogFile != "/tmp/mcp.log" {
+		t.Errorf("loadConfig() with flags = server %s, level %v, file %q, want the flags'", cfg.Servers[0].Name, cfg.LogLevel, cfg.LogFile)
+	}
+}
+
+func TestHTTPAddr(t *testing.T) {
+	if addr := (&options{}).httpAddr(); addr != "127.0.0.1:8080" {
+		t.Errorf("httpAddr() = %s, want the default 127.0.0.1:8080", addr)
+	}
+	if addr := (&options{host: "::1", port: 3000}).httpAddr(); addr != "[::1]:3000" {
+		t.Errorf("httpAddr() = %s, want [::1]:3000", addr)
+	}
+}
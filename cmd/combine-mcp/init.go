@@ -0,0 +1,130 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// jsonConfigTemplate is the example config --init writes to JSON files, which can't hold comments
+const jsonConfigTemplate = `{
+  "mcpServers": {
+    "github": {
+      "command": "npx",
+      "args": ["-y", "@modelcontextprotocol/server-github"],
+      "env": {
+        "GITHUB_TOKEN": "${GITHUB_TOKEN}"
+      }
+    },
+    "filesystem": {
+      "command": "npx",
+      "args": ["-y", "@modelcontextprotocol/server-filesystem", "."],
+      "lazy": true
+    }
+  }
+}
+`
+
+// yamlConfigTemplate is the example config --init writes to YAML files
+const yamlConfigTemplate = `# Servers combined by combine-mcp. Their tools are exposed as <server>_<tool>,
+# e.g. github_search_repositories.
+mcpServers:
+  github:
+    command: npx
+    args: ["-y", "@modelcontextprotocol/server-github"]
+    env:
+      # Taken from the environment combine-mcp runs in, so the token stays out of this file
+      GITHUB_TOKEN: "${GITHUB_TOKEN}"
+  filesystem:
+    command: npx
+    args: ["-y", "@modelcontextprotocol/server-filesystem", "."]
+    # Only started once one of its tools is needed
+    lazy: true
+
+# Settings of the aggregator itself, see the README for all of them
+# toolNameSeparator: _
+# hideUnhealthyTools: true
+`
+
+// tomlConfigTemplate is the example config --init writes to TOML files
+const tomlConfigTemplate = `# Servers combined by combine-mcp. Their tools are exposed as <server>_<tool>,
+# e.g. github_search_repositories.
+
+# Settings of the aggregator itself, see the README for all of them
+# toolNameSeparator = "_"
+# hideUnhealthyTools = true
+
+[mcpServers.github]
+command = "npx"
+args = ["-y", "@modelcontextprotocol/server-github"]
+# Taken from the environment combine-mcp runs in, so the token stays out of this file
+env = { GITHUB_TOKEN = "${GITHUB_TOKEN}" }
+
+[mcpServers.filesystem]
+command = "npx"
+args = ["-y", "@modelcontextprotocol/server-filesystem", "."]
+# Only started once one of its tools is needed
+lazy = true
+`
+
+// initConfig runs the --init mode, writing an example config to the config path, and returns the exit code
+func initConfig(opts *options, out io.Writer) int {
+	configPath := opts.configPath
+	if configPath == "" {
+		configPath = os.Getenv(config.DefaultEnvVar)
+	}
+	if err := writeConfigTemplate(configPath); err != nil {
+		fmt.Fprintf(os.Stderr, "Error writing example configuration: %v\n", err)
+		return 1
+	}
+	fmt.Fprintf(out, "Wrote an example configuration to %s, edit it and start combine-mcp with it\n", configPath)
+	return 0
+}
+
+// writeConfigTemplate writes the example config in the format the path's extension asks for,
+// never replacing an existing file
+func writeConfigTemplate(configPath string) error {
+	switch {
+	case configPath == "":
+		return fmt.Errorf("no config file given: pass --config or set %s", config.DefaultEnvVar)
+	case strings.Contains(configPath, config.ConfigPathSeparator):
+		return fmt.Errorf("--init writes a single config file, got %s", configPath)
+	}
+
+	template := jsonConfigTemplate
+	switch strings.ToLower(filepath.Ext(configPath)) {
+	case ".yaml", ".yml":
+		template = yamlConfigTemplate
+	case ".toml":
+		template = tomlConfigTemplate
+	}
+
+	if err := os.MkdirAll(filepath.Dir(configPath), 0755); err != nil {
+		return fmt.Errorf("failed to create config directory: %w", err)
+	}
+	file, err := os.OpenFile(configPath, os.O_WRONLY|os.O_CREATE|os.O_EXCL, 0644)
+	if errors.Is(err, os.ErrExist) {
+		return fmt.Errorf("%s already exists, not replacing it", configPath)
+	}
+	if err != nil {
+		return err
+	}
+	if _, err := file.WriteString(template); err != nil {
+		file.Close()
+		return err
+	}
+	return file.Close()
+}
+
+// initHint suggests --init when the config failed to load because there is none yet
+func initHint(err error) string {
+	if !errors.Is(err, os.ErrNotExist) && !errors.Is(err, config.ErrConfigNotSet) {
+		return ""
+	}
+	return "Run combine-mcp --init --config <path> to write an example configuration to start from"
+}
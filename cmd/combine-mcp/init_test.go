@@ -0,0 +1,64 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestInitConfig(t *testing.T) {
+	dir := t.TempDir()
+	for _, name := range []string{"config.json", "nested/config.yaml", "config.toml"} {
+		t.Run(name, func(t *testing.T) {
+			configPath := filepath.Join(dir, name)
+			if code := initConfig(&options{configPath: configPath}, io.Discard); code != 0 {
+				t.Fatalf("initConfig() = %d, want 0", code)
+			}
+			cfg, err := config.LoadConfigFile(configPath)
+			if err != nil {
+				t.Fatalf("LoadConfigFile() of the example error = %v", err)
+			}
+			if len(cfg.Servers) != 2 {
+				t.Errorf("example config has %d servers, want 2", len(cfg.Servers))
+			}
+
+			// An existing config is never replaced
+			if err := writeConfigTemplate(configPath); err == nil || !strings.Contains(err.Error(), "already exists") {
+				t.Errorf("writeConfigTemplate() of an existing file error = %v, want it refused", err)
+			}
+		})
+	}
+
+	if err := writeConfigTemplate("base.json,local.json"); err == nil {
+		t.Errorf("writeConfigTemplate() of a list of files error = nil, want one")
+	}
+}
+
+func TestInitHint(t *testing.T) {
+	_, missing := config.LoadConfigFile(filepath.Join(t.TempDir(), "missing.json"))
+	tests := []struct {
+		name     string
+		err      error
+		wantHint bool
+	}{
+		{name: "Missing file", err: missing, wantHint: true},
+		{name: "Config not set", err: fmt.Errorf("%w: environment variable MCP_CONFIG not set", config.ErrConfigNotSet), wantHint: true},
+		{name: "Invalid config", err: errors.New("no servers defined in config"), wantHint: false},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if hint := initHint(tt.err); (hint != "") != tt.wantHint {
+				t.Errorf("initHint(%v) = %q, want a hint: %v", tt.err, hint, tt.wantHint)
+			}
+		})
+	}
+	if !errors.Is(missing, os.ErrNotExist) {
+		t.Errorf("LoadConfigFile() of a missing file error = %v, want it to wrap os.ErrNotExist", missing)
+	}
+}
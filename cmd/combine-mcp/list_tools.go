@@ -0,0 +1,77 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"text/tabwriter"
+
+	"github.com/nazar256/combine-mcp/pkg/aggregator"
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/stdio"
+)
+
+// listedTool is a tool as printed by --list-tools --json
+type listedTool struct {
+	Name        string `json:"name"`
+	Server      string `json:"server"`
+	Description string `json:"description,omitempty"`
+}
+
+// listTools runs the --list-tools mode and returns the exit code
+func listTools(opts *options) int {
+	return runOneShot(opts, func(ctx context.Context, cfg *config.Config) bool {
+		return runListTools(ctx, cfg, os.Stdout, os.Stderr, opts.jsonOutput)
+	})
+}
+
+// runListTools starts every configured server, lazy ones included, and writes the tools clients are offered,
+// under the names they are exposed with, to out as a table or as JSON. Servers that failed to start are
+// reported to errOut. It reports whether every server started.
+func runListTools(ctx context.Context, cfg *config.Config, out, errOut io.Writer, asJSON bool) bool {
+	agg := aggregator.NewMCPAggregator()
+	defer agg.Close()
+	initErr := agg.Initialize(ctx, startEagerly(cfg))
+	// Server tools hidden behind built-in tools of the same name can't be called, so they aren't listed either
+	if cfg.ToolPrecedence == config.ToolPrecedenceBuiltinFirst {
+		agg.ReserveToolNames(stdio.BuiltinToolNames(cfg))
+	}
+
+	origins := agg.ToolOrigins()
+	tools := agg.GetTools()
+	listed := make([]listedTool, 0, len(tools))
+	for _, tool := range tools {
+		listed = append(listed, listedTool{Name: tool.Name, Server: origins[tool.Name], Description: tool.Description})
+	}
+
+	if asJSON {
+		encoder := json.NewEncoder(out)
+		encoder.SetIndent("", "  ")
+		encoder.Encode(listed)
+	} else {
+		table := tabwriter.NewWriter(out, 0, 0, 2, ' ', 0)
+		fmt.Fprintln(table, "TOOL\tSERVER\tDESCRIPTION")
+		for _, tool := range listed {
+			// Only the first line of a description fits in a row
+			description, _, _ := strings.Cut(tool.Description, "\n")
+			fmt.Fprintf(table, "%s\t%s\t%s\n", tool.Name, tool.Server, description)
+		}
+		table.Flush()
+	}
+
+	ok := true
+	for _, status := range agg.Status() {
+		if !status.Running {
+			fmt.Fprintf(errOut, "Warning: server %s failed, its tools are missing: %s\n", status.Name, status.LastError)
+			ok = false
+		}
+	}
+	if initErr != nil {
+		fmt.Fprintf(errOut, "Error: %v\n", initErr)
+		ok = false
+	}
+	return ok
+}
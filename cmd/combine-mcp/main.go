@@ -2,6 +2,8 @@ package main
 
 import (
 	"context"
+	"errors"
+	"flag"
 	"fmt"
 	"io"
 	"os"
@@ -22,7 +24,29 @@ const (
 )
 
 func main() {
-	// SET UP STDOUT REDIRECTION FIRST - before anything else!
+	// Parse flags while stdout still goes to the terminal, for --version and --help
+	opts, err := parseFlags(os.Args[1:], os.Stderr)
+	if errors.Is(err, flag.ErrHelp) {
+		os.Exit(0)
+	}
+	if err != nil {
+		os.Exit(2)
+	}
+	if opts.showVersion {
+		fmt.Printf("%s %s\n", Name, Version)
+		return
+	}
+	if opts.initConfig {
+		os.Exit(initConfig(opts, os.Stdout))
+	}
+	if opts.check {
+		os.Exit(check(opts))
+	}
+	if opts.listTools {
+		os.Exit(listTools(opts))
+	}
+
+	// SET UP STDOUT REDIRECTION NEXT - before anything else!
 	// We need to capture ALL stdout output and redirect it
 
 	// Create a pipe for capturing stdout
@@ -71,28 +95,40 @@ func main() {
 	ctx, cancel := context.WithCancel(context.Background())
 	defer cancel()
 
-	// Set up signal handling
+	// Set up signal handling: SIGINT and SIGTERM stop serving, so the servers are shut down gracefully
 	sigCh := make(chan os.Signal, 1)
 	signal.Notify(sigCh, syscall.SIGINT, syscall.SIGTERM)
 	go func() {
-		<-sigCh
+		sig := <-sigCh
+		logger.Info("Received %v, shutting down", sig)
 		cancel()
 	}()
 
 	// Load configuration
-	cfg, err := config.LoadConfig("")
+	cfg, err := loadConfig(opts)
 	if err != nil {
 		fmt.Fprintf(os.Stderr, "Error loading configuration: %v\n", err)
+		if hint := initHint(err); hint != "" {
+			fmt.Fprintln(os.Stderr, hint)
+		}
 		os.Exit(1)
 	}
 
 	// Initialize the logger
-	if err := logger.Init(cfg.LogLevel, cfg.LogFile); err != nil {
+	if err := logger.Init(cfg.LogLevel, cfg.LogFile, cfg.LogFileOptions()); err != nil {
 		fmt.Fprintf(os.Stderr, "Error initializing logger: %v\n", err)
 		os.Exit(1)
 	}
+	logger.SetServerLevels(cfg.ServerLogLevels)
 	defer logger.Close()
 
+	for _, warning := range cfg.Warnings {
+		logger.Error("Warning: %s", warning)
+	}
+	for _, note := range cfg.Notes {
+		logger.Info("%s", note)
+	}
+
 	// Log startup message to file only
 	logger.Info("Starting MCP Aggregator v%s", Version)
 	logger.Debug("Configuration loaded: %d servers configured", len(cfg.Servers))
@@ -105,19 +141,63 @@ func main() {
 	if err := agg.Initialize(ctx, cfg); err != nil {
 		logger.Fatal("Error initializing aggregator: %v", err)
 	}
-	defer agg.Close()
+	defer func() {
+		logger.Info("Shutdown complete: %s", aggregator.SummarizeExits(agg.Close()))
+	}()
 
 	// Create the MCP server
-	server := stdio.NewAggregatorServer(Name, Version, agg)
+	server := stdio.NewAggregatorServer(Name, Version, agg, cfg)
 
 	// Register tools from the aggregator
 	if err := server.RegisterTools(); err != nil {
 		logger.Fatal("Error registering tools: %v", err)
 	}
 
+	// Register resources from the aggregator
+	if err := server.RegisterResources(); err != nil {
+		logger.Fatal("Error registering resources: %v", err)
+	}
+
+	// Register prompts from the aggregator
+	if err := server.RegisterPrompts(); err != nil {
+		logger.Fatal("Error registering prompts: %v", err)
+	}
+
+	// The config is reloaded from the same files on SIGHUP and by the reload tool
+	server.SetConfigLoader(func() (*config.Config, error) {
+		reloaded, err := loadConfig(opts)
+		if err != nil {
+			return nil, err
+		}
+		for _, warning := range reloaded.Warnings {
+			logger.Error("Warning: %s", warning)
+		}
+		for _, note := range reloaded.Notes {
+			logger.Info("%s", note)
+		}
+		return reloaded, nil
+	})
+
+	// Reload the config on SIGHUP, starting added servers and shutting down removed ones
+	reloadCh := make(chan os.Signal, 1)
+	signal.Notify(reloadCh, syscall.SIGHUP)
+	go func() {
+		for range reloadCh {
+			logger.Info("Received SIGHUP, reloading configuration")
+			if _, err := server.ReloadConfig(ctx); err != nil {
+				logger.Error("%v", err)
+			}
+		}
+	}()
+
 	// Start the server - logging to file only
-	logger.Debug("Starting stdio server")
-	fmt.Fprintf(os.Stderr, "Server started, listening on stdin/stdout\n")
+	if opts.servesHTTP() {
+		logger.Debug("Starting HTTP server")
+		fmt.Fprintf(os.Stderr, "Server started, listening on http://%s/sse\n", opts.httpAddr())
+	} else {
+		logger.Debug("Starting stdio server")
+		fmt.Fprintf(os.Stderr, "Server started, listening on stdin/stdout\n")
+	}
 
 	// Close the writer to stop the redirection goroutine
 	// This ensures we've processed all previous stdout writes before we restore
@@ -129,8 +209,15 @@ func main() {
 	// Clean up when done
 	defer realStdout.Close()
 
+	if opts.servesHTTP() {
+		if err := server.ServeSSE(ctx, opts.httpAddr()); err != nil {
+			logger.Fatal("Error serving MCP: %v", err)
+		}
+		return
+	}
+
 	// Now serve using our clean stdout
-	if err := server.ServeStdio(); err != nil {
+	if err := server.ServeStdio(ctx); err != nil {
 		logger.Fatal("Error serving MCP: %v", err)
 	}
 }
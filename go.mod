@@ -2,7 +2,11 @@ module github.com/nazar256/combine-mcp
 
 go 1.24.1
 
-require github.com/mark3labs/mcp-go v0.17.0
+require (
+	github.com/BurntSushi/toml v1.4.0
+	github.com/mark3labs/mcp-go v0.17.0
+	gopkg.in/yaml.v3 v3.0.1
+)
 
 require (
 	github.com/google/uuid v1.6.0 // indirect
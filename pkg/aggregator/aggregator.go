@@ -2,44 +2,240 @@ package aggregator
 
 import (
 	"context"
+	"encoding/json"
+	"errors"
 	"fmt"
 	"os"
-	"os/exec"
+	"sort"
 	"strings"
 	"sync"
+	"sync/atomic"
 	"time"
 
-	"github.com/mark3labs/mcp-go/client"
 	"github.com/mark3labs/mcp-go/mcp"
 	"github.com/nazar256/combine-mcp/pkg/config"
 	"github.com/nazar256/combine-mcp/pkg/logger"
+	"github.com/nazar256/combine-mcp/pkg/redact"
 )
 
-// MCPClient is an interface that matches the methods we use from StdioMCPClient
+// MCPClient is the interface the aggregator uses to talk to a single MCP server. Servers run as
+// processes (stdioClient) or are reached over the network (SSE), and the aggregator only sees this
+// interface, so tests can stand in an in-memory client for a server that isn't started at all.
 type MCPClient interface {
 	Initialize(ctx context.Context, request mcp.InitializeRequest) (*mcp.InitializeResult, error)
 	ListTools(ctx context.Context, request mcp.ListToolsRequest) (*mcp.ListToolsResult, error)
 	CallTool(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error)
+	ListResources(ctx context.Context, request mcp.ListResourcesRequest) (*mcp.ListResourcesResult, error)
+	ReadResource(ctx context.Context, request mcp.ReadResourceRequest) (*mcp.ReadResourceResult, error)
+	ListPrompts(ctx context.Context, request mcp.ListPromptsRequest) (*mcp.ListPromptsResult, error)
+	GetPrompt(ctx context.Context, request mcp.GetPromptRequest) (*mcp.GetPromptResult, error)
+	SetLevel(ctx context.Context, request mcp.SetLevelRequest) error
 	Close() error
 }
 
 // MCPAggregator is responsible for aggregating multiple MCP servers
 type MCPAggregator struct {
-	clients map[string]MCPClient
-	tools   map[string]toolMapping
-	configs map[string]*config.ServerConfig
-	mu      sync.RWMutex
+	clients              map[string]MCPClient
+	tools                map[string]toolMapping
+	discovered           map[string][]mcp.Tool // Tools each server listed, before filtering
+	toolsCache           []mcp.Tool            // Exposed tools, nil when they have to be built again
+	resources            map[string]resourceMapping
+	prompts              map[string]promptMapping
+	configs              map[string]*config.ServerConfig
+	capabilities         map[string]mcp.ServerCapabilities
+	protocolVersions     map[string]string
+	health               map[string]*serverHealth
+	restarts             map[string]int
+	metrics              map[string]*serverMetrics
+	unavailable          map[string]bool           // Remote servers that lost their connection and are being reconnected
+	warming              map[string]bool           // Servers whose tools are hidden until their ready check passes
+	restarting           map[string]*restartWindow // Servers being restarted, whose last known tools stay listed meanwhile
+	reserved             map[string]bool           // Exposed names taken by built-in tools, whose server tools aren't listed
+	lazy                 []lazyServer              // Servers not started until they are needed, in config order
+	lazyMu               sync.Mutex
+	cfg                  *config.Config
+	pidFile              *pidFile // Where the processes of running servers are recorded, nil without one
+	toolsChanged         func()
+	progress             func(params json.RawMessage)
+	sampling             func(ctx context.Context, params json.RawMessage) (json.RawMessage, error)
+	upstreamInfo         *mcp.Implementation // The client that initialized the aggregator, nil until it does
+	upstreamCapabilities mcp.ClientCapabilities
+	done                 chan struct{}
+	closeOnce            sync.Once
+	mu                   sync.RWMutex
+}
+
+const (
+	// defaultInitTimeout bounds the handshake with a server, generous enough for NPM packages
+	defaultInitTimeout = 60 * time.Second
+	// defaultCallTimeout bounds a tool call to a server without its own timeoutMs
+	defaultCallTimeout = 30 * time.Second
+	// defaultToolNameSeparator joins a server name and a tool name into the exposed tool name
+	defaultToolNameSeparator = "_"
+	// selfTestTimeout bounds a server's startup self-test tool call
+	selfTestTimeout = 30 * time.Second
+)
+
+// spawnError reports a server process that couldn't be started at all
+type spawnError struct {
+	serverName string
+	err        error
+}
+
+// Error implements the error interface
+func (e *spawnError) Error() string {
+	return fmt.Sprintf("failed to create client for server %s: %v", e.serverName, e.err)
+}
+
+// Unwrap returns the underlying error
+func (e *spawnError) Unwrap() error {
+	return e.err
+}
+
+// tooFewToolsError reports a server that returned fewer tools than its minTools setting
+type tooFewToolsError struct {
+	serverName string
+	got        int
+	want       int
+}
+
+// Error implements the error interface
+func (e *tooFewToolsError) Error() string {
+	return fmt.Sprintf("server %s returned %d tools, expected at least %d", e.serverName, e.got, e.want)
+}
+
+// toolCollisionError reports a tool exposed under the same name as a tool of another server
+type toolCollisionError struct {
+	exposedName    string
+	serverName     string
+	toolName       string
+	existingServer string
+	existingTool   string
+}
+
+// Error implements the error interface
+func (e *toolCollisionError) Error() string {
+	return fmt.Sprintf("tool %s of server %s collides with tool %s of server %s as %s", e.toolName, e.serverName, e.existingTool, e.existingServer, e.exposedName)
+}
+
+// serverExitedError is returned for requests to a local server whose process exited, including
+// requests that were still waiting for their response when it did
+type serverExitedError struct {
+	serverName string
+}
+
+// Error implements the error interface
+func (e *serverExitedError) Error() string {
+	return fmt.Sprintf("server %s is no longer running: it closed its output", e.serverName)
+}
+
+// unavailableError is returned for calls to a remote server while its connection is being restored
+type unavailableError struct {
+	serverName string
+}
+
+func (e *unavailableError) Error() string {
+	return fmt.Sprintf("server %s is unavailable: its connection was lost and is being restored", e.serverName)
+}
+
+// protocolVersionError is returned when a server answers the handshake with a protocol version the aggregator doesn't speak
+type protocolVersionError struct {
+	serverName string
+	version    string
+}
+
+func (e *protocolVersionError) Error() string {
+	return fmt.Sprintf("server %s speaks unsupported protocol version %q, supported: %s", e.serverName, e.version, strings.Join(SupportedProtocolVersions, ", "))
+}
+
+// ToolNotFoundError is returned for calls to a tool the aggregator doesn't expose
+type ToolNotFoundError struct {
+	Tool   string
+	Reason string // Why no server exposes the tool, empty when none listed it
+}
+
+// Error implements the error interface
+func (e *ToolNotFoundError) Error() string {
+	if e.Reason == "" {
+		return fmt.Sprintf("tool %s not found: no server lists it", e.Tool)
+	}
+	return fmt.Sprintf("tool %s not found: %s", e.Tool, e.Reason)
+}
+
+// ServerError is returned for calls to a tool whose server crashed, isn't running or can't be reached
+type ServerError struct {
+	Server string
+	Tool   string
+	Err    error
+}
+
+// Error implements the error interface
+func (e *ServerError) Error() string {
+	return fmt.Sprintf("tool %s can't be called: %v", e.Tool, e.Err)
+}
+
+// Unwrap returns the underlying error
+func (e *ServerError) Unwrap() error {
+	return e.Err
+}
+
+// callTimeoutError reports a tool call the server didn't answer within its timeout
+type callTimeoutError struct {
+	serverName string
+	tool       string
+	timeout    time.Duration
+}
+
+// Error implements the error interface
+func (e *callTimeoutError) Error() string {
+	return fmt.Sprintf("server %s timed out after %v calling %s", e.serverName, e.timeout, e.tool)
 }
 
 type toolMapping struct {
 	serverName    string
 	originalName  string
 	sanitizedName string
+	tool          mcp.Tool       // Tool definition as listed by the server
+	replicas      []string       // Other servers listing the identical tool when dedupeTools is on
+	next          *atomic.Uint32 // Round-robin position among the servers providing the tool
 }
 
-// sanitizeToolName replaces dashes with underscores in a tool name to make it compatible with Cursor
+// sanitizeToolName replaces dashes with underscores in a tool name to make it compatible with Cursor,
+// along with every other character clients don't accept, see validToolName
 func sanitizeToolName(name string) string {
-	return strings.ReplaceAll(name, "-", "_")
+	return withLeadingNonDigit(sanitizeNamePart(name))
+}
+
+// sanitizeNamePart sanitizes a name like sanitizeToolName, for the part of an exposed name after the
+// server prefix, which may start with a digit
+func sanitizeNamePart(name string) string {
+	return strings.ReplaceAll(replaceInvalidChars(name), "-", "_")
+}
+
+// validToolName makes a tool name one strict clients accept: the characters they reject are replaced,
+// see replaceInvalidChars, and a name starting with a digit, which they reject as well, gets an underscore
+// in front, e.g. _1password_get
+func validToolName(name string) string {
+	return withLeadingNonDigit(replaceInvalidChars(name))
+}
+
+// replaceInvalidChars replaces anything but ASCII letters, digits, underscores and dashes in a tool name
+// with underscores, e.g. the spaces, slashes and dots some servers use
+func replaceInvalidChars(name string) string {
+	return strings.Map(func(r rune) rune {
+		if r >= 'a' && r <= 'z' || r >= 'A' && r <= 'Z' || r >= '0' && r <= '9' || r == '_' || r == '-' {
+			return r
+		}
+		return '_'
+	}, name)
+}
+
+// withLeadingNonDigit puts an underscore in front of a name starting with a digit
+func withLeadingNonDigit(name string) string {
+	if name != "" && name[0] >= '0' && name[0] <= '9' {
+		return "_" + name
+	}
+	return name
 }
 
 // normalizeToolName normalizes a tool name by replacing both dashes and underscores with underscores
@@ -51,19 +247,42 @@ func normalizeToolName(name string) string {
 // NewMCPAggregator creates a new MCPAggregator
 func NewMCPAggregator() *MCPAggregator {
 	return &MCPAggregator{
-		clients: make(map[string]MCPClient),
-		tools:   make(map[string]toolMapping),
-		configs: make(map[string]*config.ServerConfig),
+		clients:          make(map[string]MCPClient),
+		tools:            make(map[string]toolMapping),
+		discovered:       make(map[string][]mcp.Tool),
+		resources:        make(map[string]resourceMapping),
+		prompts:          make(map[string]promptMapping),
+		configs:          make(map[string]*config.ServerConfig),
+		capabilities:     make(map[string]mcp.ServerCapabilities),
+		protocolVersions: make(map[string]string),
+		health:           make(map[string]*serverHealth),
+		restarts:         make(map[string]int),
+		metrics:          make(map[string]*serverMetrics),
+		unavailable:      make(map[string]bool),
+		warming:          make(map[string]bool),
+		restarting:       make(map[string]*restartWindow),
+		cfg:              &config.Config{},
+		done:             make(chan struct{}),
 	}
 }
 
 // Initialize initializes connections to all configured MCP servers
 func (a *MCPAggregator) Initialize(ctx context.Context, cfg *config.Config) error {
 	// Initialize logger with config
-	if err := logger.Init(cfg.LogLevel, cfg.LogFile); err != nil {
+	if err := logger.Init(cfg.LogLevel, cfg.LogFile, cfg.LogFileOptions()); err != nil {
 		return fmt.Errorf("failed to initialize logger: %w", err)
 	}
 
+	a.mu.Lock()
+	a.cfg = cfg
+	a.mu.Unlock()
+
+	// Keep secrets passed to servers out of logs and error messages
+	redact.AddSensitiveMarkers(cfg.RedactKeys...)
+	for _, serverCfg := range cfg.Servers {
+		redact.RegisterEnv(serverCfg.Env)
+	}
+
 	// Override the os.Stdout during initialization to redirect it to stderr
 	// This prevents any subprocess output from corrupting our JSON stdout
 	oldStdout := os.Stdout
@@ -102,12 +321,75 @@ func (a *MCPAggregator) Initialize(ctx context.Context, cfg *config.Config) erro
 		os.Stdout = oldStdout
 	}()
 
-	for _, serverCfg := range cfg.Servers {
+	// Servers a crashed or killed aggregator left running are reaped before new ones start
+	a.mu.Lock()
+	a.pidFile = newPidFile(cfg.PidFile)
+	a.mu.Unlock()
+
+	var cache map[string]cachedTools
+	if cfg.ToolCacheFile != "" {
+		cache = loadToolCache(cfg.ToolCacheFile)
+	}
+
+	// Servers start after the servers they depend on
+	order, err := cfg.StartOrder()
+	if err != nil {
+		return err
+	}
+
+	var eager []*config.ServerConfig
+	for _, serverCfg := range order {
 		// Store server config for filtering
 		a.mu.Lock()
-		a.configs[serverCfg.Name] = &serverCfg
+		a.configs[serverCfg.Name] = serverCfg
 		a.mu.Unlock()
 
+		// Servers with cached tools are only started once one of their tools is called
+		if a.useCachedTools(cache, serverCfg) {
+			logger.Debug("Serving cached tools of server %s until it's needed", serverCfg.Name)
+			a.deferServer(serverCfg, true)
+			continue
+		}
+		if serverCfg.Lazy {
+			logger.Debug("Deferring the start of lazy server %s until it's needed", serverCfg.Name)
+			a.deferServer(serverCfg, false)
+			continue
+		}
+		eager = append(eager, serverCfg)
+	}
+
+	if err := a.startServers(ctx, eager); err != nil {
+		return err
+	}
+
+	// Explicit routes take precedence over the automatically prefixed names
+	a.applyRoutes(ctx)
+
+	// Check if we have at least one server initialized
+	if len(a.clients) == 0 && !a.hasLazyServers() {
+		return fmt.Errorf("no servers were successfully initialized")
+	}
+
+	return nil
+}
+
+// startServer starts or connects to a server, performs the handshake and activates the server
+func (a *MCPAggregator) startServer(ctx context.Context, serverCfg *config.ServerConfig) error {
+	var (
+		mcpClient *boundedClient
+		exited    <-chan struct{}
+	)
+	switch serverCfg.EffectiveTransport() {
+	case config.TransportSSE:
+		// Connect to the remote server
+		logger.Debug("Connecting to MCP server %s at %s", serverCfg.Name, serverCfg.URL)
+		sseClient, err := newSSEClient(ctx, serverCfg)
+		if err != nil {
+			logger.Error("Failed to create client for server %s: %v", serverCfg.Name, err)
+			return &spawnError{serverName: serverCfg.Name, err: err}
+		}
+		mcpClient = newBoundedClient(sseClient, serverCfg.MaxInFlight)
+	default:
 		// Convert environment variables to string array format
 		var envVars []string
 		for key, value := range serverCfg.Env {
@@ -116,81 +398,155 @@ func (a *MCPAggregator) Initialize(ctx context.Context, cfg *config.Config) erro
 
 		// Debug output to file only
 		logger.Debug("Initializing MCP server %s with command: %s %v", serverCfg.Name, serverCfg.Command, serverCfg.Args)
-		logger.Debug("Environment variables: %v", envVars)
-
-		// Create an exec.Cmd manually to control stderr redirection
-		cmd := exec.Command(serverCfg.Command, serverCfg.Args...)
-		cmd.Stderr = os.Stderr // Redirect stderr to stderr
-		cmd.Env = append(os.Environ(), envVars...)
-
-		// Create client
-		mcpClient, err := client.NewStdioMCPClient(
-			serverCfg.Command,
-			envVars,
-			serverCfg.Args...,
-		)
+		logger.Debug("Environment variables: %v", redact.Env(serverCfg.Env))
+
+		// Start the server process
+		stdioClient, err := newStdioClient(serverCfg, envVars)
 		if err != nil {
 			logger.Error("Failed to create client for server %s: %v", serverCfg.Name, err)
-			return fmt.Errorf("failed to create client for server %s: %w", serverCfg.Name, err)
+			return &spawnError{serverName: serverCfg.Name, err: err}
 		}
+		stdioClient.OnNotification(func(method string, params json.RawMessage) {
+			switch method {
+			case "notifications/tools/list_changed":
+				go a.handleServerToolsChanged(serverCfg.Name)
+			case "notifications/progress":
+				a.forwardProgress(params)
+			}
+		})
+		stdioClient.OnRequest(func(ctx context.Context, method string, params json.RawMessage) (json.RawMessage, error) {
+			return a.handleServerRequest(ctx, serverCfg.Name, method, params)
+		})
+		pid := stdioClient.PID()
+		a.pidFile.add(serverCfg.Name, pid)
+		stdioClient.onClosed = func() { a.pidFile.remove(serverCfg.Name, pid) }
+		mcpClient = newBoundedClient(stdioClient, serverCfg.MaxInFlight)
+		exited = stdioClient.Done()
+	}
 
-		// Initialize the client with longer timeout for NPM packages
-		ctxWithTimeout, cancel := context.WithTimeout(ctx, 60*time.Second)
-		defer cancel()
+	mcpClient.limitCalls(serverCfg.Name, serverCfg.MaxConcurrency)
 
-		// Initialize the client
-		initRequest := mcp.InitializeRequest{}
-		initRequest.Params.ProtocolVersion = mcp.LATEST_PROTOCOL_VERSION
-		initRequest.Params.ClientInfo = mcp.Implementation{
-			Name:    "mcp-aggregator",
-			Version: "1.0.0",
-		}
+	// Initialize the client with longer timeout for NPM packages
+	initTimeout := defaultInitTimeout
+	if serverCfg.InitTimeoutMs > 0 {
+		initTimeout = time.Duration(serverCfg.InitTimeoutMs) * time.Millisecond
+	}
+	ctxWithTimeout, cancel := context.WithTimeout(ctx, initTimeout)
 
-		logger.Debug("Sending initialize request to %s...", serverCfg.Name)
-		initResult, err := mcpClient.Initialize(ctxWithTimeout, initRequest)
-		if err != nil {
-			mcpClient.Close()
-			logger.Error("Failed to initialize server %s: %v", serverCfg.Name, err)
-
-			// Check if this is a context cancellation or deadline exceeded error
-			// We want to handle these more gracefully
-			if ctxWithTimeout.Err() != nil || strings.Contains(err.Error(), "context") {
-				logger.Error("Context error for server %s: %v", serverCfg.Name, err)
-				logger.Error("Skipping server %s", serverCfg.Name)
-				continue // Skip this server but continue with others
-			}
+	// Initialize the client
+	initRequest := mcp.InitializeRequest{}
+	initRequest.Params.ProtocolVersion = SupportedProtocolVersions[0]
+	initRequest.Params.ClientInfo, initRequest.Params.Capabilities = a.childHandshake()
 
-			// For other errors, we'll continue with other servers but log the error
-			logger.Error("Error initializing server %s: %v", serverCfg.Name, err)
-			logger.Error("Continuing with other servers...")
-			continue
+	logger.Debug("Sending initialize request to %s...", serverCfg.Name)
+	initResult, err := mcpClient.Initialize(ctxWithTimeout, initRequest)
+	cancel()
+	if err != nil {
+		mcpClient.Close()
+		logger.Error("Failed to initialize server %s: %v", serverCfg.Name, err)
+
+		// Check if this is a context cancellation or deadline exceeded error
+		// We want to handle these more gracefully
+		if errors.Is(err, context.DeadlineExceeded) {
+			logger.Error("Server %s did not respond within %v", serverCfg.Name, initTimeout)
+			logger.Error("Skipping server %s", serverCfg.Name)
+			return err
+		}
+		if ctx.Err() != nil || strings.Contains(err.Error(), "context") {
+			logger.Error("Context error for server %s: %v", serverCfg.Name, err)
+			logger.Error("Skipping server %s", serverCfg.Name)
+			return err
 		}
-		logger.Info("Server %s initialized: %s %s", serverCfg.Name, initResult.ServerInfo.Name, initResult.ServerInfo.Version)
 
-		// Store the client
-		a.mu.Lock()
-		a.clients[serverCfg.Name] = mcpClient
-		a.mu.Unlock()
+		// For other errors, we'll continue with other servers but log the error
+		logger.Error("Error initializing server %s: %v", serverCfg.Name, err)
+		return err
+	}
+	logger.Info("Server %s initialized: %s %s (protocol %s)", serverCfg.Name, initResult.ServerInfo.Name, initResult.ServerInfo.Version, initResult.ProtocolVersion)
+	if initResult.ProtocolVersion != initRequest.Params.ProtocolVersion {
+		logger.Info("Server %s negotiated protocol version %s instead of %s", serverCfg.Name, initResult.ProtocolVersion, initRequest.Params.ProtocolVersion)
+	}
+	// Leave out servers whose messages we may not understand rather than misreading them later
+	if !IsSupportedProtocolVersion(initResult.ProtocolVersion) {
+		mcpClient.Close()
+		versionErr := &protocolVersionError{serverName: serverCfg.Name, version: initResult.ProtocolVersion}
+		logger.Error("%v, skipping it", versionErr)
+		return versionErr
+	}
 
-		// Discover tools and register them with prefix
-		err = a.discoverTools(ctx, serverCfg.Name)
-		if err != nil {
-			logger.Error("Failed to discover tools for server %s: %v", serverCfg.Name, err)
-			// Continue with other servers even if tool discovery fails
-			logger.Error("Continuing with other servers...")
-			continue
+	// Store the client
+	a.mu.Lock()
+	a.clients[serverCfg.Name] = mcpClient
+	a.capabilities[serverCfg.Name] = initResult.Capabilities
+	a.protocolVersions[serverCfg.Name] = initResult.ProtocolVersion
+	delete(a.unavailable, serverCfg.Name)
+	a.mu.Unlock()
+
+	// Discover tools and register them with prefix
+	if err := a.activateServer(ctx, serverCfg.Name); err != nil {
+		return err
+	}
+
+	// Bring the server back if its process dies later, or stops answering while it runs
+	if exited != nil {
+		go a.superviseServer(serverCfg, mcpClient, exited)
+		if serverCfg.PingIntervalMs > 0 {
+			go a.keepAlive(serverCfg, mcpClient, exited)
 		}
+	} else {
+		go a.superviseConnection(serverCfg, mcpClient)
 	}
+	return nil
+}
 
-	// Check if we have at least one server initialized
-	if len(a.clients) == 0 {
-		return fmt.Errorf("no servers were successfully initialized")
+// activateServer discovers a server's tools and runs its self-test, dropping the server if it isn't usable
+func (a *MCPAggregator) activateServer(ctx context.Context, serverName string) error {
+	// Whatever the server no longer lists disappears once it is discovered again, and what it lists stays
+	// hidden until the server is ready
+	a.mu.Lock()
+	if serverConfig := a.configs[serverName]; serverConfig != nil && serverConfig.ReadyCheck != nil {
+		a.warming[serverName] = true
+		defer a.stopWarming(serverName)
+	}
+	a.removeToolsLocked(serverName)
+	a.removeResourcesLocked(serverName)
+	a.removePromptsLocked(serverName)
+	a.mu.Unlock()
+
+	// The server is treated as failed, like one that didn't initialize, and isn't supervised, so its
+	// process is stopped rather than left running unnoticed
+	if err := a.discoverTools(ctx, serverName); err != nil {
+		logger.Error("Failed to discover tools for server %s: %v", serverName, err)
+		a.removeClient(serverName)
+		return err
+	}
+
+	// Resources and prompts are optional, so a server failing to list them keeps its tools
+	if err := a.discoverResources(ctx, serverName); err != nil {
+		logger.Error("Failed to discover resources for server %s: %v", serverName, err)
+	}
+	if err := a.discoverPrompts(ctx, serverName); err != nil {
+		logger.Error("Failed to discover prompts for server %s: %v", serverName, err)
+	}
+
+	// Make sure the server can actually execute tools before exposing them
+	if err := a.runSelfTest(ctx, serverName); err != nil {
+		logger.Error("Self-test failed for server %s: %v", serverName, err)
+		a.removeClient(serverName)
+		return err
+	}
+
+	// Some servers list their tools before they can serve calls
+	if err := a.waitUntilReady(ctx, serverName); err != nil {
+		logger.Error("%v", err)
+		a.removeClient(serverName)
+		return err
 	}
 
 	return nil
 }
 
-// discoverTools discovers all tools available on a server and registers them with a prefix
+// discoverTools discovers all tools available on a server and registers them, prefixed unless disabled
 func (a *MCPAggregator) discoverTools(ctx context.Context, serverName string) error {
 	a.mu.RLock()
 	mcpClient, exists := a.clients[serverName]
@@ -209,17 +565,35 @@ func (a *MCPAggregator) discoverTools(ctx context.Context, serverName string) er
 	}
 	logger.Debug("Found %d tools for server %s", len(toolsResp.Tools), serverName)
 
-	// Create a map of allowed tools for faster lookup
-	allowedTools := make(map[string]bool)
-	if serverConfig != nil && serverConfig.Tools != nil {
-		logger.Debug("Tool filtering enabled for server %s", serverName)
-		for _, tool := range serverConfig.Tools.Allowed {
-			normalizedName := normalizeToolName(tool)
-			logger.Debug("Adding allowed tool: %s (normalized: %s)", tool, normalizedName)
-			allowedTools[normalizedName] = true
+	// Catch backends that initialize fine but never provide their tools
+	if serverConfig != nil && len(toolsResp.Tools) < serverConfig.MinTools {
+		tooFew := &tooFewToolsError{serverName: serverName, got: len(toolsResp.Tools), want: serverConfig.MinTools}
+		if serverConfig.MinToolsAction == config.MinToolsActionFail {
+			return tooFew
 		}
+		logger.Error("Warning: %v", tooFew)
+	}
+
+	// Servers starting together register their tools in start order
+	if err := waitForRegistrationTurn(ctx); err != nil {
+		return err
+	}
+	defer endRegistrationTurn(ctx)
+	return a.registerTools(serverName, serverConfig, toolsResp.Tools)
+}
+
+// registerTools registers the tools a server lists under their exposed names, as far as its filter allows
+func (a *MCPAggregator) registerTools(serverName string, serverConfig *config.ServerConfig, tools []mcp.Tool) error {
+	a.mu.Lock()
+	a.discovered[serverName] = tools
+	a.mu.Unlock()
+
+	var filter toolFilter
+	if serverConfig != nil && serverConfig.Tools != nil {
+		logger.Debug("Tool filtering enabled for server %s: allowed %v, denied %v", serverName, serverConfig.Tools.Allowed, serverConfig.Tools.Denied)
+		filter.config = serverConfig.Tools
 		// If Tools config exists but allowed list is empty, no tools should be exposed
-		if len(serverConfig.Tools.Allowed) == 0 {
+		if filter.exposesNothing() {
 			logger.Debug("Empty allowed tools list for server %s, no tools will be exposed", serverName)
 			return nil
 		}
@@ -227,25 +601,51 @@ func (a *MCPAggregator) discoverTools(ctx context.Context, serverName string) er
 		logger.Debug("No tool filtering configured for server %s", serverName)
 	}
 
-	// Register each tool with a prefix
+	// Register each tool under its exposed name
 	a.mu.Lock()
 	defer a.mu.Unlock()
 
-	sanitizedServerName := sanitizeToolName(serverName)
-	for _, tool := range toolsResp.Tools {
-		// Skip if tool filtering is enabled and tool is not in allowed list
-		if len(allowedTools) > 0 {
-			normalizedName := normalizeToolName(tool.Name)
-			if !allowedTools[normalizedName] {
-				logger.Debug("Skipping tool %s (normalized: %s) as it's not in allowed list for server %s", tool.Name, normalizedName, serverName)
+	for _, tool := range tools {
+		originalName := tool.Name
+		sanitizedName := sanitizeToolName(originalName)
+		prefixedName := a.exposedNameLocked(serverName, originalName)
+
+		// Skip if tool filtering is enabled and the tool isn't allowed or is denied
+		if !filter.allows(originalName, prefixedName) {
+			logger.Debug("Skipping tool %s as it's filtered out for server %s", originalName, serverName)
+			continue
+		}
+
+		// Expose a tool several servers list identically once, and spread its calls over them
+		if a.cfg.DedupesTools() {
+			if exposedName, duplicate := a.duplicateOfLocked(serverName, tool); duplicate {
+				logger.Debug("Tool %s of server %s is identical to %s, adding the server as its provider", originalName, serverName, exposedName)
+				a.addReplicaLocked(exposedName, serverName)
 				continue
 			}
-			logger.Debug("Including allowed tool %s (normalized: %s) for server %s", tool.Name, normalizedName, serverName)
 		}
 
-		originalName := tool.Name
-		sanitizedName := sanitizeToolName(originalName)
-		prefixedName := fmt.Sprintf("%s_%s", sanitizedServerName, sanitizedName)
+		// Never let one server's tool shadow another's, e.g. server a_b's tool c and server a's tool b_c
+		if existing, exists := a.tools[prefixedName]; exists && existing.serverName != serverName {
+			collision := &toolCollisionError{
+				exposedName:    prefixedName,
+				serverName:     serverName,
+				toolName:       originalName,
+				existingServer: existing.serverName,
+				existingTool:   existing.originalName,
+			}
+			if a.cfg.OnToolCollision == config.ToolCollisionFail {
+				a.removeToolsLocked(serverName)
+				return collision
+			}
+			logger.Error("%v, skipping it", collision)
+			continue
+		}
+
+		// Tools of the server whose names differ only in replaced characters, e.g. a.b and a/b, get a counter
+		if existing, exists := a.tools[prefixedName]; exists && existing.originalName != originalName {
+			prefixedName = a.uniqueNameLocked(prefixedName, serverName, originalName)
+		}
 
 		logger.Debug("Registering tool: %s -> %s (sanitized from: %s)", originalName, prefixedName, tool.Name)
 
@@ -253,52 +653,229 @@ func (a *MCPAggregator) discoverTools(ctx context.Context, serverName string) er
 			serverName:    serverName,
 			originalName:  originalName,
 			sanitizedName: sanitizedName,
+			tool:          tool,
+		}
+	}
+	a.toolsCache = nil
+
+	return nil
+}
+
+// applyRoutes registers the configured routing overrides, replacing automatically derived routes
+func (a *MCPAggregator) applyRoutes(ctx context.Context) {
+	a.mu.RLock()
+	routes := a.cfg.Routes
+	a.mu.RUnlock()
+
+	for exposedName, route := range routes {
+		if err := a.applyRoute(ctx, exposedName, route); err != nil {
+			logger.Error("Ignoring route %s: %v", exposedName, err)
+		}
+	}
+}
+
+// applyRoute points an exposed tool name at a tool of a server after checking the tool exists
+func (a *MCPAggregator) applyRoute(ctx context.Context, exposedName string, route config.RouteConfig) error {
+	a.mu.RLock()
+	mcpClient, exists := a.clients[route.Server]
+	a.mu.RUnlock()
+
+	if !exists {
+		return fmt.Errorf("server %s is not available", route.Server)
+	}
+
+	toolsResp, err := mcpClient.ListTools(ctx, mcp.ListToolsRequest{})
+	if err != nil {
+		return fmt.Errorf("failed to list tools for server %s: %w", route.Server, err)
+	}
+	var target *mcp.Tool
+	for i := range toolsResp.Tools {
+		if toolsResp.Tools[i].Name == route.Tool {
+			target = &toolsResp.Tools[i]
+			break
 		}
 	}
+	if target == nil {
+		return fmt.Errorf("server %s has no tool %s", route.Server, route.Tool)
+	}
 
+	logger.Debug("Routing tool %s to %s on server %s", exposedName, route.Tool, route.Server)
+
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.tools[exposedName] = toolMapping{
+		serverName:    route.Server,
+		originalName:  route.Tool,
+		sanitizedName: sanitizeToolName(route.Tool),
+		tool:          *target,
+	}
+	a.toolsCache = nil
 	return nil
 }
 
-// GetTools returns a list of all tools from all servers with prefixed names
+// prefixedNameLocked joins a server name and one of its tool or prompt names into the exposed name
+func (a *MCPAggregator) prefixedNameLocked(serverName, name string) string {
+	separator := a.cfg.ToolNameSeparator
+	if separator == "" {
+		separator = defaultToolNameSeparator
+	}
+	return sanitizeToolName(serverName) + separator + name
+}
+
+// uniqueNameLocked returns the exposed name with the lowest counter appended that no other tool took yet,
+// or that the tool of the server already has
+func (a *MCPAggregator) uniqueNameLocked(name, serverName, toolName string) string {
+	for i := 2; ; i++ {
+		candidate := fmt.Sprintf("%s_%d", name, i)
+		existing, exists := a.tools[candidate]
+		if !exists || existing.serverName == serverName && existing.originalName == toolName {
+			return candidate
+		}
+	}
+}
+
+// exposedNameLocked returns the name a tool of a server is exposed under, unless a route or another server takes it
+func (a *MCPAggregator) exposedNameLocked(serverName, toolName string) string {
+	if !a.cfg.PrefixesToolNames() {
+		return validToolName(toolName)
+	}
+	return a.prefixedNameLocked(serverName, sanitizeNamePart(toolName))
+}
+
+// missingToolError explains why no tool is exposed under a name: the server listing it is down,
+// its tools settings filter it out, another server's tool took the name, or no server lists it
+func (a *MCPAggregator) missingToolError(name string) error {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	for _, serverCfg := range a.cfg.Servers {
+		serverName := serverCfg.Name
+		_, running := a.clients[serverName]
+		for _, tool := range a.discovered[serverName] {
+			if a.exposedNameLocked(serverName, tool.Name) != name {
+				continue
+			}
+			if !running {
+				return &ServerError{Server: serverName, Tool: name, Err: fmt.Errorf("server %s is no longer running", serverName)}
+			}
+			if exposedName, deduped := a.dedupedIntoLocked(serverName, tool.Name); deduped {
+				return &ToolNotFoundError{Tool: name, Reason: fmt.Sprintf("it is identical to tool %s, which is exposed instead", exposedName)}
+			}
+			if serverCfg.Tools != nil {
+				return &ToolNotFoundError{Tool: name, Reason: fmt.Sprintf("it is filtered out by the tools settings of server %s", serverName)}
+			}
+			return &ToolNotFoundError{Tool: name, Reason: fmt.Sprintf("another server's tool is exposed under this name instead of the one of server %s", serverName)}
+		}
+
+		// A server that failed before listing its tools can still be recognized by its prefix
+		if !running && a.cfg.PrefixesToolNames() && strings.HasPrefix(name, a.prefixedNameLocked(serverName, "")) {
+			return &ServerError{Server: serverName, Tool: name, Err: fmt.Errorf("server %s is not running", serverName)}
+		}
+	}
+	return &ToolNotFoundError{Tool: name}
+}
+
+// GetTools returns a list of all tools from all servers with prefixed names, sorted by name.
+// The list is cached until the tools or the health of a server change.
 func (a *MCPAggregator) GetTools() []mcp.Tool {
+	a.mu.RLock()
+	cached := a.toolsCache
+	a.mu.RUnlock()
+
+	if cached == nil {
+		a.mu.Lock()
+		if a.toolsCache == nil {
+			a.toolsCache = a.buildToolsLocked()
+		}
+		cached = a.toolsCache
+		a.mu.Unlock()
+	}
+
+	return append([]mcp.Tool(nil), cached...)
+}
+
+// ReserveToolNames hands the exposed names over to built-in tools: server tools and routes under any of them are
+// left out of the tool list, their origins and their tags, as the built-in tools take precedence
+func (a *MCPAggregator) ReserveToolNames(names []string) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+
+	a.reserved = make(map[string]bool, len(names))
+	for _, name := range names {
+		a.reserved[name] = true
+		if mapping, exists := a.tools[name]; exists {
+			logger.Error("Warning: built-in tool %s shadows the tool %s of server %s", name, mapping.originalName, mapping.serverName)
+		}
+	}
+	a.toolsCache = nil
+}
+
+// ToolOrigins returns the server each exposed tool comes from, by exposed name
+func (a *MCPAggregator) ToolOrigins() map[string]string {
 	a.mu.RLock()
 	defer a.mu.RUnlock()
 
-	// Get tools from all servers
-	var allTools []mcp.Tool
-	for prefixedName, mapping := range a.tools {
-		mcpClient := a.clients[mapping.serverName]
+	origins := make(map[string]string, len(a.tools))
+	for exposedName, mapping := range a.listedToolsLocked() {
+		origins[exposedName] = mapping.serverName
+	}
+	return origins
+}
 
-		// Get the original tool schema using ListTools
-		toolsResp, err := mcpClient.ListTools(context.Background(), mcp.ListToolsRequest{})
-		if err != nil {
-			// Skip tools that can't be retrieved
-			logger.Error("Error getting tools for %s: %v", mapping.serverName, err)
+// TaggedTools returns the exposed names of the tools provided by a server carrying any of the tags
+func (a *MCPAggregator) TaggedTools(tags []string) map[string]bool {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	wanted := make(map[string]bool, len(tags))
+	for _, tag := range tags {
+		wanted[tag] = true
+	}
+	tagged := make(map[string]bool)
+	for exposedName, mapping := range a.tools {
+		if a.reserved[exposedName] {
 			continue
 		}
-
-		// Find the specific tool
-		var tool mcp.Tool
-		found := false
-		for _, t := range toolsResp.Tools {
-			if t.Name == mapping.originalName {
-				tool = t
-				found = true
+		for _, serverName := range toolProviders(mapping) {
+			if serverCfg := a.configs[serverName]; serverCfg != nil && serverCfg.HasAnyTag(wanted) {
+				tagged[exposedName] = true
 				break
 			}
 		}
+	}
+	return tagged
+}
 
-		if !found {
-			logger.Debug("Tool %s not found in server %s", mapping.originalName, mapping.serverName)
+// buildToolsLocked builds the list of exposed tools from the discovered ones
+func (a *MCPAggregator) buildToolsLocked() []mcp.Tool {
+	// Get tools from all servers
+	allTools := make([]mcp.Tool, 0, len(a.tools))
+	for prefixedName, mapping := range a.listedToolsLocked() {
+		// Hide tools of degraded servers so the model doesn't attempt calls that will fail
+		if a.cfg.HideUnhealthyTools && !a.anyProviderHealthyLocked(mapping) {
+			continue
+		}
+		// Hide tools of servers that aren't ready to serve calls yet
+		if !a.anyProviderReadyLocked(mapping) {
 			continue
 		}
 
+		// Use the tool definition captured at discovery instead of asking the server again
+		tool := mapping.tool
+
 		// Create a new tool with the prefixed name (with underscores instead of dashes)
 		tool.Name = prefixedName
 
 		// Update the description to indicate the source server
-		if tool.Description != "" {
-			tool.Description = fmt.Sprintf("[%s] %s", mapping.serverName, tool.Description)
+		override, overridden := a.cfg.ToolOverrides[prefixedName]
+		if tool.Description != "" && override.Description == "" {
+			template := a.cfg.DescriptionTemplateFor(a.configs[mapping.serverName])
+			tool.Description = config.DescribeTool(template, mapping.serverName, mapping.originalName, tool.Description)
+		}
+		if overridden {
+			if err := applyToolOverride(&tool, override); err != nil {
+				logger.Error("Warning: %v", err)
+			}
 		}
 
 		// Ensure the tool has a valid input schema for Cursor
@@ -307,9 +884,54 @@ func (a *MCPAggregator) GetTools() []mcp.Tool {
 		allTools = append(allTools, tool)
 	}
 
+	sort.Slice(allTools, func(i, j int) bool { return allTools[i].Name < allTools[j].Name })
 	return allTools
 }
 
+// handleServerToolsChanged refreshes the tools of a server that reported its tool list changed
+func (a *MCPAggregator) handleServerToolsChanged(serverName string) {
+	ctx, cancel := context.WithTimeout(context.Background(), defaultCallTimeout)
+	defer cancel()
+	if err := a.refreshServerTools(ctx, serverName); err != nil {
+		logger.Error("%v", err)
+	}
+}
+
+// RefreshTools discovers the tools of every server again, e.g. after they changed without notice
+func (a *MCPAggregator) RefreshTools(ctx context.Context) error {
+	a.mu.RLock()
+	serverNames := make([]string, 0, len(a.clients))
+	for name := range a.clients {
+		serverNames = append(serverNames, name)
+	}
+	a.mu.RUnlock()
+
+	var errs []error
+	for _, name := range serverNames {
+		if err := a.refreshServerTools(ctx, name); err != nil {
+			errs = append(errs, err)
+		}
+	}
+	return errors.Join(errs...)
+}
+
+// refreshServerTools replaces the tools of a server with the ones it lists now
+func (a *MCPAggregator) refreshServerTools(ctx context.Context, serverName string) error {
+	logger.Debug("Refreshing tools of server %s", serverName)
+
+	a.mu.Lock()
+	a.removeToolsLocked(serverName)
+	a.mu.Unlock()
+
+	err := a.discoverTools(ctx, serverName)
+	a.applyRoutes(ctx)
+	a.notifyToolsChanged()
+	if err != nil {
+		return fmt.Errorf("failed to refresh tools of server %s: %w", serverName, err)
+	}
+	return nil
+}
+
 // ensureValidToolSchema ensures the tool's input schema is in a format Cursor expects
 func ensureValidToolSchema(tool *mcp.Tool) {
 	// Ensure the input schema has required fields
@@ -325,37 +947,231 @@ func ensureValidToolSchema(tool *mcp.Tool) {
 
 // CallTool calls a tool on the appropriate server
 func (a *MCPAggregator) CallTool(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
-	a.mu.RLock()
 	prefixedName := request.Params.Name
+	if err := a.waitForRestart(ctx, prefixedName); err != nil {
+		return nil, err
+	}
+
+	a.mu.RLock()
 	mapping, exists := a.tools[prefixedName]
+	clientExists := a.anyProviderRunningLocked(mapping)
+	a.mu.RUnlock()
+
+	// The tool may belong to a lazy server that isn't started yet, or be a cached tool of one
+	if !exists && a.startLazyServersFor(ctx, prefixedName) {
+		a.notifyToolsChanged()
+	} else if exists && !clientExists && a.startLazyServer(ctx, mapping.serverName) {
+		a.notifyToolsChanged()
+	}
+
+	a.mu.RLock()
+	mapping, exists = a.tools[prefixedName]
+	mapping.serverName = a.pickProviderLocked(mapping)
 	mcpClient, clientExists := a.clients[mapping.serverName]
+	serverConfig := a.configs[mapping.serverName]
+	unavailable := a.unavailable[mapping.serverName]
+	argumentDefaults := a.cfg.ToolOverrides[prefixedName].ArgumentDefaults
 	a.mu.RUnlock()
 
 	if !exists {
-		return nil, fmt.Errorf("tool %s not found", prefixedName)
+		return nil, a.missingToolError(prefixedName)
 	}
 
 	if !clientExists {
-		return nil, fmt.Errorf("client for server %s not found", mapping.serverName)
+		return nil, &ServerError{Server: mapping.serverName, Tool: prefixedName, Err: fmt.Errorf("server %s is not running", mapping.serverName)}
+	}
+
+	// Don't wait on a connection that is known to be gone
+	if unavailable {
+		return nil, &ServerError{Server: mapping.serverName, Tool: prefixedName, Err: &unavailableError{serverName: mapping.serverName}}
 	}
 
-	logger.Debug("Calling tool %s on server %s (mapped from %s)", mapping.originalName, mapping.serverName, prefixedName)
+	// Tie the messages exchanged with the server to the upstream call in the trace log
+	correlationID := CorrelationID(ctx)
+	if correlationID == "" {
+		ctx, correlationID = WithCorrelationID(ctx)
+	}
+	logger.Debug("Calling tool %s on server %s (mapped from %s, correlation id %s)", mapping.originalName, mapping.serverName, prefixedName, correlationID)
 
 	// Create a new request with the original tool name (without prefix and with original dashes)
 	newRequest := request
 	newRequest.Params.Name = mapping.originalName
+	newRequest.Params.Arguments = withArgumentDefaults(request.Params.Arguments, argumentDefaults)
+
+	// Call the tool on the appropriate server, never waiting on a slow server forever
+	callTimeout := defaultCallTimeout
+	if serverConfig != nil && serverConfig.TimeoutMs > 0 {
+		callTimeout = time.Duration(serverConfig.TimeoutMs) * time.Millisecond
+	}
+	callCtx, cancel := context.WithTimeout(ctx, callTimeout)
+	defer cancel()
+
+	start := time.Now()
+	result, err := mcpClient.CallTool(callCtx, newRequest)
+	latency := time.Since(start)
+	a.metricsFor(mapping.serverName).record(latency, err != nil || (result != nil && result.IsError))
+	result = withCallMeta(result, mapping.serverName, latency)
+	if err != nil && ctx.Err() == nil && errors.Is(callCtx.Err(), context.DeadlineExceeded) {
+		err = &callTimeoutError{serverName: mapping.serverName, tool: mapping.originalName, timeout: callTimeout}
+	}
+	a.recordCallResult(mapping.serverName, err)
+
+	// Keep oversized results from flooding the client's context
+	limit := a.cfg.MaxResponseBytesFor(serverConfig)
+	if truncated := truncateResult(result, limit); truncated != result {
+		logger.Info("Truncated the result of tool %s on server %s to %d bytes", mapping.originalName, mapping.serverName, limit)
+		result = truncated
+	}
+
+	// Tell a server that went away during the call apart from a tool that failed
+	var exited *serverExitedError
+	var lost *unavailableError
+	if errors.As(err, &exited) || errors.As(err, &lost) {
+		err = &ServerError{Server: mapping.serverName, Tool: prefixedName, Err: err}
+	}
+	return result, err
+}
+
+// SetLogLevel forwards a logging/setLevel request to every server that supports logging
+func (a *MCPAggregator) SetLogLevel(ctx context.Context, level mcp.LoggingLevel) {
+	a.mu.RLock()
+	targets := make(map[string]MCPClient)
+	for name, mcpClient := range a.clients {
+		if a.capabilities[name].Logging != nil {
+			targets[name] = mcpClient
+		}
+	}
+	a.mu.RUnlock()
+
+	for name, mcpClient := range targets {
+		request := mcp.SetLevelRequest{}
+		request.Params.Level = level
+		if err := mcpClient.SetLevel(ctx, request); err != nil {
+			logger.Error("Failed to set log level on server %s: %v", name, err)
+			continue
+		}
+		logger.Debug("Set log level %s on server %s", level, name)
+	}
+}
+
+// runSelfTest invokes the server's configured self-test tool call, if any, and requires it to succeed
+func (a *MCPAggregator) runSelfTest(ctx context.Context, serverName string) error {
+	a.mu.RLock()
+	mcpClient, exists := a.clients[serverName]
+	serverConfig := a.configs[serverName]
+	a.mu.RUnlock()
+
+	if serverConfig == nil || serverConfig.SelfTest == nil {
+		return nil
+	}
+	if !exists {
+		return fmt.Errorf("client for server %s not found", serverName)
+	}
 
-	// Call the tool on the appropriate server
-	return mcpClient.CallTool(ctx, newRequest)
+	ctx, cancel := context.WithTimeout(ctx, selfTestTimeout)
+	defer cancel()
+
+	request := mcp.CallToolRequest{}
+	request.Params.Name = serverConfig.SelfTest.Tool
+	request.Params.Arguments = serverConfig.SelfTest.Arguments
+
+	logger.Debug("Running self-test %s on server %s...", serverConfig.SelfTest.Tool, serverName)
+	result, err := mcpClient.CallTool(ctx, request)
+	if err != nil {
+		return fmt.Errorf("self-test tool %s failed: %w", serverConfig.SelfTest.Tool, err)
+	}
+	if result.IsError {
+		return fmt.Errorf("self-test tool %s returned an error result", serverConfig.SelfTest.Tool)
+	}
+
+	logger.Info("Self-test %s passed for server %s", serverConfig.SelfTest.Tool, serverName)
+	return nil
+}
+
+// ProtocolVersion returns the protocol version negotiated with a server, empty if it isn't connected
+func (a *MCPAggregator) ProtocolVersion(serverName string) string {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+	return a.protocolVersions[serverName]
 }
 
-// Close closes all client connections
-func (a *MCPAggregator) Close() {
+// removeClient closes and forgets a server's client along with its tools
+func (a *MCPAggregator) removeClient(serverName string) {
 	a.mu.Lock()
-	defer a.mu.Unlock()
+	mcpClient, exists := a.clients[serverName]
+	delete(a.clients, serverName)
+	delete(a.capabilities, serverName)
+	delete(a.protocolVersions, serverName)
+	delete(a.unavailable, serverName)
+	a.removeToolsLocked(serverName)
+	a.removeResourcesLocked(serverName)
+	a.removePromptsLocked(serverName)
+	a.mu.Unlock()
 
-	for name, mcpClient := range a.clients {
+	if exists {
 		mcpClient.Close()
-		delete(a.clients, name)
 	}
 }
+
+// removeToolsLocked forgets every tool routed to a server, except deduplicated ones other servers provide too
+func (a *MCPAggregator) removeToolsLocked(serverName string) {
+	for prefixedName, mapping := range a.tools {
+		if len(mapping.replicas) > 0 {
+			a.removeReplicatedLocked(prefixedName, serverName)
+		} else if mapping.serverName == serverName {
+			delete(a.tools, prefixedName)
+		}
+	}
+	a.toolsCache = nil
+}
+
+// PendingRequests returns the number of requests currently in flight to each server
+func (a *MCPAggregator) PendingRequests() map[string]int {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	pending := make(map[string]int, len(a.clients))
+	for name, mcpClient := range a.clients {
+		if bounded, ok := mcpClient.(*boundedClient); ok {
+			pending[name] = bounded.Pending()
+		}
+	}
+	return pending
+}
+
+// Close closes all client connections and reports how each server stopped, ordered by server name
+func (a *MCPAggregator) Close() []ServerExit {
+	a.closeOnce.Do(func() {
+		close(a.done)
+		if err := a.saveToolCache(); err != nil {
+			logger.Error("Failed to save tool cache: %v", err)
+		}
+	})
+
+	a.mu.Lock()
+	clients := a.clients
+	a.clients = make(map[string]MCPClient)
+	pids := a.pidFile
+	a.mu.Unlock()
+
+	// Servers may take a while to exit, so shut them all down at once
+	exits := make([]ServerExit, 0, len(clients))
+	var exitsMu sync.Mutex
+	var wg sync.WaitGroup
+	for name, mcpClient := range clients {
+		wg.Add(1)
+		go func(name string, mcpClient MCPClient) {
+			defer wg.Done()
+			exit := clientExit(name, mcpClient, mcpClient.Close())
+			logger.Info("Server %s", exit)
+			exitsMu.Lock()
+			exits = append(exits, exit)
+			exitsMu.Unlock()
+		}(name, mcpClient)
+	}
+	wg.Wait()
+	pids.close()
+
+	sortExits(exits)
+	return exits
+}
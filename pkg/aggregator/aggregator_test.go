@@ -2,12 +2,27 @@ package aggregator
 
 import (
 	"context"
+	"errors"
+	"os"
+	"reflect"
+	"strings"
+	"sync/atomic"
 	"testing"
+	"time"
 
 	"github.com/mark3labs/mcp-go/mcp"
 	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
 )
 
+func TestMain(m *testing.M) {
+	// Errors are always logged, so the logger has to be set up before the aggregator is used
+	if err := logger.Init(config.LogLevelError, "", config.LogFileOptions{}); err != nil {
+		panic(err)
+	}
+	os.Exit(m.Run())
+}
+
 func TestSanitizeToolName(t *testing.T) {
 	tests := []struct {
 		name     string
@@ -39,6 +54,31 @@ func TestSanitizeToolName(t *testing.T) {
 			input:    "get_user-details",
 			expected: "get_user_details",
 		},
+		{
+			name:     "Spaces",
+			input:    "get user details",
+			expected: "get_user_details",
+		},
+		{
+			name:     "Slashes and dots",
+			input:    "repos/get.user",
+			expected: "repos_get_user",
+		},
+		{
+			name:     "Unicode",
+			input:    "získat-uživatele",
+			expected: "z_skat_u_ivatele",
+		},
+		{
+			name:     "Leading digit",
+			input:    "2fa-verify",
+			expected: "_2fa_verify",
+		},
+		{
+			name:     "Digit after the start",
+			input:    "get-2fa",
+			expected: "get_2fa",
+		},
 	}
 
 	for _, tt := range tests {
@@ -53,7 +93,9 @@ func TestSanitizeToolName(t *testing.T) {
 
 // MockClient implements a simple mock for testing without real StdioMCPClient
 type MockClient struct {
-	Tools []mcp.Tool
+	Tools     []mcp.Tool
+	Resources []mcp.Resource
+	Level     mcp.LoggingLevel
 }
 
 func (m *MockClient) Initialize(ctx context.Context, request mcp.InitializeRequest) (*mcp.InitializeResult, error) {
@@ -76,6 +118,33 @@ func (m *MockClient) CallTool(ctx context.Context, request mcp.CallToolRequest)
 	return &mcp.CallToolResult{}, nil
 }
 
+func (m *MockClient) ListResources(ctx context.Context, request mcp.ListResourcesRequest) (*mcp.ListResourcesResult, error) {
+	return &mcp.ListResourcesResult{
+		Resources: m.Resources,
+	}, nil
+}
+
+func (m *MockClient) ReadResource(ctx context.Context, request mcp.ReadResourceRequest) (*mcp.ReadResourceResult, error) {
+	return &mcp.ReadResourceResult{
+		Contents: []mcp.ResourceContents{
+			mcp.TextResourceContents{URI: request.Params.URI, Text: "contents of " + request.Params.URI},
+		},
+	}, nil
+}
+
+func (m *MockClient) ListPrompts(ctx context.Context, request mcp.ListPromptsRequest) (*mcp.ListPromptsResult, error) {
+	return &mcp.ListPromptsResult{}, nil
+}
+
+func (m *MockClient) GetPrompt(ctx context.Context, request mcp.GetPromptRequest) (*mcp.GetPromptResult, error) {
+	return &mcp.GetPromptResult{}, nil
+}
+
+func (m *MockClient) SetLevel(ctx context.Context, request mcp.SetLevelRequest) error {
+	m.Level = request.Params.Level
+	return nil
+}
+
 func (m *MockClient) Close() error {
 	return nil
 }
@@ -108,6 +177,50 @@ func TestToolNameSanitization(t *testing.T) {
 	}
 }
 
+func TestSanitizedToolNamesStayUnique(t *testing.T) {
+	tests := []struct {
+		name       string
+		prefix     bool
+		wantRoutes map[string]string // exposed name -> tool it calls
+	}{
+		{
+			name:       "Prefixed",
+			prefix:     true,
+			wantRoutes: map[string]string{"files_read_file": "read.file", "files_read_file_2": "read/file", "files_read_file_3": "read file", "files_list": "list", "files_2fa": "2fa"},
+		},
+		{
+			name:       "Unprefixed",
+			prefix:     false,
+			wantRoutes: map[string]string{"read_file": "read.file", "read_file_2": "read/file", "read_file_3": "read file", "list": "list", "_2fa": "2fa"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			agg := NewMCPAggregator()
+			agg.cfg = &config.Config{PrefixTools: &tt.prefix}
+			client := &recordingClient{MockClient: MockClient{Tools: []mcp.Tool{{Name: "read.file"}, {Name: "read/file"}, {Name: "read file"}, {Name: "list"}, {Name: "2fa"}}}}
+			agg.clients["files"] = client
+			agg.configs["files"] = &config.ServerConfig{Name: "files", Command: "test-command"}
+			for i := 0; i < 2; i++ {
+				// Discovering the same tools again keeps their names
+				if err := agg.discoverTools(context.Background(), "files"); err != nil {
+					t.Fatalf("discoverTools() error = %v", err)
+				}
+			}
+
+			if len(agg.GetTools()) != len(tt.wantRoutes) {
+				t.Errorf("GetTools() = %v, want %d tools", agg.GetTools(), len(tt.wantRoutes))
+			}
+			for exposedName, toolName := range tt.wantRoutes {
+				if _, err := callEcho(agg, exposedName, "hello"); err != nil || client.called != toolName {
+					t.Errorf("Calling %s called %q, error %v, want %s", exposedName, client.called, err, toolName)
+				}
+			}
+		})
+	}
+}
+
 func TestToolFiltering(t *testing.T) {
 	tests := []struct {
 		name          string
@@ -172,6 +285,53 @@ func TestToolFiltering(t *testing.T) {
 			},
 			wantToolNames: []string{"test_server_tool1"},
 		},
+		{
+			name: "Glob patterns select tools",
+			serverConfig: config.ServerConfig{
+				Name:    "test-server",
+				Command: "test-command",
+				Tools: &config.ToolsConfig{
+					Allowed: []string{"get-*"},
+				},
+			},
+			serverTools: []mcp.Tool{
+				{Name: "get-user", Description: "Get user"},
+				{Name: "get_issue", Description: "Get issue"},
+				{Name: "create-issue", Description: "Create issue"},
+			},
+			wantToolNames: []string{"test_server_get_user", "test_server_get_issue"},
+		},
+		{
+			name: "Denied tools are removed from allowed ones",
+			serverConfig: config.ServerConfig{
+				Name:    "test-server",
+				Command: "test-command",
+				Tools: &config.ToolsConfig{
+					Allowed: []string{"test_server_*"},
+					Denied:  []string{"*delete*"},
+				},
+			},
+			serverTools: []mcp.Tool{
+				{Name: "get-user", Description: "Get user"},
+				{Name: "delete-user", Description: "Delete user"},
+			},
+			wantToolNames: []string{"test_server_get_user"},
+		},
+		{
+			name: "Denied list alone keeps the other tools",
+			serverConfig: config.ServerConfig{
+				Name:    "test-server",
+				Command: "test-command",
+				Tools: &config.ToolsConfig{
+					Denied: []string{"tool2"},
+				},
+			},
+			serverTools: []mcp.Tool{
+				{Name: "tool1", Description: "Tool 1"},
+				{Name: "tool2", Description: "Tool 2"},
+			},
+			wantToolNames: []string{"test_server_tool1"},
+		},
 	}
 
 	for _, tt := range tests {
@@ -231,3 +391,686 @@ func TestToolFiltering(t *testing.T) {
 		})
 	}
 }
+
+func TestSetLogLevelPropagation(t *testing.T) {
+	withLogging := &MockClient{}
+	withoutLogging := &MockClient{}
+
+	agg := NewMCPAggregator()
+	agg.clients["logging"] = withLogging
+	agg.clients["silent"] = withoutLogging
+	agg.capabilities["logging"] = mcp.ServerCapabilities{Logging: &struct{}{}}
+	agg.capabilities["silent"] = mcp.ServerCapabilities{}
+
+	agg.SetLogLevel(context.Background(), mcp.LoggingLevelDebug)
+
+	if withLogging.Level != mcp.LoggingLevelDebug {
+		t.Errorf("Server with logging capability got level %q, want %q", withLogging.Level, mcp.LoggingLevelDebug)
+	}
+	if withoutLogging.Level != "" {
+		t.Errorf("Server without logging capability got level %q, want none", withoutLogging.Level)
+	}
+}
+
+func TestMinTools(t *testing.T) {
+	tests := []struct {
+		name    string
+		action  string
+		wantErr bool
+	}{
+		{name: "Warn keeps the server", action: "", wantErr: false},
+		{name: "Fail flags the server", action: config.MinToolsActionFail, wantErr: true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			serverConfig := config.ServerConfig{
+				Name:           "empty",
+				Command:        "empty-command",
+				MinTools:       1,
+				MinToolsAction: tt.action,
+			}
+
+			agg := NewMCPAggregator()
+			agg.clients[serverConfig.Name] = &MockClient{}
+			agg.configs[serverConfig.Name] = &serverConfig
+
+			err := agg.discoverTools(context.Background(), serverConfig.Name)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("discoverTools() error = %v, wantErr %v", err, tt.wantErr)
+			}
+			if tt.wantErr {
+				var tooFew *tooFewToolsError
+				if !errors.As(err, &tooFew) || tooFew.got != 0 || tooFew.want != 1 {
+					t.Errorf("discoverTools() error = %v, want a too few tools error", err)
+				}
+			}
+		})
+	}
+}
+
+// recordingClient records the last tool called and answers with an error result when failing is set
+type recordingClient struct {
+	MockClient
+	failing   bool
+	called    string
+	arguments map[string]interface{}
+}
+
+func (c *recordingClient) CallTool(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	c.called = request.Params.Name
+	c.arguments = request.Params.Arguments
+	return &mcp.CallToolResult{IsError: c.failing}, nil
+}
+
+func TestSelfTest(t *testing.T) {
+	tests := []struct {
+		name      string
+		failing   bool
+		wantReady bool
+	}{
+		{name: "Passing self-test marks the server ready", failing: false, wantReady: true},
+		{name: "Failing self-test drops the server", failing: true, wantReady: false},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			serverConfig := config.ServerConfig{
+				Name:     "backend",
+				Command:  "backend-command",
+				SelfTest: &config.SelfTestConfig{Tool: "whoami"},
+			}
+			mockClient := &recordingClient{
+				MockClient: MockClient{Tools: []mcp.Tool{{Name: "whoami"}}},
+				failing:    tt.failing,
+			}
+
+			agg := NewMCPAggregator()
+			agg.clients[serverConfig.Name] = mockClient
+			agg.configs[serverConfig.Name] = &serverConfig
+
+			err := agg.activateServer(context.Background(), serverConfig.Name)
+			if (err == nil) != tt.wantReady {
+				t.Fatalf("activateServer() error = %v, wantReady %v", err, tt.wantReady)
+			}
+			if mockClient.called != "whoami" {
+				t.Errorf("Self-test called %q, want whoami", mockClient.called)
+			}
+
+			_, clientExists := agg.clients[serverConfig.Name]
+			if clientExists != tt.wantReady {
+				t.Errorf("Client registered = %v, want %v", clientExists, tt.wantReady)
+			}
+			if gotTools := len(agg.GetTools()); (gotTools > 0) != tt.wantReady {
+				t.Errorf("Got %d exposed tools, wantReady %v", gotTools, tt.wantReady)
+			}
+		})
+	}
+}
+
+// warmingClient fails tool calls with an error result until ready is set, counting them
+type warmingClient struct {
+	MockClient
+	ready atomic.Bool
+	calls atomic.Int32
+}
+
+func (c *warmingClient) CallTool(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	c.calls.Add(1)
+	return &mcp.CallToolResult{IsError: !c.ready.Load()}, nil
+}
+
+func TestReadyCheck(t *testing.T) {
+	serverConfig := config.ServerConfig{
+		Name:       "backend",
+		Command:    "backend-command",
+		ReadyCheck: &config.ReadyCheckConfig{Tool: "ping_db"},
+	}
+	mockClient := &warmingClient{MockClient: MockClient{Tools: []mcp.Tool{{Name: "query"}}}}
+
+	agg := NewMCPAggregator()
+	agg.clients[serverConfig.Name] = mockClient
+	agg.configs[serverConfig.Name] = &serverConfig
+
+	activated := make(chan error, 1)
+	go func() { activated <- agg.activateServer(context.Background(), serverConfig.Name) }()
+
+	// The tools stay hidden while the check fails
+	waitFor(t, 5*time.Second, "the ready check to be called", func() bool { return mockClient.calls.Load() > 0 })
+	if tools := agg.GetTools(); len(tools) != 0 {
+		t.Errorf("Got %d exposed tools of a server that isn't ready, want none", len(tools))
+	}
+
+	mockClient.ready.Store(true)
+	select {
+	case err := <-activated:
+		if err != nil {
+			t.Fatalf("activateServer() error = %v", err)
+		}
+	case <-time.After(5 * time.Second):
+		t.Fatalf("activateServer() didn't return once the server was ready")
+	}
+	if tools := agg.GetTools(); len(tools) != 1 {
+		t.Errorf("Got %d exposed tools of the ready server, want 1", len(tools))
+	}
+
+	// A server that never gets ready is dropped
+	serverConfig.ReadyCheck.TimeoutMs = 100
+	mockClient.ready.Store(false)
+	if err := agg.activateServer(context.Background(), serverConfig.Name); err == nil {
+		t.Errorf("activateServer() succeeded for a server that never got ready")
+	}
+	if _, exists := agg.clients[serverConfig.Name]; exists {
+		t.Errorf("Client of a server that never got ready is still registered")
+	}
+}
+
+func TestRoutes(t *testing.T) {
+	searchTools := []mcp.Tool{{Name: "search"}}
+	first := &recordingClient{MockClient: MockClient{Tools: searchTools}}
+	second := &recordingClient{MockClient: MockClient{Tools: searchTools}}
+
+	agg := NewMCPAggregator()
+	agg.cfg = &config.Config{
+		Routes: map[string]config.RouteConfig{
+			"first_search": {Server: "second", Tool: "search"},
+			"missing_tool": {Server: "second", Tool: "missing"},
+		},
+	}
+	for name, mcpClient := range map[string]MCPClient{"first": first, "second": second} {
+		agg.clients[name] = mcpClient
+		agg.configs[name] = &config.ServerConfig{Name: name, Command: name + "-command"}
+		if err := agg.discoverTools(context.Background(), name); err != nil {
+			t.Fatalf("discoverTools(%s) error = %v", name, err)
+		}
+	}
+	agg.applyRoutes(context.Background())
+
+	request := mcp.CallToolRequest{}
+	request.Params.Name = "first_search"
+	if _, err := agg.CallTool(context.Background(), request); err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if first.called != "" {
+		t.Errorf("Auto-derived server got the call for %q", first.called)
+	}
+	if second.called != "search" {
+		t.Errorf("Routed server called %q, want search", second.called)
+	}
+
+	if _, exists := agg.tools["missing_tool"]; exists {
+		t.Errorf("Route to a missing tool was registered")
+	}
+}
+
+func TestToolNamePrefixing(t *testing.T) {
+	tests := []struct {
+		name      string
+		separator string
+		servers   map[string]string // server name -> tool name
+		wantTools map[string]string // exposed name -> server name
+	}{
+		{
+			name:      "Same tool on two servers",
+			separator: "",
+			servers:   map[string]string{"first": "search", "second": "search"},
+			wantTools: map[string]string{"first_search": "first", "second_search": "second"},
+		},
+		{
+			name:      "Custom separator keeps ambiguous names apart",
+			separator: "__",
+			servers:   map[string]string{"a_b": "c", "a": "b_c"},
+			wantTools: map[string]string{"a_b__c": "a_b", "a__b_c": "a"},
+		},
+		{
+			name:      "Dash separator",
+			separator: "-",
+			servers:   map[string]string{"my-server": "search", "other": "search"},
+			wantTools: map[string]string{"my_server-search": "my-server", "other-search": "other"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			agg := NewMCPAggregator()
+			agg.cfg = &config.Config{ToolNameSeparator: tt.separator}
+			clients := make(map[string]*recordingClient)
+			for serverName, toolName := range tt.servers {
+				clients[serverName] = &recordingClient{MockClient: MockClient{Tools: []mcp.Tool{{Name: toolName}}}}
+				agg.clients[serverName] = clients[serverName]
+				agg.configs[serverName] = &config.ServerConfig{Name: serverName, Command: serverName + "-command"}
+				if err := agg.discoverTools(context.Background(), serverName); err != nil {
+					t.Fatalf("discoverTools(%s) error = %v", serverName, err)
+				}
+			}
+
+			if got := len(agg.GetTools()); got != len(tt.wantTools) {
+				t.Fatalf("Got %d tools, want %d", got, len(tt.wantTools))
+			}
+
+			// Every exposed name reaches its own server with the original tool name
+			for exposedName, serverName := range tt.wantTools {
+				request := mcp.CallToolRequest{}
+				request.Params.Name = exposedName
+				if _, err := agg.CallTool(context.Background(), request); err != nil {
+					t.Fatalf("CallTool(%s) error = %v", exposedName, err)
+				}
+				if got, want := clients[serverName].called, tt.servers[serverName]; got != want {
+					t.Errorf("CallTool(%s) reached server %s with %q, want %q", exposedName, serverName, got, want)
+				}
+			}
+		})
+	}
+}
+
+func TestToolNameCollision(t *testing.T) {
+	agg := NewMCPAggregator()
+	first := &recordingClient{MockClient: MockClient{Tools: []mcp.Tool{{Name: "c"}}}}
+	second := &recordingClient{MockClient: MockClient{Tools: []mcp.Tool{{Name: "b_c"}}}}
+	agg.clients["a_b"] = first
+	agg.clients["a"] = second
+	agg.configs["a_b"] = &config.ServerConfig{Name: "a_b", Command: "first-command"}
+	agg.configs["a"] = &config.ServerConfig{Name: "a", Command: "second-command"}
+
+	for _, serverName := range []string{"a_b", "a"} {
+		if err := agg.discoverTools(context.Background(), serverName); err != nil {
+			t.Fatalf("discoverTools(%s) error = %v", serverName, err)
+		}
+	}
+
+	// The tool registered first keeps the name instead of being silently replaced
+	if mapping := agg.tools["a_b_c"]; mapping.serverName != "a_b" {
+		t.Errorf("a_b_c routes to server %q, want a_b", mapping.serverName)
+	}
+}
+
+func TestUnprefixedToolNames(t *testing.T) {
+	prefixTools := false
+	tests := []struct {
+		name        string
+		onCollision string
+		wantErr     bool
+		wantTools   map[string]string // exposed name -> server name
+	}{
+		{
+			name:      "Collision keeps the first registrant",
+			wantTools: map[string]string{"search": "first", "open-file": "first", "fetch": "second"},
+		},
+		{
+			name:        "Collision fails the second server",
+			onCollision: config.ToolCollisionFail,
+			wantErr:     true,
+			wantTools:   map[string]string{"search": "first", "open-file": "first"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			agg := NewMCPAggregator()
+			agg.cfg = &config.Config{PrefixTools: &prefixTools, OnToolCollision: tt.onCollision}
+			agg.clients["first"] = &MockClient{Tools: []mcp.Tool{{Name: "search"}, {Name: "open-file"}}}
+			agg.clients["second"] = &MockClient{Tools: []mcp.Tool{{Name: "search"}, {Name: "fetch"}}}
+			agg.configs["first"] = &config.ServerConfig{Name: "first", Command: "first-command"}
+			agg.configs["second"] = &config.ServerConfig{Name: "second", Command: "second-command"}
+
+			if err := agg.discoverTools(context.Background(), "first"); err != nil {
+				t.Fatalf("discoverTools(first) error = %v", err)
+			}
+			err := agg.discoverTools(context.Background(), "second")
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("discoverTools(second) error = %v, wantErr %v", err, tt.wantErr)
+			}
+			if err != nil && (!strings.Contains(err.Error(), "server first") || !strings.Contains(err.Error(), "server second")) {
+				t.Errorf("Collision error %q should name both servers", err)
+			}
+
+			// Tools keep their original names, dashes included
+			if len(agg.tools) != len(tt.wantTools) {
+				t.Fatalf("Got tools %v, want %v", agg.tools, tt.wantTools)
+			}
+			for exposedName, serverName := range tt.wantTools {
+				if mapping := agg.tools[exposedName]; mapping.serverName != serverName {
+					t.Errorf("%s routes to server %q, want %s", exposedName, mapping.serverName, serverName)
+				}
+			}
+		})
+	}
+}
+
+func TestDescriptionTemplate(t *testing.T) {
+	agg := NewMCPAggregator()
+	agg.cfg = &config.Config{DescriptionTemplate: "{description} (via {server})"}
+	for _, name := range []string{"github", "gitlab"} {
+		agg.clients[name] = &MockClient{Tools: []mcp.Tool{{Name: "create_issue", Description: "Create an issue"}}}
+		agg.configs[name] = &config.ServerConfig{Name: name, Command: "test-command"}
+	}
+	agg.configs["gitlab"].DescriptionTemplate = "[{server}:{tool}] {description}"
+
+	for _, name := range []string{"github", "gitlab"} {
+		if err := agg.discoverTools(context.Background(), name); err != nil {
+			t.Fatalf("discoverTools(%s) error = %v", name, err)
+		}
+	}
+
+	want := map[string]string{
+		"github_create_issue": "Create an issue (via github)",
+		"gitlab_create_issue": "[gitlab:create_issue] Create an issue",
+	}
+	for _, tool := range agg.GetTools() {
+		if tool.Description != want[tool.Name] {
+			t.Errorf("Description of %s = %q, want %q", tool.Name, tool.Description, want[tool.Name])
+		}
+	}
+}
+
+func TestToolOverrides(t *testing.T) {
+	agg := NewMCPAggregator()
+	agg.cfg = &config.Config{ToolOverrides: map[string]config.ToolOverride{
+		"shortcut_search": {
+			Description: "Search stories by keyword",
+			InputSchema: map[string]interface{}{
+				"type":       "object",
+				"properties": map[string]interface{}{"query": map[string]interface{}{"type": "string"}},
+				"required":   []interface{}{"query"},
+			},
+		},
+		"shortcut_create_story": {Description: "Create a story"},
+	}}
+	agg.clients["shortcut"] = &MockClient{Tools: []mcp.Tool{
+		{Name: "search", Description: "Search", InputSchema: mcp.ToolInputSchema{Type: "object", Properties: map[string]interface{}{}}},
+		{Name: "create_story", Description: "Create", InputSchema: mcp.ToolInputSchema{Type: "object", Required: []string{"name"}}},
+		{Name: "delete_story", Description: "Delete"},
+	}}
+	agg.configs["shortcut"] = &config.ServerConfig{Name: "shortcut", Command: "test-command"}
+	if err := agg.discoverTools(context.Background(), "shortcut"); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+
+	tools := make(map[string]mcp.Tool)
+	for _, tool := range agg.GetTools() {
+		tools[tool.Name] = tool
+	}
+
+	search := tools["shortcut_search"]
+	if search.Description != "Search stories by keyword" {
+		t.Errorf("Description of shortcut_search = %q, want the override without the server prefix", search.Description)
+	}
+	if _, ok := search.InputSchema.Properties["query"]; !ok || len(search.InputSchema.Required) != 1 || search.InputSchema.Required[0] != "query" {
+		t.Errorf("InputSchema of shortcut_search = %+v, want the overridden schema", search.InputSchema)
+	}
+
+	// Overriding the description keeps the discovered schema
+	create := tools["shortcut_create_story"]
+	if create.Description != "Create a story" || len(create.InputSchema.Required) != 1 || create.InputSchema.Required[0] != "name" {
+		t.Errorf("shortcut_create_story = %+v, want the overridden description and the discovered schema", create)
+	}
+
+	if tools["shortcut_delete_story"].Description != "[shortcut] Delete" {
+		t.Errorf("Description of shortcut_delete_story = %q, want it unchanged", tools["shortcut_delete_story"].Description)
+	}
+
+	// Calls still reach the tool under its original name
+	if mapping := agg.tools["shortcut_search"]; mapping.originalName != "search" || mapping.tool.Description != "Search" {
+		t.Errorf("mapping of shortcut_search = %+v, want the discovered tool", mapping)
+	}
+}
+
+func TestArgumentDefaults(t *testing.T) {
+	defaults := map[string]interface{}{
+		"owner":   "acme",
+		"options": map[string]interface{}{"draft": true, "labels": []interface{}{"triage"}},
+	}
+	agg := NewMCPAggregator()
+	agg.cfg = &config.Config{ToolOverrides: map[string]config.ToolOverride{
+		"github_create_issue": {ArgumentDefaults: defaults},
+	}}
+	client := &recordingClient{MockClient: MockClient{Tools: []mcp.Tool{
+		{Name: "create_issue", InputSchema: mcp.ToolInputSchema{Type: "object", Required: []string{"owner", "title"}}},
+	}}}
+	agg.clients["github"] = client
+	agg.configs["github"] = &config.ServerConfig{Name: "github", Command: "test-command"}
+	if err := agg.discoverTools(context.Background(), "github"); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+
+	// Clients aren't asked for arguments that have a default
+	if tools := agg.GetTools(); len(tools) != 1 || !reflect.DeepEqual(tools[0].InputSchema.Required, []string{"title"}) {
+		t.Errorf("GetTools() = %+v, want create_issue requiring only title", tools)
+	}
+
+	tests := []struct {
+		name      string
+		arguments map[string]interface{}
+		want      map[string]interface{}
+	}{
+		{
+			name:      "Defaults fill in missing arguments",
+			arguments: map[string]interface{}{"title": "Bug"},
+			want: map[string]interface{}{
+				"owner":   "acme",
+				"title":   "Bug",
+				"options": map[string]interface{}{"draft": true, "labels": []interface{}{"triage"}},
+			},
+		},
+		{
+			name:      "Client arguments win, objects are merged",
+			arguments: map[string]interface{}{"owner": "other", "options": map[string]interface{}{"draft": false}},
+			want: map[string]interface{}{
+				"owner":   "other",
+				"options": map[string]interface{}{"draft": false, "labels": []interface{}{"triage"}},
+			},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			request := mcp.CallToolRequest{}
+			request.Params.Name = "github_create_issue"
+			request.Params.Arguments = tt.arguments
+			if _, err := agg.CallTool(context.Background(), request); err != nil {
+				t.Fatalf("CallTool() error = %v", err)
+			}
+			if !reflect.DeepEqual(client.arguments, tt.want) {
+				t.Errorf("Server got arguments %v, want %v", client.arguments, tt.want)
+			}
+		})
+	}
+
+	// The configured defaults are never changed by a call
+	if options := defaults["options"].(map[string]interface{}); options["draft"] != true {
+		t.Errorf("Defaults changed to %v", defaults)
+	}
+}
+
+func TestTaggedTools(t *testing.T) {
+	agg := NewMCPAggregator()
+	servers := map[string][]string{"github": {"code", "review"}, "gitlab": {"code"}, "slack": {"chat"}, "notes": nil}
+	for name, tags := range servers {
+		agg.clients[name] = &MockClient{Tools: []mcp.Tool{{Name: "search"}}}
+		agg.configs[name] = &config.ServerConfig{Name: name, Command: "test-command", Tags: tags}
+		if err := agg.discoverTools(context.Background(), name); err != nil {
+			t.Fatalf("discoverTools(%s) error = %v", name, err)
+		}
+	}
+
+	tests := []struct {
+		tags []string
+		want []string
+	}{
+		{tags: []string{"code"}, want: []string{"github_search", "gitlab_search"}},
+		{tags: []string{"review", "chat"}, want: []string{"github_search", "slack_search"}},
+		{tags: []string{"unknown"}, want: nil},
+	}
+	for _, tt := range tests {
+		tagged := agg.TaggedTools(tt.tags)
+		if len(tagged) != len(tt.want) {
+			t.Errorf("TaggedTools(%v) = %v, want %v", tt.tags, tagged, tt.want)
+			continue
+		}
+		for _, name := range tt.want {
+			if !tagged[name] {
+				t.Errorf("TaggedTools(%v) = %v, want %v", tt.tags, tagged, tt.want)
+			}
+		}
+	}
+}
+
+func TestToolsCache(t *testing.T) {
+	agg := NewMCPAggregator()
+	mockClient := &MockClient{Tools: []mcp.Tool{{Name: "b"}, {Name: "a"}}}
+	agg.clients["server"] = mockClient
+	agg.configs["server"] = &config.ServerConfig{Name: "server", Command: "test-command"}
+
+	if err := agg.discoverTools(context.Background(), "server"); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+
+	tools := agg.GetTools()
+	if len(tools) != 2 || tools[0].Name != "server_a" || tools[1].Name != "server_b" {
+		t.Fatalf("GetTools() = %v, want server_a and server_b in order", tools)
+	}
+
+	// Changes on the server stay invisible until the tools are refreshed
+	mockClient.Tools = append(mockClient.Tools, mcp.Tool{Name: "c"})
+	if got := len(agg.GetTools()); got != 2 {
+		t.Errorf("GetTools() before refresh returned %d tools, want the cached 2", got)
+	}
+
+	if err := agg.RefreshTools(context.Background()); err != nil {
+		t.Fatalf("RefreshTools() error = %v", err)
+	}
+	if got := len(agg.GetTools()); got != 3 {
+		t.Errorf("GetTools() after refresh returned %d tools, want 3", got)
+	}
+}
+
+func TestMissingToolErrors(t *testing.T) {
+	agg := NewMCPAggregator()
+	agg.cfg = &config.Config{Servers: []config.ServerConfig{
+		{Name: "github", Command: "github-command", Tools: &config.ToolsConfig{Denied: []string{"delete_repo"}}},
+		{Name: "crashed", Command: "crashed-command"},
+		{Name: "broken", Command: "broken-command"},
+	}}
+	for i := range agg.cfg.Servers[:2] {
+		serverConfig := &agg.cfg.Servers[i]
+		agg.clients[serverConfig.Name] = &MockClient{Tools: []mcp.Tool{{Name: "delete_repo"}, {Name: "search"}}}
+		agg.configs[serverConfig.Name] = serverConfig
+		if err := agg.discoverTools(context.Background(), serverConfig.Name); err != nil {
+			t.Fatalf("discoverTools(%s) error = %v", serverConfig.Name, err)
+		}
+	}
+	agg.removeClient("crashed")
+
+	tests := []struct {
+		tool       string
+		wantServer bool
+		wantReason string
+	}{
+		{tool: "github_delete_repo", wantReason: "filtered out by the tools settings of server github"},
+		{tool: "crashed_search", wantServer: true},
+		{tool: "broken_search", wantServer: true},
+		{tool: "gitlab_search", wantReason: ""},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.tool, func(t *testing.T) {
+			_, err := callEcho(agg, tt.tool, "hello")
+			var serverErr *ServerError
+			var notFound *ToolNotFoundError
+			switch {
+			case tt.wantServer:
+				if !errors.As(err, &serverErr) {
+					t.Errorf("CallTool() error = %v, want a server error", err)
+				}
+			case !errors.As(err, &notFound):
+				t.Errorf("CallTool() error = %v, want a tool not found error", err)
+			case tt.wantReason == "" && notFound.Reason != "", !strings.Contains(notFound.Reason, tt.wantReason):
+				t.Errorf("CallTool() reason = %q, want %q", notFound.Reason, tt.wantReason)
+			}
+		})
+	}
+}
+
+func TestDedupeTools(t *testing.T) {
+	schema := mcp.ToolInputSchema{Type: "object", Properties: map[string]interface{}{"query": map[string]interface{}{"type": "string"}}}
+	otherSchema := mcp.ToolInputSchema{Type: "object", Properties: map[string]interface{}{"q": map[string]interface{}{"type": "string"}}}
+
+	tests := []struct {
+		name        string
+		policy      string
+		weight      int      // weight of the primary server
+		wantServers []string // servers called by four calls of primary_search
+	}{
+		{name: "First healthy", policy: config.DedupeToolsFirstHealthy, wantServers: []string{"primary", "primary", "primary", "primary"}},
+		{name: "Round robin", policy: config.DedupeToolsRoundRobin, wantServers: []string{"primary", "backup", "primary", "backup"}},
+		{name: "Weighted round robin", policy: config.DedupeToolsRoundRobin, weight: 3, wantServers: []string{"primary", "primary", "primary", "backup"}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			agg := NewMCPAggregator()
+			agg.cfg = &config.Config{DedupeTools: tt.policy, Servers: []config.ServerConfig{
+				{Name: "primary", Command: "primary-command", Weight: tt.weight},
+				{Name: "backup", Command: "backup-command"},
+				{Name: "other", Command: "other-command"},
+			}}
+			clients := map[string]*recordingClient{
+				"primary": {MockClient: MockClient{Tools: []mcp.Tool{{Name: "search", InputSchema: schema}}}},
+				"backup":  {MockClient: MockClient{Tools: []mcp.Tool{{Name: "search", InputSchema: schema}}}},
+				"other":   {MockClient: MockClient{Tools: []mcp.Tool{{Name: "search", InputSchema: otherSchema}}}},
+			}
+			for i := range agg.cfg.Servers {
+				serverConfig := &agg.cfg.Servers[i]
+				agg.clients[serverConfig.Name] = clients[serverConfig.Name]
+				agg.configs[serverConfig.Name] = serverConfig
+				if err := agg.discoverTools(context.Background(), serverConfig.Name); err != nil {
+					t.Fatalf("discoverTools(%s) error = %v", serverConfig.Name, err)
+				}
+			}
+
+			// The identical tool is exposed once, the one with another schema separately
+			var names []string
+			for _, tool := range agg.GetTools() {
+				names = append(names, tool.Name)
+			}
+			if strings.Join(names, ",") != "other_search,primary_search" {
+				t.Fatalf("GetTools() names = %v, want [other_search primary_search]", names)
+			}
+
+			var called []string
+			for range tt.wantServers {
+				if _, err := callEcho(agg, "primary_search", "hello"); err != nil {
+					t.Fatalf("CallTool() error = %v", err)
+				}
+				for serverName, client := range clients {
+					if client.called != "" {
+						called = append(called, serverName)
+						client.called = ""
+					}
+				}
+			}
+			if strings.Join(called, ",") != strings.Join(tt.wantServers, ",") {
+				t.Errorf("Calls went to %v, want %v", called, tt.wantServers)
+			}
+
+			// An unhealthy provider is skipped
+			agg.SetServerHealthy("primary", false)
+			if _, err := callEcho(agg, "primary_search", "hello"); err != nil || clients["backup"].called != "search" || clients["primary"].called != "" {
+				t.Errorf("CallTool() with unhealthy primary called backup %q, primary %q, error %v, want backup", clients["backup"].called, clients["primary"].called, err)
+			}
+			agg.SetServerHealthy("primary", true)
+
+			_, err := callEcho(agg, "backup_search", "hello")
+			var notFound *ToolNotFoundError
+			if !errors.As(err, &notFound) || !strings.Contains(notFound.Reason, "identical to tool primary_search") {
+				t.Errorf("CallTool(backup_search) error = %v, want a tool not found error naming primary_search", err)
+			}
+
+			// The tool keeps its exposed name when the server it was registered for goes away
+			agg.removeClient("primary")
+			if mapping := agg.tools["primary_search"]; mapping.serverName != "backup" || len(mapping.replicas) != 0 {
+				t.Errorf("primary_search routes to server %q with replicas %v after removing primary, want backup alone", mapping.serverName, mapping.replicas)
+			}
+		})
+	}
+}
@@ -0,0 +1,160 @@
+package aggregator
+
+import (
+	"context"
+	"sync/atomic"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+const (
+	// defaultMaxInFlight bounds the outstanding requests to a single server when not configured
+	defaultMaxInFlight = 64
+	// callWaitLogThreshold is how long a tool call may wait for a maxConcurrency slot before it is logged
+	callWaitLogThreshold = time.Second
+)
+
+// boundedClient limits how many requests may be outstanding to a single server at once.
+// This is an internal memory guard: requests beyond the limit wait for a free slot.
+type boundedClient struct {
+	MCPClient
+	slots   chan struct{}
+	pending int64
+
+	serverName string
+	calls      chan struct{} // Tool call slots, nil when tool calls aren't limited
+}
+
+// newBoundedClient wraps a client so that at most limit requests are in flight
+func newBoundedClient(c MCPClient, limit int) *boundedClient {
+	if limit <= 0 {
+		limit = defaultMaxInFlight
+	}
+	return &boundedClient{
+		MCPClient: c,
+		slots:     make(chan struct{}, limit),
+	}
+}
+
+// limitCalls lets at most limit tool calls to the server run at once, queuing the others; 0 means no limit
+func (c *boundedClient) limitCalls(serverName string, limit int) {
+	c.serverName = serverName
+	if limit > 0 {
+		c.calls = make(chan struct{}, limit)
+	}
+}
+
+// acquireCall waits for a free tool call slot, logging calls that wait long enough to hint at contention
+func (c *boundedClient) acquireCall(ctx context.Context, toolName string) error {
+	select {
+	case c.calls <- struct{}{}:
+		return nil
+	default:
+	}
+
+	waiting := time.NewTimer(callWaitLogThreshold)
+	defer waiting.Stop()
+	for {
+		select {
+		case c.calls <- struct{}{}:
+			return nil
+		case <-waiting.C:
+			logger.Info("Call of tool %s is waiting for server %s, which already runs its maximum of %d concurrent calls", toolName, c.serverName, cap(c.calls))
+		case <-ctx.Done():
+			return ctx.Err()
+		}
+	}
+}
+
+// acquire waits for a free request slot
+func (c *boundedClient) acquire(ctx context.Context) error {
+	select {
+	case c.slots <- struct{}{}:
+		atomic.AddInt64(&c.pending, 1)
+		return nil
+	case <-ctx.Done():
+		return ctx.Err()
+	}
+}
+
+// release frees a request slot
+func (c *boundedClient) release() {
+	atomic.AddInt64(&c.pending, -1)
+	<-c.slots
+}
+
+// Pending returns the number of requests currently in flight
+func (c *boundedClient) Pending() int {
+	return int(atomic.LoadInt64(&c.pending))
+}
+
+// Initialize forwards the initialize request once a slot is free
+func (c *boundedClient) Initialize(ctx context.Context, request mcp.InitializeRequest) (*mcp.InitializeResult, error) {
+	if err := c.acquire(ctx); err != nil {
+		return nil, err
+	}
+	defer c.release()
+	return c.MCPClient.Initialize(ctx, request)
+}
+
+// ListTools forwards the tools/list request once a slot is free
+func (c *boundedClient) ListTools(ctx context.Context, request mcp.ListToolsRequest) (*mcp.ListToolsResult, error) {
+	if err := c.acquire(ctx); err != nil {
+		return nil, err
+	}
+	defer c.release()
+	return c.MCPClient.ListTools(ctx, request)
+}
+
+// CallTool forwards the tools/call request once a tool call slot, if limited, and a request slot are free
+func (c *boundedClient) CallTool(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	if c.calls != nil {
+		if err := c.acquireCall(ctx, request.Params.Name); err != nil {
+			return nil, err
+		}
+		defer func() { <-c.calls }()
+	}
+	if err := c.acquire(ctx); err != nil {
+		return nil, err
+	}
+	defer c.release()
+	return c.MCPClient.CallTool(ctx, request)
+}
+
+// ListResources forwards the resources/list request once a slot is free
+func (c *boundedClient) ListResources(ctx context.Context, request mcp.ListResourcesRequest) (*mcp.ListResourcesResult, error) {
+	if err := c.acquire(ctx); err != nil {
+		return nil, err
+	}
+	defer c.release()
+	return c.MCPClient.ListResources(ctx, request)
+}
+
+// ReadResource forwards the resources/read request once a slot is free
+func (c *boundedClient) ReadResource(ctx context.Context, request mcp.ReadResourceRequest) (*mcp.ReadResourceResult, error) {
+	if err := c.acquire(ctx); err != nil {
+		return nil, err
+	}
+	defer c.release()
+	return c.MCPClient.ReadResource(ctx, request)
+}
+
+// ListPrompts forwards the prompts/list request once a slot is free
+func (c *boundedClient) ListPrompts(ctx context.Context, request mcp.ListPromptsRequest) (*mcp.ListPromptsResult, error) {
+	if err := c.acquire(ctx); err != nil {
+		return nil, err
+	}
+	defer c.release()
+	return c.MCPClient.ListPrompts(ctx, request)
+}
+
+// GetPrompt forwards the prompts/get request once a slot is free
+func (c *boundedClient) GetPrompt(ctx context.Context, request mcp.GetPromptRequest) (*mcp.GetPromptResult, error) {
+	if err := c.acquire(ctx); err != nil {
+		return nil, err
+	}
+	defer c.release()
+	return c.MCPClient.GetPrompt(ctx, request)
+}
@@ -0,0 +1,131 @@
+package aggregator
+
+import (
+	"context"
+	"errors"
+	"sync"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+)
+
+// blockingClient holds every tool call until release is closed and records peak concurrency
+type blockingClient struct {
+	MockClient
+	release chan struct{}
+	active  int64
+	peak    int64
+}
+
+func (c *blockingClient) CallTool(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	current := atomic.AddInt64(&c.active, 1)
+	for {
+		peak := atomic.LoadInt64(&c.peak)
+		if current <= peak || atomic.CompareAndSwapInt64(&c.peak, peak, current) {
+			break
+		}
+	}
+	<-c.release
+	atomic.AddInt64(&c.active, -1)
+	return &mcp.CallToolResult{}, nil
+}
+
+func TestBoundedClientLimitsInFlightRequests(t *testing.T) {
+	const limit = 4
+	const callers = 50
+
+	inner := &blockingClient{release: make(chan struct{})}
+	bounded := newBoundedClient(inner, limit)
+
+	var wg sync.WaitGroup
+	for i := 0; i < callers; i++ {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			if _, err := bounded.CallTool(context.Background(), mcp.CallToolRequest{}); err != nil {
+				t.Errorf("CallTool() error = %v", err)
+			}
+		}()
+	}
+
+	// Give the flood time to pile up against the limit
+	deadline := time.Now().Add(time.Second)
+	for bounded.Pending() < limit && time.Now().Before(deadline) {
+		time.Sleep(time.Millisecond)
+	}
+	if got := bounded.Pending(); got != limit {
+		t.Errorf("Pending() = %d, want %d", got, limit)
+	}
+
+	close(inner.release)
+	wg.Wait()
+
+	if peak := atomic.LoadInt64(&inner.peak); peak > limit {
+		t.Errorf("Peak in-flight requests = %d, want at most %d", peak, limit)
+	}
+	if got := bounded.Pending(); got != 0 {
+		t.Errorf("Pending() after completion = %d, want 0", got)
+	}
+}
+
+func TestBoundedClientLimitsConcurrentCalls(t *testing.T) {
+	const limit = 2
+	const callers = 10
+
+	inner := &blockingClient{release: make(chan struct{})}
+	bounded := newBoundedClient(inner, 0)
+	bounded.limitCalls("test", limit)
+
+	var wg sync.WaitGroup
+	for i := 0; i < callers; i++ {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			if _, err := bounded.CallTool(context.Background(), mcp.CallToolRequest{}); err != nil {
+				t.Errorf("CallTool() error = %v", err)
+			}
+		}()
+	}
+
+	deadline := time.Now().Add(time.Second)
+	for atomic.LoadInt64(&inner.active) < limit && time.Now().Before(deadline) {
+		time.Sleep(time.Millisecond)
+	}
+	time.Sleep(20 * time.Millisecond)
+	if got := bounded.Pending(); got != limit {
+		t.Errorf("Pending() = %d, want %d", got, limit)
+	}
+
+	// Other requests aren't held back by the queued calls
+	if _, err := bounded.ListTools(context.Background(), mcp.ListToolsRequest{}); err != nil {
+		t.Errorf("ListTools() error = %v", err)
+	}
+
+	close(inner.release)
+	wg.Wait()
+
+	if peak := atomic.LoadInt64(&inner.peak); peak > limit {
+		t.Errorf("Peak concurrent calls = %d, want at most %d", peak, limit)
+	}
+}
+
+func TestBoundedClientQueuedCallHonorsContext(t *testing.T) {
+	inner := &blockingClient{release: make(chan struct{})}
+	defer close(inner.release)
+	bounded := newBoundedClient(inner, 0)
+	bounded.limitCalls("test", 1)
+
+	go bounded.CallTool(context.Background(), mcp.CallToolRequest{})
+	deadline := time.Now().Add(time.Second)
+	for atomic.LoadInt64(&inner.active) < 1 && time.Now().Before(deadline) {
+		time.Sleep(time.Millisecond)
+	}
+
+	ctx, cancel := context.WithTimeout(context.Background(), 20*time.Millisecond)
+	defer cancel()
+	if _, err := bounded.CallTool(ctx, mcp.CallToolRequest{}); !errors.Is(err, context.DeadlineExceeded) {
+		t.Errorf("CallTool() error = %v, want %v", err, context.DeadlineExceeded)
+	}
+}
@@ -0,0 +1,24 @@
+package aggregator
+
+// ProvidedCapabilities tells which of the optional capabilities the servers provide between them
+type ProvidedCapabilities struct {
+	Resources bool
+	Prompts   bool
+}
+
+// Capabilities merges the capabilities of the servers: a capability is provided when any started server has
+// it. While a lazy server hasn't started yet it may provide anything, so every capability is reported then.
+func (a *MCPAggregator) Capabilities() ProvidedCapabilities {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	if len(a.lazy) > 0 {
+		return ProvidedCapabilities{Resources: true, Prompts: true}
+	}
+	var provided ProvidedCapabilities
+	for _, capabilities := range a.capabilities {
+		provided.Resources = provided.Resources || capabilities.Resources != nil
+		provided.Prompts = provided.Prompts || capabilities.Prompts != nil
+	}
+	return provided
+}
@@ -0,0 +1,33 @@
+package aggregator
+
+import (
+	"encoding/json"
+	"testing"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestCapabilities(t *testing.T) {
+	var withPrompts mcp.ServerCapabilities
+	if err := json.Unmarshal([]byte(`{"tools":{},"prompts":{}}`), &withPrompts); err != nil {
+		t.Fatalf("Failed to decode capabilities: %v", err)
+	}
+
+	agg := NewMCPAggregator()
+	if got := agg.Capabilities(); got != (ProvidedCapabilities{}) {
+		t.Errorf("Capabilities() without servers = %+v, want none", got)
+	}
+
+	agg.capabilities["plain"] = mcp.ServerCapabilities{}
+	agg.capabilities["prompting"] = withPrompts
+	if got, want := agg.Capabilities(), (ProvidedCapabilities{Prompts: true}); got != want {
+		t.Errorf("Capabilities() = %+v, want %+v", got, want)
+	}
+
+	// A lazy server may provide anything once it's started
+	agg.deferServer(&config.ServerConfig{Name: "lazy", Command: "lazy-command"}, false)
+	if got, want := agg.Capabilities(), (ProvidedCapabilities{Resources: true, Prompts: true}); got != want {
+		t.Errorf("Capabilities() with a lazy server = %+v, want %+v", got, want)
+	}
+}
@@ -0,0 +1,25 @@
+package aggregator
+
+import (
+	"context"
+	"crypto/rand"
+	"encoding/hex"
+)
+
+// correlationKey marks a context carrying the correlation id of the upstream request being handled
+type correlationKey struct{}
+
+// WithCorrelationID returns a context carrying a newly generated correlation id, which tags the trace
+// logs of the messages exchanged with a server on behalf of one upstream request, and the id itself
+func WithCorrelationID(ctx context.Context) (context.Context, string) {
+	var random [6]byte
+	rand.Read(random[:])
+	id := hex.EncodeToString(random[:])
+	return context.WithValue(ctx, correlationKey{}, id), id
+}
+
+// CorrelationID returns the correlation id carried by ctx, empty when it has none
+func CorrelationID(ctx context.Context) string {
+	id, _ := ctx.Value(correlationKey{}).(string)
+	return id
+}
@@ -0,0 +1,21 @@
+package aggregator
+
+import (
+	"context"
+	"testing"
+)
+
+func TestCorrelationID(t *testing.T) {
+	if id := CorrelationID(context.Background()); id != "" {
+		t.Errorf("CorrelationID() of a plain context = %q, want none", id)
+	}
+
+	first, firstID := WithCorrelationID(context.Background())
+	_, secondID := WithCorrelationID(context.Background())
+	if firstID == "" || firstID == secondID {
+		t.Errorf("WithCorrelationID() ids = %q and %q, want distinct ids", firstID, secondID)
+	}
+	if got := CorrelationID(first); got != firstID {
+		t.Errorf("CorrelationID() = %q, want %q", got, firstID)
+	}
+}
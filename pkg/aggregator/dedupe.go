@@ -0,0 +1,138 @@
+package aggregator
+
+import (
+	"reflect"
+	"sync/atomic"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// duplicateOfLocked returns the exposed name of another server's tool with the same name and input schema
+func (a *MCPAggregator) duplicateOfLocked(serverName string, tool mcp.Tool) (string, bool) {
+	for exposedName, mapping := range a.tools {
+		if mapping.serverName != serverName && mapping.originalName == tool.Name && reflect.DeepEqual(mapping.tool.InputSchema, tool.InputSchema) {
+			return exposedName, true
+		}
+	}
+	return "", false
+}
+
+// addReplicaLocked makes a server one more provider of an exposed tool
+func (a *MCPAggregator) addReplicaLocked(exposedName, serverName string) {
+	mapping := a.tools[exposedName]
+	for _, replica := range mapping.replicas {
+		if replica == serverName {
+			return
+		}
+	}
+	mapping.replicas = append(append([]string(nil), mapping.replicas...), serverName)
+	if mapping.next == nil {
+		mapping.next = &atomic.Uint32{}
+	}
+	a.tools[exposedName] = mapping
+}
+
+// dedupedIntoLocked returns the exposed name of a deduplicated tool the server provides under the given name
+func (a *MCPAggregator) dedupedIntoLocked(serverName, toolName string) (string, bool) {
+	for exposedName, mapping := range a.tools {
+		if mapping.originalName != toolName || len(mapping.replicas) == 0 {
+			continue
+		}
+		for _, provider := range toolProviders(mapping) {
+			if provider == serverName {
+				return exposedName, true
+			}
+		}
+	}
+	return "", false
+}
+
+// toolProviders returns the servers providing an exposed tool, the one it was registered for first
+func toolProviders(mapping toolMapping) []string {
+	return append([]string{mapping.serverName}, mapping.replicas...)
+}
+
+// pickProviderLocked chooses the server to call a tool on by the dedupeTools policy among the running,
+// reachable and healthy servers providing it, falling back to the one it was registered for
+func (a *MCPAggregator) pickProviderLocked(mapping toolMapping) string {
+	if len(mapping.replicas) == 0 {
+		return mapping.serverName
+	}
+
+	provides := make(map[string]bool, len(mapping.replicas)+1)
+	for _, serverName := range toolProviders(mapping) {
+		provides[serverName] = true
+	}
+	var candidates []*config.ServerConfig
+	for i := range a.cfg.Servers {
+		serverName := a.cfg.Servers[i].Name
+		if _, running := a.clients[serverName]; provides[serverName] && running && !a.unavailable[serverName] && a.isHealthyLocked(serverName) {
+			candidates = append(candidates, &a.cfg.Servers[i])
+		}
+	}
+
+	switch {
+	case len(candidates) == 0:
+		return mapping.serverName
+	case a.cfg.DedupeTools == config.DedupeToolsRoundRobin:
+		return pickWeighted(candidates, mapping.next.Add(1)-1)
+	default:
+		return candidates[0].Name
+	}
+}
+
+// pickWeighted returns the server taking the turn-th call of a round-robin, where each round gives every
+// server as many calls in a row as its weight
+func pickWeighted(candidates []*config.ServerConfig, turn uint32) string {
+	total := 0
+	for _, candidate := range candidates {
+		total += candidate.RoundRobinWeight()
+	}
+
+	slot := int(turn % uint32(total))
+	for _, candidate := range candidates {
+		if slot < candidate.RoundRobinWeight() {
+			return candidate.Name
+		}
+		slot -= candidate.RoundRobinWeight()
+	}
+	return candidates[0].Name
+}
+
+// anyProviderHealthyLocked reports whether any server providing an exposed tool is healthy
+func (a *MCPAggregator) anyProviderHealthyLocked(mapping toolMapping) bool {
+	for _, serverName := range toolProviders(mapping) {
+		if a.isHealthyLocked(serverName) {
+			return true
+		}
+	}
+	return false
+}
+
+// anyProviderRunningLocked reports whether any server providing an exposed tool has a client
+func (a *MCPAggregator) anyProviderRunningLocked(mapping toolMapping) bool {
+	for _, serverName := range toolProviders(mapping) {
+		if _, running := a.clients[serverName]; running {
+			return true
+		}
+	}
+	return false
+}
+
+// removeReplicatedLocked drops a server from the providers of a deduplicated tool. A tool registered for
+// the server keeps its exposed name and moves to its next provider, so clients can keep calling it.
+func (a *MCPAggregator) removeReplicatedLocked(exposedName, serverName string) {
+	mapping := a.tools[exposedName]
+	remaining := make([]string, 0, len(mapping.replicas))
+	for _, replica := range mapping.replicas {
+		if replica != serverName {
+			remaining = append(remaining, replica)
+		}
+	}
+	if mapping.serverName == serverName {
+		mapping.serverName, remaining = remaining[0], remaining[1:]
+	}
+	mapping.replicas = remaining
+	a.tools[exposedName] = mapping
+}
@@ -0,0 +1,55 @@
+package aggregator
+
+import (
+	"context"
+	"errors"
+)
+
+// Kinds of tool call failures, stable names by which clients can tell failures apart
+const (
+	ErrorKindToolNotFound      = "tool_not_found"     // No server exposes the tool
+	ErrorKindServerUnavailable = "server_unavailable" // The server crashed, couldn't be started or can't be reached
+	ErrorKindTimeout           = "timeout"            // The server didn't answer within its timeoutMs
+	ErrorKindCancelled         = "cancelled"          // The client gave up on the call
+	ErrorKindServerError       = "server_error"       // The server answered the call with an error
+	ErrorKindInternal          = "internal"           // Anything else
+)
+
+// ErrorKind classifies the error a tool call failed with
+func ErrorKind(err error) string {
+	var notFound *ToolNotFoundError
+	var timeout *callTimeoutError
+	var serverErr *ServerError
+	var spawnErr *spawnError
+	var exited *serverExitedError
+	var lost *unavailableError
+	var rpcErr *rpcError
+	switch {
+	case errors.As(err, &notFound):
+		return ErrorKindToolNotFound
+	case errors.As(err, &timeout), errors.Is(err, context.DeadlineExceeded):
+		return ErrorKindTimeout
+	case errors.Is(err, context.Canceled):
+		return ErrorKindCancelled
+	case errors.As(err, &serverErr), errors.As(err, &spawnErr), errors.As(err, &exited), errors.As(err, &lost):
+		return ErrorKindServerUnavailable
+	case errors.As(err, &rpcErr):
+		return ErrorKindServerError
+	default:
+		return ErrorKindInternal
+	}
+}
+
+// ErrorServer returns the name of the server a tool call failed on, empty when it isn't known
+func ErrorServer(err error) string {
+	var timeout *callTimeoutError
+	var serverErr *ServerError
+	switch {
+	case errors.As(err, &serverErr):
+		return serverErr.Server
+	case errors.As(err, &timeout):
+		return timeout.serverName
+	default:
+		return ""
+	}
+}
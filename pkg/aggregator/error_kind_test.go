@@ -0,0 +1,37 @@
+package aggregator
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"testing"
+	"time"
+)
+
+func TestErrorKind(t *testing.T) {
+	tests := []struct {
+		name       string
+		err        error
+		wantKind   string
+		wantServer string
+	}{
+		{name: "Unknown tool", err: &ToolNotFoundError{Tool: "missing"}, wantKind: ErrorKindToolNotFound},
+		{name: "Timeout", err: &callTimeoutError{serverName: "slow", tool: "search", timeout: time.Second}, wantKind: ErrorKindTimeout, wantServer: "slow"},
+		{name: "Cancelled call", err: fmt.Errorf("call failed: %w", context.Canceled), wantKind: ErrorKindCancelled},
+		{name: "Crashed server", err: &ServerError{Server: "github", Tool: "github_search", Err: &serverExitedError{serverName: "github"}}, wantKind: ErrorKindServerUnavailable, wantServer: "github"},
+		{name: "Server that didn't start", err: &spawnError{serverName: "github", err: errors.New("not found")}, wantKind: ErrorKindServerUnavailable},
+		{name: "Error answer", err: &rpcError{Code: -32603, Message: "boom"}, wantKind: ErrorKindServerError},
+		{name: "Anything else", err: errors.New("bad input"), wantKind: ErrorKindInternal},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := ErrorKind(tt.err); got != tt.wantKind {
+				t.Errorf("ErrorKind() = %q, want %q", got, tt.wantKind)
+			}
+			if got := ErrorServer(tt.err); got != tt.wantServer {
+				t.Errorf("ErrorServer() = %q, want %q", got, tt.wantServer)
+			}
+		})
+	}
+}
@@ -0,0 +1,42 @@
+package aggregator
+
+import (
+	"path"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// toolFilter decides which of a server's tools are exposed based on its tools config
+type toolFilter struct {
+	config *config.ToolsConfig
+}
+
+// exposesNothing reports whether the config filters out every tool, which an empty allowed list does
+func (f toolFilter) exposesNothing() bool {
+	return f.config != nil && len(f.config.Allowed) == 0 && len(f.config.Denied) == 0
+}
+
+// allows reports whether a tool is exposed. Patterns match either the tool's own name or its
+// prefixed name, with dashes and underscores treated alike.
+func (f toolFilter) allows(name, prefixedName string) bool {
+	if f.config == nil {
+		return true
+	}
+	if len(f.config.Allowed) > 0 && !matchesAny(f.config.Allowed, name, prefixedName) {
+		return false
+	}
+	return !matchesAny(f.config.Denied, name, prefixedName)
+}
+
+// matchesAny reports whether any of the glob patterns matches one of the names
+func matchesAny(patterns []string, names ...string) bool {
+	for _, pattern := range patterns {
+		pattern = normalizeToolName(pattern)
+		for _, name := range names {
+			if matched, _ := path.Match(pattern, normalizeToolName(name)); matched {
+				return true
+			}
+		}
+	}
+	return false
+}
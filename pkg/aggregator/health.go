@@ -0,0 +1,187 @@
+package aggregator
+
+import (
+	"context"
+	"encoding/json"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+const (
+	// defaultUnhealthyAfterFailures is how many consecutive failed calls mark a server unhealthy
+	defaultUnhealthyAfterFailures = 3
+	// recoveryProbeInterval is how often an unhealthy server is probed with tools/list
+	recoveryProbeInterval = 10 * time.Second
+	// recoveryProbeTimeout bounds a single recovery probe
+	recoveryProbeTimeout = 5 * time.Second
+)
+
+// serverHealth tracks the health of a single server based on recent calls
+type serverHealth struct {
+	healthy             bool
+	consecutiveFailures int
+	lastError           string
+	lastPing            time.Time // When the server last answered a keepalive ping, zero if it never did
+}
+
+// OnToolsChanged registers a callback invoked whenever the set of exposed tools changes
+func (a *MCPAggregator) OnToolsChanged(callback func()) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.toolsChanged = callback
+}
+
+// OnProgress registers a callback invoked with the params of every progress notification from a server.
+// The progress token in them is the one the client passed with its request.
+func (a *MCPAggregator) OnProgress(callback func(params json.RawMessage)) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.progress = callback
+}
+
+// forwardProgress hands a progress notification from a server to the registered callback, if any
+func (a *MCPAggregator) forwardProgress(params json.RawMessage) {
+	a.mu.RLock()
+	callback := a.progress
+	a.mu.RUnlock()
+
+	if callback != nil {
+		callback(params)
+	}
+}
+
+// notifyToolsChanged invokes the registered tools changed callback, if any
+func (a *MCPAggregator) notifyToolsChanged() {
+	a.mu.RLock()
+	callback := a.toolsChanged
+	a.mu.RUnlock()
+
+	if callback != nil {
+		callback()
+	}
+}
+
+// IsServerHealthy reports whether a server is currently considered healthy
+func (a *MCPAggregator) IsServerHealthy(serverName string) bool {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+	return a.isHealthyLocked(serverName)
+}
+
+// isHealthyLocked reports server health; servers without recorded health are healthy
+func (a *MCPAggregator) isHealthyLocked(serverName string) bool {
+	health, exists := a.health[serverName]
+	return !exists || health.healthy
+}
+
+// healthLocked returns the health record of a server, creating a healthy one if needed
+func (a *MCPAggregator) healthLocked(serverName string) *serverHealth {
+	health, exists := a.health[serverName]
+	if !exists {
+		health = &serverHealth{healthy: true}
+		a.health[serverName] = health
+	}
+	return health
+}
+
+// SetServerHealthy marks a server healthy or unhealthy, hiding or restoring its tools when configured
+func (a *MCPAggregator) SetServerHealthy(serverName string, healthy bool) {
+	a.mu.Lock()
+	health := a.healthLocked(serverName)
+	changed := health.healthy != healthy
+	health.healthy = healthy
+	if changed {
+		a.toolsCache = nil
+	}
+	if healthy {
+		health.consecutiveFailures = 0
+	}
+	hideUnhealthy := a.cfg.HideUnhealthyTools
+	a.mu.Unlock()
+
+	if !changed {
+		return
+	}
+
+	if healthy {
+		logger.Info("Server %s recovered", serverName)
+	} else {
+		logger.Error("Server %s is unhealthy", serverName)
+		go a.probeRecovery(serverName)
+	}
+
+	if hideUnhealthy {
+		a.notifyToolsChanged()
+	}
+}
+
+// recordServerError remembers the latest error of a server for status reports
+func (a *MCPAggregator) recordServerError(serverName string, err error) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.healthLocked(serverName).lastError = err.Error()
+}
+
+// recordCallResult updates a server's health after a request to it
+func (a *MCPAggregator) recordCallResult(serverName string, err error) {
+	a.mu.Lock()
+	health := a.healthLocked(serverName)
+	if err == nil {
+		recovered := !health.healthy
+		health.consecutiveFailures = 0
+		a.mu.Unlock()
+		if recovered {
+			a.SetServerHealthy(serverName, true)
+		}
+		return
+	}
+
+	health.consecutiveFailures++
+	health.lastError = err.Error()
+	threshold := a.cfg.UnhealthyAfterFailures
+	if threshold <= 0 {
+		threshold = defaultUnhealthyAfterFailures
+	}
+	becameUnhealthy := health.healthy && health.consecutiveFailures >= threshold
+	a.mu.Unlock()
+
+	if becameUnhealthy {
+		a.SetServerHealthy(serverName, false)
+	}
+}
+
+// probeRecovery polls an unhealthy server with tools/list until it answers again
+func (a *MCPAggregator) probeRecovery(serverName string) {
+	ticker := time.NewTicker(recoveryProbeInterval)
+	defer ticker.Stop()
+
+	for {
+		select {
+		case <-a.done:
+			return
+		case <-ticker.C:
+		}
+
+		if a.IsServerHealthy(serverName) {
+			return
+		}
+
+		a.mu.RLock()
+		mcpClient, exists := a.clients[serverName]
+		a.mu.RUnlock()
+		if !exists {
+			return
+		}
+
+		ctx, cancel := context.WithTimeout(context.Background(), recoveryProbeTimeout)
+		_, err := mcpClient.ListTools(ctx, mcp.ListToolsRequest{})
+		cancel()
+		if err == nil {
+			a.SetServerHealthy(serverName, true)
+			return
+		}
+		logger.Debug("Recovery probe for server %s failed: %v", serverName, err)
+	}
+}
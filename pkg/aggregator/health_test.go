@@ -0,0 +1,119 @@
+package aggregator
+
+import (
+	"context"
+	"errors"
+	"testing"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// failingClient fails every tool call while failing is set
+type failingClient struct {
+	MockClient
+	failing bool
+}
+
+func (c *failingClient) CallTool(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	if c.failing {
+		return nil, errors.New("backend unavailable")
+	}
+	return &mcp.CallToolResult{}, nil
+}
+
+func TestHideUnhealthyTools(t *testing.T) {
+	serverConfig := config.ServerConfig{Name: "flaky", Command: "flaky-command"}
+	mockClient := &failingClient{
+		MockClient: MockClient{Tools: []mcp.Tool{{Name: "search"}}},
+		failing:    true,
+	}
+
+	agg := NewMCPAggregator()
+	defer agg.Close()
+	agg.cfg = &config.Config{HideUnhealthyTools: true, UnhealthyAfterFailures: 2}
+	agg.clients[serverConfig.Name] = mockClient
+	agg.configs[serverConfig.Name] = &serverConfig
+	if err := agg.discoverTools(context.Background(), serverConfig.Name); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+
+	changes := 0
+	agg.OnToolsChanged(func() { changes++ })
+
+	request := mcp.CallToolRequest{}
+	request.Params.Name = "flaky_search"
+
+	// The first failure stays below the threshold
+	if _, err := agg.CallTool(context.Background(), request); err == nil {
+		t.Fatal("Expected the call to fail")
+	}
+	if got := len(agg.GetTools()); got != 1 {
+		t.Fatalf("Got %d tools after one failure, want 1", got)
+	}
+
+	// The second consecutive failure marks the server unhealthy and hides its tools
+	if _, err := agg.CallTool(context.Background(), request); err == nil {
+		t.Fatal("Expected the call to fail")
+	}
+	if agg.IsServerHealthy(serverConfig.Name) {
+		t.Fatal("Expected the server to be unhealthy")
+	}
+	if got := len(agg.GetTools()); got != 0 {
+		t.Errorf("Got %d tools from an unhealthy server, want 0", got)
+	}
+	if changes != 1 {
+		t.Errorf("Got %d tools changed notifications, want 1", changes)
+	}
+
+	// Recovery restores the tools
+	mockClient.failing = false
+	agg.SetServerHealthy(serverConfig.Name, true)
+	if got := len(agg.GetTools()); got != 1 {
+		t.Errorf("Got %d tools after recovery, want 1", got)
+	}
+	if changes != 2 {
+		t.Errorf("Got %d tools changed notifications, want 2", changes)
+	}
+}
+
+func TestMetrics(t *testing.T) {
+	serverConfig := config.ServerConfig{Name: "flaky", Command: "flaky-command"}
+	mockClient := &failingClient{MockClient: MockClient{Tools: []mcp.Tool{{Name: "search"}}}}
+
+	agg := NewMCPAggregator()
+	defer agg.Close()
+	agg.cfg = &config.Config{Servers: []config.ServerConfig{serverConfig, {Name: "idle", Command: "idle-command"}}}
+	agg.clients[serverConfig.Name] = mockClient
+	agg.configs[serverConfig.Name] = &serverConfig
+	if err := agg.discoverTools(context.Background(), serverConfig.Name); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+
+	request := mcp.CallToolRequest{}
+	request.Params.Name = "flaky_search"
+	for i := 0; i < 3; i++ {
+		mockClient.failing = i == 2
+		agg.CallTool(context.Background(), request)
+	}
+
+	metrics := agg.Metrics()
+	if len(metrics) != 2 {
+		t.Fatalf("Got metrics of %d servers, want one per configured server", len(metrics))
+	}
+	flaky := metrics[0]
+	if flaky.Name != "flaky" || flaky.Calls != 3 || flaky.Successes != 2 || flaky.Errors != 1 {
+		t.Errorf("Metrics of the called server = %+v, want 3 calls with 2 successes and 1 error", flaky)
+	}
+	var histogramCalls int64
+	for _, bucket := range flaky.LatencyHistogram {
+		histogramCalls += bucket.Count
+	}
+	if histogramCalls != 3 || len(flaky.LatencyHistogram) != len(latencyBucketsMs)+1 {
+		t.Errorf("Latency histogram = %+v, want every call in one of its buckets", flaky.LatencyHistogram)
+	}
+	idle := metrics[1]
+	if idle.Name != "idle" || idle.Calls != 0 || idle.AverageLatencyMs != 0 {
+		t.Errorf("Metrics of the idle server = %+v, want no calls", idle)
+	}
+}
@@ -0,0 +1,60 @@
+package aggregator
+
+import (
+	"fmt"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// pingTimeout bounds a single ping, never waiting longer than the interval between two pings
+func pingTimeout(interval time.Duration) time.Duration {
+	if interval < connectionCheckTimeout {
+		return interval
+	}
+	return connectionCheckTimeout
+}
+
+// keepAlive pings a local server every pingIntervalMs and restarts it once it misses a ping,
+// catching servers whose process still runs but no longer answers
+func (a *MCPAggregator) keepAlive(serverCfg *config.ServerConfig, mcpClient MCPClient, exited <-chan struct{}) {
+	interval := time.Duration(serverCfg.PingIntervalMs) * time.Millisecond
+	ticker := time.NewTicker(interval)
+	defer ticker.Stop()
+
+	for {
+		select {
+		case <-a.done:
+			return
+		case <-exited:
+			return
+		case <-ticker.C:
+		}
+
+		// Dropping or replacing the server ends its keepalive
+		a.mu.RLock()
+		current := a.clients[serverCfg.Name]
+		a.mu.RUnlock()
+		if current != mcpClient {
+			return
+		}
+
+		if err := pingClient(mcpClient, pingTimeout(interval)); err != nil {
+			logger.Error("Warning: server %s missed a ping (%v), restarting it", serverCfg.Name, err)
+			a.recordServerError(serverCfg.Name, fmt.Errorf("missed a ping: %w", err))
+			a.SetServerHealthy(serverCfg.Name, false)
+			// Ending the process hands the server to its supervisor, which restarts it
+			mcpClient.Close()
+			return
+		}
+		a.recordPing(serverCfg.Name)
+	}
+}
+
+// recordPing remembers when a server last answered a ping, for status reports
+func (a *MCPAggregator) recordPing(serverName string) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.healthLocked(serverName).lastPing = time.Now()
+}
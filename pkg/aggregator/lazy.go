@@ -0,0 +1,90 @@
+package aggregator
+
+import (
+	"context"
+	"strings"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// lazyServer is a server whose start is deferred until it's needed
+type lazyServer struct {
+	config *config.ServerConfig
+	cached bool // Its tools are served from the tool cache, so only calling one of them starts it
+}
+
+// StartLazyServers starts the lazy servers whose tools aren't known yet, reporting whether there were any.
+// Concurrent callers wait until the servers are started, so they all see their tools.
+func (a *MCPAggregator) StartLazyServers(ctx context.Context) bool {
+	return a.startDeferred(ctx, func(server lazyServer) bool { return !server.cached })
+}
+
+// startLazyServersFor starts the lazy servers whose tools aren't known yet that a call of the exposed tool name
+// may be for: the ones whose prefix it starts with and the one a route of the name points to. Without prefixes
+// the name doesn't tell the server, so no other is started. It reports whether any server was started.
+func (a *MCPAggregator) startLazyServersFor(ctx context.Context, toolName string) bool {
+	return a.startDeferred(ctx, func(server lazyServer) bool {
+		if server.cached {
+			return false
+		}
+		if route, routed := a.cfg.Routes[toolName]; routed && route.Server == server.config.Name {
+			return true
+		}
+		return a.cfg.PrefixesToolNames() && strings.HasPrefix(toolName, a.prefixedNameLocked(server.config.Name, ""))
+	})
+}
+
+// startLazyServer starts a single deferred server, reporting whether it was deferred
+func (a *MCPAggregator) startLazyServer(ctx context.Context, serverName string) bool {
+	return a.startDeferred(ctx, func(server lazyServer) bool { return server.config.Name == serverName })
+}
+
+// startDeferred starts the deferred servers selected by start, which is called with a.mu held, and keeps
+// deferring the others
+func (a *MCPAggregator) startDeferred(ctx context.Context, start func(server lazyServer) bool) bool {
+	a.lazyMu.Lock()
+	defer a.lazyMu.Unlock()
+
+	var selected []lazyServer
+	a.mu.Lock()
+	remaining := a.lazy[:0:0]
+	for _, server := range a.lazy {
+		if start(server) {
+			selected = append(selected, server)
+		} else {
+			remaining = append(remaining, server)
+		}
+	}
+	a.lazy = remaining
+	a.mu.Unlock()
+	if len(selected) == 0 {
+		return false
+	}
+
+	// The servers outlive the request that needed them first
+	ctx = context.WithoutCancel(ctx)
+	for _, server := range selected {
+		logger.Info("Starting lazy server %s on first use", server.config.Name)
+		if err := a.startServer(ctx, server.config); err != nil {
+			a.recordServerError(server.config.Name, err)
+			logger.Error("Failed to start lazy server %s: %v", server.config.Name, err)
+		}
+	}
+	a.applyRoutes(ctx)
+	return true
+}
+
+// hasLazyServers reports whether some lazy servers haven't been started yet
+func (a *MCPAggregator) hasLazyServers() bool {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+	return len(a.lazy) > 0
+}
+
+// deferServer remembers a server to start when it's needed
+func (a *MCPAggregator) deferServer(serverCfg *config.ServerConfig, cached bool) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.lazy = append(a.lazy, lazyServer{config: serverCfg, cached: cached})
+}
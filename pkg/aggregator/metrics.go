@@ -0,0 +1,126 @@
+package aggregator
+
+import (
+	"fmt"
+	"sync/atomic"
+	"time"
+)
+
+// latencyBucketsMs are the upper bounds of the tool call latency histogram buckets
+var latencyBucketsMs = [...]int64{10, 50, 100, 250, 500, 1000, 2500, 5000, 10000}
+
+// serverMetrics counts the tool calls dispatched to a server; it is updated without holding the aggregator lock
+type serverMetrics struct {
+	calls          atomic.Int64
+	successes      atomic.Int64
+	errors         atomic.Int64
+	totalLatencyNs atomic.Int64
+	maxLatencyNs   atomic.Int64
+	buckets        [len(latencyBucketsMs) + 1]atomic.Int64 // The last bucket holds calls slower than every bound
+}
+
+// ServerMetrics describes the tool calls dispatched to a configured server since startup
+type ServerMetrics struct {
+	Name             string          `json:"name"`
+	Calls            int64           `json:"calls"`
+	Successes        int64           `json:"successes"`
+	Errors           int64           `json:"errors"`
+	AverageLatencyMs float64         `json:"averageLatencyMs"`
+	MaxLatencyMs     float64         `json:"maxLatencyMs"`
+	LatencyHistogram []LatencyBucket `json:"latencyHistogram"`
+}
+
+// LatencyBucket counts the calls that took no longer than Le and longer than the previous bucket's bound
+type LatencyBucket struct {
+	Le    string `json:"le"`
+	Count int64  `json:"count"`
+}
+
+// record adds a finished call to the metrics; failed is set for errors and error results alike
+func (m *serverMetrics) record(latency time.Duration, failed bool) {
+	m.calls.Add(1)
+	if failed {
+		m.errors.Add(1)
+	} else {
+		m.successes.Add(1)
+	}
+
+	m.totalLatencyNs.Add(int64(latency))
+	for {
+		current := m.maxLatencyNs.Load()
+		if int64(latency) <= current || m.maxLatencyNs.CompareAndSwap(current, int64(latency)) {
+			break
+		}
+	}
+
+	bucket := len(latencyBucketsMs)
+	for i, bound := range latencyBucketsMs {
+		if latency <= time.Duration(bound)*time.Millisecond {
+			bucket = i
+			break
+		}
+	}
+	m.buckets[bucket].Add(1)
+}
+
+// snapshot returns the current metrics of a server
+func (m *serverMetrics) snapshot(serverName string) ServerMetrics {
+	snapshot := ServerMetrics{
+		Name:             serverName,
+		Calls:            m.calls.Load(),
+		Successes:        m.successes.Load(),
+		Errors:           m.errors.Load(),
+		MaxLatencyMs:     nsToMs(m.maxLatencyNs.Load()),
+		LatencyHistogram: make([]LatencyBucket, 0, len(m.buckets)),
+	}
+	if snapshot.Calls > 0 {
+		snapshot.AverageLatencyMs = nsToMs(m.totalLatencyNs.Load()) / float64(snapshot.Calls)
+	}
+	for i := range m.buckets {
+		le := "+Inf"
+		if i < len(latencyBucketsMs) {
+			le = fmt.Sprintf("%dms", latencyBucketsMs[i])
+		}
+		snapshot.LatencyHistogram = append(snapshot.LatencyHistogram, LatencyBucket{Le: le, Count: m.buckets[i].Load()})
+	}
+	return snapshot
+}
+
+// nsToMs converts nanoseconds to fractional milliseconds
+func nsToMs(ns int64) float64 {
+	return float64(ns) / float64(time.Millisecond)
+}
+
+// metricsFor returns the metrics of a server, creating them if needed
+func (a *MCPAggregator) metricsFor(serverName string) *serverMetrics {
+	a.mu.RLock()
+	metrics, exists := a.metrics[serverName]
+	a.mu.RUnlock()
+	if exists {
+		return metrics
+	}
+
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	if metrics, exists = a.metrics[serverName]; !exists {
+		metrics = &serverMetrics{}
+		a.metrics[serverName] = metrics
+	}
+	return metrics
+}
+
+// Metrics reports the tool call metrics of every configured server, in config order
+func (a *MCPAggregator) Metrics() []ServerMetrics {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	snapshots := make([]ServerMetrics, 0, len(a.cfg.Servers))
+	for _, serverCfg := range a.cfg.Servers {
+		metrics, exists := a.metrics[serverCfg.Name]
+		if !exists {
+			metrics = &serverMetrics{}
+		}
+		snapshots = append(snapshots, metrics.snapshot(serverCfg.Name))
+	}
+	return snapshots
+}
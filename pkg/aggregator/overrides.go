@@ -0,0 +1,68 @@
+package aggregator
+
+import (
+	"encoding/json"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// applyToolOverride replaces the description and input schema of an exposed tool with the ones
+// configured for it. The overridden description is used as is, without the description template.
+// Arguments with a default aren't required from clients anymore.
+func applyToolOverride(tool *mcp.Tool, override config.ToolOverride) error {
+	if override.Description != "" {
+		tool.Description = override.Description
+	}
+	if override.InputSchema != nil {
+		if err := overrideInputSchema(tool, override.InputSchema); err != nil {
+			return err
+		}
+	}
+	if len(override.ArgumentDefaults) > 0 && len(tool.InputSchema.Required) > 0 {
+		var required []string
+		for _, name := range tool.InputSchema.Required {
+			if _, defaulted := override.ArgumentDefaults[name]; !defaulted {
+				required = append(required, name)
+			}
+		}
+		tool.InputSchema.Required = required
+	}
+	return nil
+}
+
+// overrideInputSchema replaces the input schema of an exposed tool
+func overrideInputSchema(tool *mcp.Tool, inputSchema map[string]interface{}) error {
+	data, err := json.Marshal(inputSchema)
+	if err != nil {
+		return fmt.Errorf("failed to encode inputSchema override of tool %s: %w", tool.Name, err)
+	}
+	var schema mcp.ToolInputSchema
+	if err := json.Unmarshal(data, &schema); err != nil {
+		return fmt.Errorf("failed to decode inputSchema override of tool %s: %w", tool.Name, err)
+	}
+	tool.InputSchema = schema
+	return nil
+}
+
+// withArgumentDefaults merges the configured default arguments of a tool under the arguments of a call.
+// Arguments the client gave win, and objects both have are merged the same way, key by key.
+func withArgumentDefaults(arguments, defaults map[string]interface{}) map[string]interface{} {
+	if len(defaults) == 0 {
+		return arguments
+	}
+	merged := make(map[string]interface{}, len(defaults)+len(arguments))
+	for name, value := range defaults {
+		merged[name] = value
+	}
+	for name, value := range arguments {
+		given, isObject := value.(map[string]interface{})
+		defaulted, defaultIsObject := merged[name].(map[string]interface{})
+		if isObject && defaultIsObject {
+			value = withArgumentDefaults(given, defaulted)
+		}
+		merged[name] = value
+	}
+	return merged
+}
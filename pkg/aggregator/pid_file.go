@@ -0,0 +1,209 @@
+package aggregator
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"strconv"
+	"strings"
+	"sync"
+	"syscall"
+
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// pidRecord is the content of the pid file: the aggregator's process and the servers it started,
+// by the process each server runs in and leads the process group of
+type pidRecord struct {
+	AggregatorPID   int                      `json:"aggregatorPid"`
+	AggregatorStart string                   `json:"aggregatorStart,omitempty"`
+	Servers         map[string]serverProcess `json:"servers"`
+}
+
+// serverProcess identifies the process of a server by its pid and its start time, so a process the
+// system gave the pid of an exited server later isn't taken for the server
+type serverProcess struct {
+	PID   int    `json:"pid"`
+	Start string `json:"start,omitempty"` // As processStartTime reports it, empty if it couldn't be told
+}
+
+// pidFile keeps the processes of the running servers on disk, so the next run can reap the ones
+// left behind when the aggregator crashes or is killed before it shuts them down
+type pidFile struct {
+	path    string
+	mu      sync.Mutex
+	servers map[string]serverProcess
+}
+
+// newPidFile reaps the servers a previous aggregator left running in the pid file at path and
+// takes the file over. A nil pidFile, for no path, records nothing.
+func newPidFile(path string) *pidFile {
+	if path == "" {
+		return nil
+	}
+	reapOrphans(path)
+	f := &pidFile{path: path, servers: make(map[string]serverProcess)}
+	f.save()
+	return f
+}
+
+// add records the process of a started server
+func (f *pidFile) add(serverName string, pid int) {
+	if f == nil {
+		return
+	}
+	start, err := processStartTime(pid)
+	if err != nil {
+		logger.Error("Warning: can't tell when the process %d of server %s started, it won't be reaped: %v", pid, serverName, err)
+	}
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	f.servers[serverName] = serverProcess{PID: pid, Start: start}
+	f.save()
+}
+
+// remove forgets the process of a server once it was shut down, unless the server runs in another
+// process by now
+func (f *pidFile) remove(serverName string, pid int) {
+	if f == nil {
+		return
+	}
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	if f.servers[serverName].PID != pid {
+		return
+	}
+	delete(f.servers, serverName)
+	f.save()
+}
+
+// close deletes the pid file after every server was shut down
+func (f *pidFile) close() {
+	if f == nil {
+		return
+	}
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	if err := os.Remove(f.path); err != nil && !errors.Is(err, os.ErrNotExist) {
+		logger.Error("Failed to remove pid file %s: %v", f.path, err)
+	}
+}
+
+// save writes the recorded processes to the pid file; f.mu must be held
+func (f *pidFile) save() {
+	aggregatorStart, _ := processStartTime(os.Getpid())
+	record := pidRecord{AggregatorPID: os.Getpid(), AggregatorStart: aggregatorStart, Servers: f.servers}
+	if err := writePidRecord(f.path, record); err != nil {
+		logger.Error("Failed to write pid file %s: %v", f.path, err)
+	}
+}
+
+// writePidRecord replaces the pid file at once, so a crash while writing never leaves a truncated file
+func writePidRecord(path string, record pidRecord) error {
+	data, err := json.MarshalIndent(record, "", "  ")
+	if err != nil {
+		return fmt.Errorf("failed to encode pid file: %w", err)
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0755); err != nil {
+		return fmt.Errorf("failed to create pid file directory: %w", err)
+	}
+	tmp := path + ".tmp"
+	if err := os.WriteFile(tmp, data, 0644); err != nil {
+		return err
+	}
+	return os.Rename(tmp, path)
+}
+
+// reapOrphans kills the process groups of the servers recorded in the pid file at path, unless the
+// aggregator that recorded them still runs. A recorded process is only killed while it's still the one
+// that started at the recorded time, so processes that got a pid of the record later are left alone.
+// A missing file has nothing to reap.
+func reapOrphans(path string) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		if !errors.Is(err, os.ErrNotExist) {
+			logger.Error("Failed to read pid file %s: %v", path, err)
+		}
+		return
+	}
+
+	var record pidRecord
+	if err := json.Unmarshal(data, &record); err != nil {
+		logger.Error("Ignoring malformed pid file %s: %v", path, err)
+		return
+	}
+	if record.AggregatorPID != os.Getpid() && processRunning(record.AggregatorPID) &&
+		(record.AggregatorStart == "" || isProcess(record.AggregatorPID, record.AggregatorStart)) {
+		logger.Error("Warning: pid file %s belongs to the running aggregator %d, leaving its servers alone", path, record.AggregatorPID)
+		return
+	}
+
+	for serverName, process := range record.Servers {
+		if !processRunning(process.PID) {
+			continue
+		}
+		if !isProcess(process.PID, process.Start) {
+			logger.Error("Warning: not killing process %d recorded for server %s, it isn't the process the server started in", process.PID, serverName)
+			continue
+		}
+		// The server led its process group, so the processes it started go with it
+		if err := syscall.Kill(-process.PID, syscall.SIGKILL); err == nil {
+			logger.Info("Killed server %s (pid %d), left running by a previous aggregator", serverName, process.PID)
+		}
+	}
+}
+
+// isProcess reports whether the process with the pid started at the given time, false if the time is unknown
+func isProcess(pid int, start string) bool {
+	if start == "" {
+		return false
+	}
+	current, err := processStartTime(pid)
+	return err == nil && current == start
+}
+
+// processStartTime returns when the process with the pid started, as the system tells it: the start time
+// in clock ticks after boot from /proc on Linux, and the start time ps reports elsewhere
+func processStartTime(pid int) (string, error) {
+	if runtime.GOOS == "linux" {
+		data, err := os.ReadFile(fmt.Sprintf("/proc/%d/stat", pid))
+		if err != nil {
+			return "", err
+		}
+		// The command name in parentheses may contain spaces, so the fields are counted after it.
+		// The start time is the 22nd field of the line, the 20th after the command name.
+		end := bytes.LastIndexByte(data, ')')
+		if end < 0 {
+			return "", fmt.Errorf("malformed /proc/%d/stat", pid)
+		}
+		fields := strings.Fields(string(data[end+1:]))
+		if len(fields) < 20 {
+			return "", fmt.Errorf("malformed /proc/%d/stat", pid)
+		}
+		return fields[19], nil
+	}
+
+	output, err := exec.Command("ps", "-o", "lstart=", "-p", strconv.Itoa(pid)).Output()
+	if err != nil {
+		return "", fmt.Errorf("failed to run ps: %w", err)
+	}
+	start := strings.TrimSpace(string(output))
+	if start == "" {
+		return "", fmt.Errorf("no process %d", pid)
+	}
+	return start, nil
+}
+
+// processRunning reports whether a process with the pid exists, whoever it belongs to
+func processRunning(pid int) bool {
+	if pid <= 0 {
+		return false
+	}
+	err := syscall.Kill(pid, 0)
+	return err == nil || errors.Is(err, syscall.EPERM)
+}
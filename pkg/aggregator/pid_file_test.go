@@ -0,0 +1,137 @@
+package aggregator
+
+import (
+	"encoding/json"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"syscall"
+	"testing"
+	"time"
+)
+
+// startOrphan starts a long running process leading its own process group, like a server
+func startOrphan(t *testing.T) *exec.Cmd {
+	t.Helper()
+	cmd := exec.Command("sleep", "60")
+	cmd.SysProcAttr = &syscall.SysProcAttr{Setpgid: true}
+	if err := cmd.Start(); err != nil {
+		t.Skipf("Can't start sleep: %v", err)
+	}
+	// exited waits for the process, whether it was reaped or killed here
+	t.Cleanup(func() { cmd.Process.Kill() })
+	return cmd
+}
+
+// exited reports whether the process exits within a short while
+func exited(cmd *exec.Cmd) bool {
+	waited := make(chan struct{})
+	go func() {
+		cmd.Process.Wait()
+		close(waited)
+	}()
+	select {
+	case <-waited:
+		return true
+	case <-time.After(2 * time.Second):
+		return false
+	}
+}
+
+func TestReapOrphans(t *testing.T) {
+	// A process that already exited stands for the crashed aggregator
+	gone := exec.Command("true")
+	if err := gone.Run(); err != nil {
+		t.Skipf("Can't run true: %v", err)
+	}
+	parentStart, err := processStartTime(os.Getppid())
+	if err != nil {
+		t.Skipf("Can't tell when processes started: %v", err)
+	}
+
+	tests := []struct {
+		name            string
+		aggregatorPID   int
+		aggregatorStart string
+		recycled        bool
+		wantReaped      bool
+	}{
+		{name: "Crashed aggregator", aggregatorPID: gone.Process.Pid, wantReaped: true},
+		{name: "Running aggregator", aggregatorPID: os.Getppid(), aggregatorStart: parentStart, wantReaped: false},
+		{name: "Aggregator pid taken by another process", aggregatorPID: os.Getppid(), aggregatorStart: "0", wantReaped: true},
+		{name: "Server pid taken by another process", aggregatorPID: gone.Process.Pid, recycled: true, wantReaped: false},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			orphan := startOrphan(t)
+			start, err := processStartTime(orphan.Process.Pid)
+			if err != nil {
+				t.Fatalf("processStartTime() error = %v", err)
+			}
+			if tt.recycled {
+				// The recorded server started at another time than the process running under its pid
+				start = "0"
+			}
+
+			path := filepath.Join(t.TempDir(), "combine-mcp.pid")
+			record := pidRecord{
+				AggregatorPID:   tt.aggregatorPID,
+				AggregatorStart: tt.aggregatorStart,
+				Servers:         map[string]serverProcess{"orphan": {PID: orphan.Process.Pid, Start: start}},
+			}
+			if err := writePidRecord(path, record); err != nil {
+				t.Fatalf("writePidRecord() error = %v", err)
+			}
+
+			reapOrphans(path)
+			if got := exited(orphan); got != tt.wantReaped {
+				t.Errorf("Server process exited = %v, want %v", got, tt.wantReaped)
+			}
+		})
+	}
+}
+
+func TestPidFile(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "state", "combine-mcp.pid")
+	readServers := func() map[string]serverProcess {
+		t.Helper()
+		data, err := os.ReadFile(path)
+		if err != nil {
+			t.Fatalf("ReadFile() error = %v", err)
+		}
+		var record pidRecord
+		if err := json.Unmarshal(data, &record); err != nil {
+			t.Fatalf("Unmarshal() error = %v", err)
+		}
+		if record.AggregatorPID != os.Getpid() {
+			t.Errorf("aggregatorPid = %d, want %d", record.AggregatorPID, os.Getpid())
+		}
+		return record.Servers
+	}
+
+	f := newPidFile(path)
+	f.add("github", 1001)
+	f.add("gitlab", 1002)
+	if servers := readServers(); len(servers) != 2 || servers["github"].PID != 1001 {
+		t.Errorf("Recorded servers = %v, want github and gitlab", servers)
+	}
+
+	// A server restarted in another process keeps its new one
+	f.add("github", 1003)
+	f.remove("github", 1001)
+	f.remove("gitlab", 1002)
+	if servers := readServers(); len(servers) != 1 || servers["github"].PID != 1003 {
+		t.Errorf("Recorded servers = %v, want github with pid 1003", servers)
+	}
+
+	f.close()
+	if _, err := os.Stat(path); !os.IsNotExist(err) {
+		t.Errorf("Pid file still exists after close, Stat() error = %v", err)
+	}
+
+	// Without a path nothing is recorded
+	none := newPidFile("")
+	none.add("github", 1001)
+	none.close()
+}
@@ -0,0 +1,108 @@
+package aggregator
+
+import (
+	"context"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+type promptMapping struct {
+	serverName   string
+	originalName string
+	prompt       mcp.Prompt // Prompt as listed by the server
+}
+
+// discoverPrompts lists the prompts of a server that supports them and registers them with a prefix
+func (a *MCPAggregator) discoverPrompts(ctx context.Context, serverName string) error {
+	a.mu.RLock()
+	mcpClient, exists := a.clients[serverName]
+	supported := a.capabilities[serverName].Prompts != nil
+	a.mu.RUnlock()
+
+	if !exists {
+		return fmt.Errorf("client for server %s not found", serverName)
+	}
+	if !supported {
+		logger.Debug("Server %s doesn't provide prompts", serverName)
+		return nil
+	}
+
+	logger.Debug("Discovering prompts for server %s...", serverName)
+	promptsResp, err := mcpClient.ListPrompts(ctx, mcp.ListPromptsRequest{})
+	if err != nil {
+		return fmt.Errorf("failed to list prompts for server %s: %w", serverName, err)
+	}
+	logger.Debug("Found %d prompts for server %s", len(promptsResp.Prompts), serverName)
+
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	for _, prompt := range promptsResp.Prompts {
+		prefixedName := a.prefixedNameLocked(serverName, prompt.Name)
+		if existing, exists := a.prompts[prefixedName]; exists && existing.serverName != serverName {
+			logger.Error("Prompt %s of server %s collides with prompt %s of server %s as %s, skipping it", prompt.Name, serverName, existing.originalName, existing.serverName, prefixedName)
+			continue
+		}
+		a.prompts[prefixedName] = promptMapping{
+			serverName:   serverName,
+			originalName: prompt.Name,
+			prompt:       prompt,
+		}
+	}
+	return nil
+}
+
+// GetPrompts returns the prompts of all servers with prefixed names
+func (a *MCPAggregator) GetPrompts() []mcp.Prompt {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	var allPrompts []mcp.Prompt
+	for prefixedName, mapping := range a.prompts {
+		if a.cfg.HideUnhealthyTools && !a.isHealthyLocked(mapping.serverName) {
+			continue
+		}
+
+		prompt := mapping.prompt
+		prompt.Name = prefixedName
+		if prompt.Description != "" {
+			prompt.Description = fmt.Sprintf("[%s] %s", mapping.serverName, prompt.Description)
+		}
+		allPrompts = append(allPrompts, prompt)
+	}
+	return allPrompts
+}
+
+// GetPrompt gets a prompt from the server that owns it, passing the arguments through
+func (a *MCPAggregator) GetPrompt(ctx context.Context, request mcp.GetPromptRequest) (*mcp.GetPromptResult, error) {
+	a.mu.RLock()
+	prefixedName := request.Params.Name
+	mapping, exists := a.prompts[prefixedName]
+	mcpClient, clientExists := a.clients[mapping.serverName]
+	a.mu.RUnlock()
+
+	if !exists {
+		return nil, fmt.Errorf("prompt %s not found", prefixedName)
+	}
+	if !clientExists {
+		return nil, fmt.Errorf("client for server %s not found", mapping.serverName)
+	}
+
+	logger.Debug("Getting prompt %s on server %s (mapped from %s)", mapping.originalName, mapping.serverName, prefixedName)
+
+	newRequest := request
+	newRequest.Params.Name = mapping.originalName
+	result, err := mcpClient.GetPrompt(ctx, newRequest)
+	a.recordCallResult(mapping.serverName, err)
+	return result, err
+}
+
+// removePromptsLocked forgets every prompt of a server
+func (a *MCPAggregator) removePromptsLocked(serverName string) {
+	for prefixedName, mapping := range a.prompts {
+		if mapping.serverName == serverName {
+			delete(a.prompts, prefixedName)
+		}
+	}
+}
@@ -0,0 +1,59 @@
+package aggregator
+
+import "github.com/mark3labs/mcp-go/mcp"
+
+// SupportedProtocolVersions lists the MCP protocol versions the aggregator speaks, newest first
+var SupportedProtocolVersions = []string{
+	mcp.LATEST_PROTOCOL_VERSION,
+}
+
+// IsSupportedProtocolVersion reports whether the aggregator speaks the given protocol version
+func IsSupportedProtocolVersion(version string) bool {
+	for _, supported := range SupportedProtocolVersions {
+		if version == supported {
+			return true
+		}
+	}
+	return false
+}
+
+// NegotiateProtocolVersion picks the version to answer an initialize request with: the requested one
+// when it is supported, otherwise the newest supported one, leaving it to the client to disconnect
+func NegotiateProtocolVersion(requested string) string {
+	if IsSupportedProtocolVersion(requested) {
+		return requested
+	}
+	return SupportedProtocolVersions[0]
+}
+
+// aggregatorClientInfo identifies the aggregator to servers until a client has initialized it
+var aggregatorClientInfo = mcp.Implementation{
+	Name:    "mcp-aggregator",
+	Version: "1.0.0",
+}
+
+// SetUpstreamClient remembers the client that initialized the aggregator, so servers started
+// from then on are initialized with the client's info and the capabilities the aggregator can relay
+func (a *MCPAggregator) SetUpstreamClient(info mcp.Implementation, capabilities mcp.ClientCapabilities) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.upstreamInfo = &info
+	a.upstreamCapabilities = relayedCapabilities(capabilities)
+}
+
+// childHandshake returns the client info and capabilities to initialize a server with
+func (a *MCPAggregator) childHandshake() (mcp.Implementation, mcp.ClientCapabilities) {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+	if a.upstreamInfo == nil {
+		// Without a client to ask yet, sampling is offered and refused later if the client can't sample
+		return aggregatorClientInfo, mcp.ClientCapabilities{Sampling: &struct{}{}}
+	}
+	return *a.upstreamInfo, a.upstreamCapabilities
+}
+
+// relayedCapabilities keeps the client capabilities whose requests the aggregator can pass between
+// servers and the client; advertising others would make servers send requests nobody answers
+func relayedCapabilities(capabilities mcp.ClientCapabilities) mcp.ClientCapabilities {
+	return mcp.ClientCapabilities{Sampling: capabilities.Sampling}
+}
@@ -0,0 +1,28 @@
+package aggregator
+
+import (
+	"testing"
+
+	"github.com/mark3labs/mcp-go/mcp"
+)
+
+func TestChildHandshake(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	if info, _ := agg.childHandshake(); info != aggregatorClientInfo {
+		t.Errorf("Client info before a client initialized = %+v, want %+v", info, aggregatorClientInfo)
+	}
+
+	upstream := mcp.Implementation{Name: "cursor", Version: "0.48.0"}
+	capabilities := mcp.ClientCapabilities{Experimental: map[string]interface{}{"canvas": true}}
+	agg.SetUpstreamClient(upstream, capabilities)
+
+	info, relayed := agg.childHandshake()
+	if info != upstream {
+		t.Errorf("Client info = %+v, want the upstream client's %+v", info, upstream)
+	}
+	if relayed.Experimental != nil {
+		t.Errorf("Experimental capabilities %v were advertised to servers, but the aggregator can't relay them", relayed.Experimental)
+	}
+}
@@ -0,0 +1,100 @@
+package aggregator
+
+import (
+	"context"
+	"fmt"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+const (
+	// defaultReadyTimeout bounds how long a server with a ready check may take to get ready
+	defaultReadyTimeout = 60 * time.Second
+	// readyCheckInterval is the pause between attempts of a ready check tool call
+	readyCheckInterval = 500 * time.Millisecond
+)
+
+// waitUntilReady runs the server's ready check, if any. The tool call is retried until it succeeds or the
+// check times out.
+func (a *MCPAggregator) waitUntilReady(ctx context.Context, serverName string) error {
+	a.mu.RLock()
+	mcpClient, exists := a.clients[serverName]
+	serverConfig := a.configs[serverName]
+	a.mu.RUnlock()
+
+	if serverConfig == nil || serverConfig.ReadyCheck == nil {
+		return nil
+	}
+	if !exists {
+		return fmt.Errorf("client for server %s not found", serverName)
+	}
+	check := serverConfig.ReadyCheck
+
+	timeout := defaultReadyTimeout
+	if check.TimeoutMs > 0 {
+		timeout = time.Duration(check.TimeoutMs) * time.Millisecond
+	}
+	ctx, cancel := context.WithTimeout(ctx, timeout)
+	defer cancel()
+
+	logger.Debug("Waiting for server %s to get ready...", serverName)
+	if err := sleepContext(ctx, time.Duration(check.DelayMs)*time.Millisecond); err != nil {
+		return fmt.Errorf("server %s wasn't ready within %v", serverName, timeout)
+	}
+	if check.Tool == "" {
+		logger.Info("Server %s is ready", serverName)
+		return nil
+	}
+
+	request := mcp.CallToolRequest{}
+	request.Params.Name = check.Tool
+	request.Params.Arguments = check.Arguments
+	for attempt := 1; ; attempt++ {
+		result, err := mcpClient.CallTool(ctx, request)
+		if err == nil && !result.IsError {
+			logger.Info("Server %s is ready after %d ready check calls of %s", serverName, attempt, check.Tool)
+			return nil
+		}
+		if err == nil {
+			err = fmt.Errorf("%s returned an error result", check.Tool)
+		}
+		logger.ServerDebug(serverName, "Server %s isn't ready yet: %v", serverName, err)
+
+		if sleepContext(ctx, readyCheckInterval) != nil {
+			return fmt.Errorf("server %s wasn't ready within %v: %w", serverName, timeout, err)
+		}
+	}
+}
+
+// stopWarming exposes the tools of a server that was hidden while it got ready, or failed to
+func (a *MCPAggregator) stopWarming(serverName string) {
+	a.mu.Lock()
+	delete(a.warming, serverName)
+	a.toolsCache = nil
+	a.mu.Unlock()
+	a.notifyToolsChanged()
+}
+
+// sleepContext waits for the duration unless the context is done first
+func sleepContext(ctx context.Context, duration time.Duration) error {
+	timer := time.NewTimer(duration)
+	defer timer.Stop()
+	select {
+	case <-timer.C:
+		return nil
+	case <-ctx.Done():
+		return ctx.Err()
+	}
+}
+
+// anyProviderReadyLocked reports whether any server providing an exposed tool is done with its ready check
+func (a *MCPAggregator) anyProviderReadyLocked(mapping toolMapping) bool {
+	for _, serverName := range toolProviders(mapping) {
+		if !a.warming[serverName] {
+			return true
+		}
+	}
+	return false
+}
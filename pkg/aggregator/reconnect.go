@@ -0,0 +1,126 @@
+package aggregator
+
+import (
+	"context"
+	"fmt"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+const (
+	// defaultReconnectBackoff is the delay before the first reconnect to a remote server, doubled for every further one
+	defaultReconnectBackoff = time.Second
+	// defaultMaxReconnectBackoff caps the delay between reconnects
+	defaultMaxReconnectBackoff = 30 * time.Second
+	// connectionCheckInterval is how often a remote server is pinged to notice a lost connection
+	connectionCheckInterval = 10 * time.Second
+	// connectionCheckTimeout bounds a single ping
+	connectionCheckTimeout = 5 * time.Second
+)
+
+// pinger is implemented by clients that can check their connection to the server
+type pinger interface {
+	Ping(ctx context.Context) error
+}
+
+// pingClient checks the connection of a client within timeout, clients that can't be pinged are assumed connected
+func pingClient(c MCPClient, timeout time.Duration) error {
+	if bounded, ok := c.(*boundedClient); ok {
+		c = bounded.MCPClient
+	}
+	p, ok := c.(pinger)
+	if !ok {
+		return nil
+	}
+
+	ctx, cancel := context.WithTimeout(context.Background(), timeout)
+	defer cancel()
+	return p.Ping(ctx)
+}
+
+// superviseConnection pings a remote server and reconnects it once the connection is lost
+func (a *MCPAggregator) superviseConnection(serverCfg *config.ServerConfig, mcpClient MCPClient) {
+	interval := connectionCheckInterval
+	if serverCfg.PingIntervalMs > 0 {
+		interval = time.Duration(serverCfg.PingIntervalMs) * time.Millisecond
+	}
+	ticker := time.NewTicker(interval)
+	defer ticker.Stop()
+
+	for {
+		select {
+		case <-a.done:
+			return
+		case <-ticker.C:
+		}
+
+		// Dropping or replacing the server ends its supervision
+		a.mu.RLock()
+		current := a.clients[serverCfg.Name]
+		a.mu.RUnlock()
+		if current != mcpClient {
+			return
+		}
+
+		if err := pingClient(mcpClient, pingTimeout(interval)); err != nil {
+			a.reconnectServer(serverCfg, mcpClient, err)
+			return
+		}
+		a.recordPing(serverCfg.Name)
+	}
+}
+
+// reconnectServer connects to a remote server again with backoff until it succeeds. The server's tools stay
+// listed meanwhile, but calls to them fail fast, and everything is discovered again once it is back.
+func (a *MCPAggregator) reconnectServer(serverCfg *config.ServerConfig, mcpClient MCPClient, cause error) {
+	backoff := defaultReconnectBackoff
+	if serverCfg.ReconnectBackoffMs > 0 {
+		backoff = time.Duration(serverCfg.ReconnectBackoffMs) * time.Millisecond
+	}
+	maxBackoff := defaultMaxReconnectBackoff
+	if serverCfg.MaxReconnectBackoffMs > 0 {
+		maxBackoff = time.Duration(serverCfg.MaxReconnectBackoffMs) * time.Millisecond
+	}
+
+	a.recordServerError(serverCfg.Name, fmt.Errorf("connection lost: %w", cause))
+	a.mu.Lock()
+	a.unavailable[serverCfg.Name] = true
+	a.mu.Unlock()
+	mcpClient.Close()
+
+	for attempt := 1; ; attempt++ {
+		logger.Error("Warning: lost connection to server %s (%v), reconnecting in %v (attempt %d)", serverCfg.Name, cause, backoff, attempt)
+
+		select {
+		case <-a.done:
+			return
+		case <-time.After(backoff):
+		}
+
+		// A config reload may have removed or replaced the server meanwhile, while a failed attempt leaves no client
+		a.mu.RLock()
+		current, connected := a.clients[serverCfg.Name]
+		currentCfg := a.configs[serverCfg.Name]
+		a.mu.RUnlock()
+		if currentCfg != serverCfg || connected && current != mcpClient {
+			return
+		}
+
+		err := a.startServer(context.Background(), serverCfg)
+		if err == nil {
+			logger.Info("Reconnected to server %s", serverCfg.Name)
+			a.applyRoutes(context.Background())
+			a.notifyToolsChanged()
+			return
+		}
+		a.recordServerError(serverCfg.Name, err)
+		cause = err
+
+		backoff *= 2
+		if backoff > maxBackoff {
+			backoff = maxBackoff
+		}
+	}
+}
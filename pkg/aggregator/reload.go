@@ -0,0 +1,130 @@
+package aggregator
+
+import (
+	"context"
+	"reflect"
+	"sort"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+	"github.com/nazar256/combine-mcp/pkg/redact"
+)
+
+// ReloadSummary names the servers a config reload added, removed and restarted because their entry changed
+type ReloadSummary struct {
+	Added     []string `json:"added"`
+	Removed   []string `json:"removed"`
+	Restarted []string `json:"restarted"`
+}
+
+// Empty reports whether the reload changed no server
+func (r ReloadSummary) Empty() bool {
+	return len(r.Added) == 0 && len(r.Removed) == 0 && len(r.Restarted) == 0
+}
+
+// Reload applies the servers of a reloaded config: added servers are started, removed ones shut down and
+// the ones whose entry changed restarted, while unchanged servers keep running. Servers start after the
+// servers they depend on. Top-level options keep the values they were started with. It returns the servers
+// that were added, removed or restarted, or an error for a config whose servers can't be started, which is
+// then ignored.
+func (a *MCPAggregator) Reload(ctx context.Context, cfg *config.Config) (ReloadSummary, error) {
+	summary := ReloadSummary{Added: []string{}, Removed: []string{}, Restarted: []string{}}
+	order, err := cfg.StartOrder()
+	if err != nil {
+		return summary, err
+	}
+
+	a.mu.Lock()
+	previous := make(map[string]config.ServerConfig, len(a.cfg.Servers))
+	for _, serverCfg := range a.cfg.Servers {
+		previous[serverCfg.Name] = serverCfg
+	}
+	updated := *a.cfg
+	updated.Servers = cfg.Servers
+	a.cfg = &updated
+	a.mu.Unlock()
+
+	var stopped []string
+	var started []*config.ServerConfig
+	kept := make(map[string]bool, len(cfg.Servers))
+	for _, serverCfg := range order {
+		kept[serverCfg.Name] = true
+		old, exists := previous[serverCfg.Name]
+		switch {
+		case !exists:
+			logger.Info("Server %s was added to the config", serverCfg.Name)
+			summary.Added = append(summary.Added, serverCfg.Name)
+		case !reflect.DeepEqual(old, *serverCfg):
+			logger.Info("Server %s changed in the config, restarting it", serverCfg.Name)
+			stopped = append(stopped, serverCfg.Name)
+			summary.Restarted = append(summary.Restarted, serverCfg.Name)
+		default:
+			continue
+		}
+		started = append(started, serverCfg)
+	}
+	for name := range previous {
+		if !kept[name] {
+			logger.Info("Server %s was removed from the config", name)
+			stopped = append(stopped, name)
+			summary.Removed = append(summary.Removed, name)
+		}
+	}
+	sort.Strings(summary.Removed)
+	if summary.Empty() {
+		logger.Info("Reloaded config has no server changes")
+		return summary, nil
+	}
+
+	// Clients keep seeing the tools of restarted servers until they are back
+	for _, serverCfg := range started {
+		if _, existed := previous[serverCfg.Name]; existed {
+			a.keepStaleTools(serverCfg.Name)
+		}
+	}
+	for _, name := range stopped {
+		a.forgetServer(name)
+	}
+
+	for _, serverCfg := range started {
+		redact.RegisterEnv(serverCfg.Env)
+		a.mu.Lock()
+		a.configs[serverCfg.Name] = serverCfg
+		a.mu.Unlock()
+
+		if serverCfg.Lazy {
+			a.endStaleTools(serverCfg.Name)
+			a.deferServer(serverCfg, false)
+			continue
+		}
+		if err := a.startServer(ctx, serverCfg); err != nil {
+			a.recordServerError(serverCfg.Name, err)
+			logger.Error("Failed to start server %s after reloading the config: %v", serverCfg.Name, err)
+		}
+		a.endStaleTools(serverCfg.Name)
+	}
+
+	a.applyRoutes(ctx)
+	a.notifyToolsChanged()
+	return summary, nil
+}
+
+// forgetServer shuts a server down for good and drops everything known about it
+func (a *MCPAggregator) forgetServer(serverName string) {
+	// Closing the client ends its supervision, so the server isn't restarted
+	a.removeClient(serverName)
+
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	delete(a.configs, serverName)
+	delete(a.discovered, serverName)
+	delete(a.health, serverName)
+	delete(a.restarts, serverName)
+	remaining := a.lazy[:0:0]
+	for _, server := range a.lazy {
+		if server.config.Name != serverName {
+			remaining = append(remaining, server)
+		}
+	}
+	a.lazy = remaining
+}
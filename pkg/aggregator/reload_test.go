@@ -0,0 +1,82 @@
+package aggregator
+
+import (
+	"context"
+	"reflect"
+	"sync/atomic"
+	"testing"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestReload(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	kept := helperServerConfig("kept", "")
+	changed := helperServerConfig("changed", "")
+	removed := helperServerConfig("removed", "")
+	cfg := &config.Config{Servers: []config.ServerConfig{kept, changed, removed}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	var notified atomic.Int32
+	agg.OnToolsChanged(func() { notified.Add(1) })
+
+	agg.mu.RLock()
+	keptClient, changedClient := agg.clients["kept"], agg.clients["changed"]
+	agg.mu.RUnlock()
+
+	changed.Env = map[string]string{"EXTRA": "1"}
+	for key, value := range helperServerConfig("changed", "").Env {
+		changed.Env[key] = value
+	}
+	added := helperServerConfig("added", "")
+	summary, err := agg.Reload(context.Background(), &config.Config{Servers: []config.ServerConfig{kept, changed, added}})
+	if err != nil {
+		t.Fatalf("Reload() error = %v", err)
+	}
+	want := ReloadSummary{Added: []string{"added"}, Removed: []string{"removed"}, Restarted: []string{"changed"}}
+	if !reflect.DeepEqual(summary, want) {
+		t.Errorf("Reload() = %+v, want %+v", summary, want)
+	}
+	if got := notified.Load(); got != 1 {
+		t.Errorf("Tools changed was notified %d times, want 1", got)
+	}
+
+	agg.mu.RLock()
+	defer agg.mu.RUnlock()
+	if agg.clients["kept"] != keptClient {
+		t.Errorf("Unchanged server kept was restarted")
+	}
+	if current := agg.clients["changed"]; current == nil || current == changedClient {
+		t.Errorf("Changed server wasn't restarted")
+	}
+	if _, exists := agg.clients["removed"]; exists {
+		t.Errorf("Removed server is still running")
+	}
+	for _, name := range []string{"kept_echo", "changed_echo", "added_echo"} {
+		if _, exists := agg.tools[name]; !exists {
+			t.Errorf("Tool %s is missing after the reload", name)
+		}
+	}
+	if _, exists := agg.tools["removed_echo"]; exists {
+		t.Errorf("Tool removed_echo of the removed server is still exposed")
+	}
+}
+
+func TestReloadWithoutChanges(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	reloaded := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if summary, err := agg.Reload(context.Background(), reloaded); err != nil || !summary.Empty() {
+		t.Errorf("Reload() = %+v, %v, want no changes for the same servers", summary, err)
+	}
+}
@@ -0,0 +1,126 @@
+package aggregator
+
+import (
+	"context"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// resourceURISeparator joins a server name and a resource URI into the exposed URI
+const resourceURISeparator = "+"
+
+type resourceMapping struct {
+	serverName  string
+	originalURI string
+	resource    mcp.Resource // Resource as listed by the server
+}
+
+// prefixResourceURI returns the exposed URI of a server's resource, e.g. github+file:///README.md
+func prefixResourceURI(serverName, uri string) string {
+	return serverName + resourceURISeparator + uri
+}
+
+// discoverResources lists the resources of a server that supports them and registers them with prefixed URIs
+func (a *MCPAggregator) discoverResources(ctx context.Context, serverName string) error {
+	a.mu.RLock()
+	mcpClient, exists := a.clients[serverName]
+	supported := a.capabilities[serverName].Resources != nil
+	a.mu.RUnlock()
+
+	if !exists {
+		return fmt.Errorf("client for server %s not found", serverName)
+	}
+	if !supported {
+		logger.Debug("Server %s doesn't provide resources", serverName)
+		return nil
+	}
+
+	logger.Debug("Discovering resources for server %s...", serverName)
+	resourcesResp, err := mcpClient.ListResources(ctx, mcp.ListResourcesRequest{})
+	if err != nil {
+		return fmt.Errorf("failed to list resources for server %s: %w", serverName, err)
+	}
+	logger.Debug("Found %d resources for server %s", len(resourcesResp.Resources), serverName)
+
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	for _, resource := range resourcesResp.Resources {
+		a.resources[prefixResourceURI(serverName, resource.URI)] = resourceMapping{
+			serverName:  serverName,
+			originalURI: resource.URI,
+			resource:    resource,
+		}
+	}
+	return nil
+}
+
+// GetResources returns the resources of all servers with prefixed URIs
+func (a *MCPAggregator) GetResources() []mcp.Resource {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	var allResources []mcp.Resource
+	for prefixedURI, mapping := range a.resources {
+		if a.cfg.HideUnhealthyTools && !a.isHealthyLocked(mapping.serverName) {
+			continue
+		}
+
+		resource := mapping.resource
+		resource.URI = prefixedURI
+		if resource.Description != "" {
+			resource.Description = fmt.Sprintf("[%s] %s", mapping.serverName, resource.Description)
+		}
+		allResources = append(allResources, resource)
+	}
+	return allResources
+}
+
+// ReadResource reads a resource from the server that owns it
+func (a *MCPAggregator) ReadResource(ctx context.Context, request mcp.ReadResourceRequest) (*mcp.ReadResourceResult, error) {
+	a.mu.RLock()
+	prefixedURI := request.Params.URI
+	mapping, exists := a.resources[prefixedURI]
+	mcpClient, clientExists := a.clients[mapping.serverName]
+	a.mu.RUnlock()
+
+	if !exists {
+		return nil, fmt.Errorf("resource %s not found", prefixedURI)
+	}
+	if !clientExists {
+		return nil, fmt.Errorf("client for server %s not found", mapping.serverName)
+	}
+
+	logger.Debug("Reading resource %s on server %s (mapped from %s)", mapping.originalURI, mapping.serverName, prefixedURI)
+
+	newRequest := request
+	newRequest.Params.URI = mapping.originalURI
+	result, err := mcpClient.ReadResource(ctx, newRequest)
+	a.recordCallResult(mapping.serverName, err)
+	if err != nil {
+		return nil, err
+	}
+
+	// Contents carry the server's URIs, which the client only knows in prefixed form
+	for i, contents := range result.Contents {
+		switch c := contents.(type) {
+		case mcp.TextResourceContents:
+			c.URI = prefixResourceURI(mapping.serverName, c.URI)
+			result.Contents[i] = c
+		case mcp.BlobResourceContents:
+			c.URI = prefixResourceURI(mapping.serverName, c.URI)
+			result.Contents[i] = c
+		}
+	}
+	return result, nil
+}
+
+// removeResourcesLocked forgets every resource of a server
+func (a *MCPAggregator) removeResourcesLocked(serverName string) {
+	for prefixedURI, mapping := range a.resources {
+		if mapping.serverName == serverName {
+			delete(a.resources, prefixedURI)
+		}
+	}
+}
@@ -0,0 +1,75 @@
+package aggregator
+
+import (
+	"context"
+	"encoding/json"
+	"testing"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestResources(t *testing.T) {
+	var withResources mcp.ServerCapabilities
+	if err := json.Unmarshal([]byte(`{"resources":{}}`), &withResources); err != nil {
+		t.Fatalf("Failed to decode capabilities: %v", err)
+	}
+
+	agg := NewMCPAggregator()
+	for _, name := range []string{"first", "second"} {
+		agg.clients[name] = &MockClient{
+			Resources: []mcp.Resource{{URI: "file:///README.md", Name: "README", Description: "Project readme"}},
+		}
+		agg.configs[name] = &config.ServerConfig{Name: name, Command: name + "-command"}
+		agg.capabilities[name] = withResources
+		if err := agg.discoverResources(context.Background(), name); err != nil {
+			t.Fatalf("discoverResources(%s) error = %v", name, err)
+		}
+	}
+
+	// A server without the resources capability isn't asked
+	agg.clients["plain"] = &MockClient{Resources: []mcp.Resource{{URI: "file:///hidden"}}}
+	if err := agg.discoverResources(context.Background(), "plain"); err != nil {
+		t.Fatalf("discoverResources(plain) error = %v", err)
+	}
+
+	resources := agg.GetResources()
+	got := make(map[string]string)
+	for _, resource := range resources {
+		got[resource.URI] = resource.Description
+	}
+	want := map[string]string{
+		"first+file:///README.md":  "[first] Project readme",
+		"second+file:///README.md": "[second] Project readme",
+	}
+	if len(got) != len(want) {
+		t.Fatalf("GetResources() = %v, want %v", got, want)
+	}
+	for uri, description := range want {
+		if got[uri] != description {
+			t.Errorf("Resource %s description = %q, want %q", uri, got[uri], description)
+		}
+	}
+
+	request := mcp.ReadResourceRequest{}
+	request.Params.URI = "second+file:///README.md"
+	result, err := agg.ReadResource(context.Background(), request)
+	if err != nil {
+		t.Fatalf("ReadResource() error = %v", err)
+	}
+	contents, ok := result.Contents[0].(mcp.TextResourceContents)
+	if !ok {
+		t.Fatalf("Contents[0] is %T, want mcp.TextResourceContents", result.Contents[0])
+	}
+	if contents.Text != "contents of file:///README.md" {
+		t.Errorf("Server was asked for %q, want the original URI", contents.Text)
+	}
+	if contents.URI != "second+file:///README.md" {
+		t.Errorf("Contents URI = %q, want the prefixed URI", contents.URI)
+	}
+
+	request.Params.URI = "file:///README.md"
+	if _, err := agg.ReadResource(context.Background(), request); err == nil {
+		t.Errorf("ReadResource() accepted an unprefixed URI")
+	}
+}
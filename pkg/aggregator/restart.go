@@ -0,0 +1,123 @@
+package aggregator
+
+import (
+	"context"
+	"fmt"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+const (
+	// defaultMaxRestarts is how often a crashed server is restarted before giving up
+	defaultMaxRestarts = 3
+	// restartBackoff is the delay before the first restart, doubled for every further one
+	restartBackoff = time.Second
+	// maxRestartBackoff caps the delay between restarts
+	maxRestartBackoff = 30 * time.Second
+	// restartResetAfter is how long a server must stay up for its restart count to reset
+	restartResetAfter = time.Minute
+)
+
+// superviseServer waits for a server process to exit and restarts it unless it was stopped on purpose
+func (a *MCPAggregator) superviseServer(serverCfg *config.ServerConfig, mcpClient MCPClient, exited <-chan struct{}) {
+	started := time.Now()
+
+	select {
+	case <-a.done:
+		return
+	case <-exited:
+	}
+
+	// Closing the aggregator or dropping the server also ends the process
+	select {
+	case <-a.done:
+		return
+	default:
+	}
+	a.mu.RLock()
+	current := a.clients[serverCfg.Name]
+	a.mu.RUnlock()
+	if current != mcpClient {
+		return
+	}
+
+	exitStatus := "exit status 0"
+	if err := mcpClient.Close(); err != nil {
+		exitStatus = err.Error()
+	}
+	a.recordServerError(serverCfg.Name, fmt.Errorf("exited unexpectedly: %s", exitStatus))
+	a.restartServer(serverCfg, exitStatus, time.Since(started))
+}
+
+// restartServer respawns a crashed server with backoff until it is running again or out of restarts
+func (a *MCPAggregator) restartServer(serverCfg *config.ServerConfig, exitStatus string, uptime time.Duration) {
+	maxRestarts := serverCfg.MaxRestarts
+	if maxRestarts == 0 {
+		maxRestarts = defaultMaxRestarts
+	}
+
+	a.mu.Lock()
+	if uptime >= restartResetAfter {
+		a.restarts[serverCfg.Name] = 0
+	}
+	a.mu.Unlock()
+
+	// Clients keep seeing the tools of the server while it comes back
+	a.keepStaleTools(serverCfg.Name)
+	defer a.endStaleTools(serverCfg.Name)
+
+	for {
+		a.mu.Lock()
+		attempt := a.restarts[serverCfg.Name]
+		if maxRestarts < 0 || attempt >= maxRestarts {
+			a.mu.Unlock()
+			logger.Error("Server %s exited (%s) and won't be restarted after %d restarts", serverCfg.Name, exitStatus, attempt)
+			a.endStaleTools(serverCfg.Name)
+			a.removeClient(serverCfg.Name)
+			a.notifyToolsChanged()
+			return
+		}
+		a.restarts[serverCfg.Name] = attempt + 1
+		a.mu.Unlock()
+
+		backoff := restartBackoff << attempt
+		if backoff > maxRestartBackoff || backoff <= 0 {
+			backoff = maxRestartBackoff
+		}
+		logger.Error("Warning: server %s exited (%s), restarting in %v (restart %d of %d)", serverCfg.Name, exitStatus, backoff, attempt+1, maxRestarts)
+
+		select {
+		case <-a.done:
+			return
+		case <-time.After(backoff):
+		}
+
+		// A config reload may have removed or replaced the server meanwhile
+		a.mu.RLock()
+		current := a.configs[serverCfg.Name]
+		a.mu.RUnlock()
+		if current != serverCfg {
+			return
+		}
+
+		// Everything is discovered again, so whatever the server no longer lists disappears
+		a.mu.Lock()
+		a.removeToolsLocked(serverCfg.Name)
+		a.removeResourcesLocked(serverCfg.Name)
+		a.removePromptsLocked(serverCfg.Name)
+		a.mu.Unlock()
+
+		err := a.startServer(context.Background(), serverCfg)
+		if err == nil {
+			logger.Info("Server %s restarted", serverCfg.Name)
+			a.applyRoutes(context.Background())
+			a.endStaleTools(serverCfg.Name)
+			a.notifyToolsChanged()
+			return
+		}
+		a.recordServerError(serverCfg.Name, err)
+		exitStatus = err.Error()
+	}
+}
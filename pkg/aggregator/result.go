@@ -0,0 +1,187 @@
+package aggregator
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// resultMetaKey is the _meta entry of a tool result in which the aggregator tells where the result came from
+const resultMetaKey = "combine-mcp"
+
+// rawCallToolResult is a tools/call result before its content blocks are parsed
+type rawCallToolResult struct {
+	Meta    map[string]interface{} `json:"_meta,omitempty"`
+	Content json.RawMessage        `json:"content"`
+	IsError bool                   `json:"isError,omitempty"`
+	Text    *string                `json:"text,omitempty"` // Non-canonical top-level text returned instead of content
+}
+
+// parseCallToolResult converts a server's tools/call result into a canonical CallToolResult.
+// The canonical shape is parsed strictly; the lenient shape also accepts results that are
+// a bare string, content given as a single block or a string, and top-level text.
+func parseCallToolResult(raw json.RawMessage, shape string) (*mcp.CallToolResult, error) {
+	lenient := shape == config.ResultShapeLenient
+
+	if lenient {
+		var text string
+		if err := json.Unmarshal(raw, &text); err == nil {
+			return &mcp.CallToolResult{Content: []mcp.Content{textContent(text)}}, nil
+		}
+	}
+
+	var parsed rawCallToolResult
+	if err := json.Unmarshal(raw, &parsed); err != nil {
+		return nil, fmt.Errorf("malformed result: %w", err)
+	}
+
+	result := &mcp.CallToolResult{IsError: parsed.IsError}
+	result.Meta = parsed.Meta
+
+	content := bytes.TrimSpace(parsed.Content)
+	if lenient && (len(content) == 0 || string(content) == "null") {
+		result.Content = []mcp.Content{}
+		if parsed.Text != nil {
+			result.Content = append(result.Content, textContent(*parsed.Text))
+		}
+		return result, nil
+	}
+
+	blocks, err := parseContentBlocks(content, lenient)
+	if err != nil {
+		return nil, err
+	}
+	result.Content = blocks
+	return result, nil
+}
+
+// parseContentBlocks parses the content field of a tool result
+func parseContentBlocks(content []byte, lenient bool) ([]mcp.Content, error) {
+	if len(content) == 0 {
+		return nil, fmt.Errorf("result has no content")
+	}
+
+	switch content[0] {
+	case '[':
+		var items []json.RawMessage
+		if err := json.Unmarshal(content, &items); err != nil {
+			return nil, fmt.Errorf("malformed content: %w", err)
+		}
+		blocks := make([]mcp.Content, 0, len(items))
+		for _, item := range items {
+			block, err := parseContent(item, lenient)
+			if err != nil {
+				return nil, err
+			}
+			blocks = append(blocks, block)
+		}
+		return blocks, nil
+	case '{':
+		if lenient {
+			block, err := parseContent(content, lenient)
+			if err != nil {
+				return nil, err
+			}
+			return []mcp.Content{block}, nil
+		}
+	case '"':
+		if lenient {
+			var text string
+			if err := json.Unmarshal(content, &text); err != nil {
+				return nil, fmt.Errorf("malformed content: %w", err)
+			}
+			return []mcp.Content{textContent(text)}, nil
+		}
+	}
+
+	return nil, fmt.Errorf("content must be an array of content blocks")
+}
+
+// parseContent converts a single MCP content block into its mcp-go type.
+// In lenient mode blocks of unknown types are passed on as their JSON text.
+func parseContent(raw json.RawMessage, lenient bool) (mcp.Content, error) {
+	var block struct {
+		Type     string          `json:"type"`
+		Text     string          `json:"text"`
+		Data     string          `json:"data"`
+		MIMEType string          `json:"mimeType"`
+		Resource json.RawMessage `json:"resource"`
+	}
+	if err := json.Unmarshal(raw, &block); err != nil {
+		return nil, fmt.Errorf("malformed content block: %w", err)
+	}
+
+	switch block.Type {
+	case "text":
+		return textContent(block.Text), nil
+	case "image":
+		return mcp.ImageContent{Type: "image", Data: block.Data, MIMEType: block.MIMEType}, nil
+	case "resource":
+		resource, err := parseResourceContents(block.Resource)
+		if err != nil {
+			return nil, err
+		}
+		return mcp.EmbeddedResource{Type: "resource", Resource: resource}, nil
+	}
+
+	if lenient {
+		if block.Type == "" && block.Text != "" {
+			return textContent(block.Text), nil
+		}
+		return textContent(string(raw)), nil
+	}
+	return nil, fmt.Errorf("unsupported content type %q", block.Type)
+}
+
+// parseResourceContents parses the contents of an embedded resource
+func parseResourceContents(raw json.RawMessage) (mcp.ResourceContents, error) {
+	var contents struct {
+		URI      string  `json:"uri"`
+		MIMEType string  `json:"mimeType"`
+		Text     *string `json:"text"`
+		Blob     *string `json:"blob"`
+	}
+	if len(raw) == 0 {
+		return nil, fmt.Errorf("resource content block has no resource")
+	}
+	if err := json.Unmarshal(raw, &contents); err != nil {
+		return nil, fmt.Errorf("malformed resource contents: %w", err)
+	}
+
+	if contents.Blob != nil {
+		return mcp.BlobResourceContents{URI: contents.URI, MIMEType: contents.MIMEType, Blob: *contents.Blob}, nil
+	}
+	text := ""
+	if contents.Text != nil {
+		text = *contents.Text
+	}
+	return mcp.TextResourceContents{URI: contents.URI, MIMEType: contents.MIMEType, Text: text}, nil
+}
+
+// textContent creates a text content block
+func textContent(text string) mcp.TextContent {
+	return mcp.TextContent{Type: "text", Text: text}
+}
+
+// withCallMeta adds the server a tool result came from and how long the call took to the result's _meta,
+// keeping every entry the server set
+func withCallMeta(result *mcp.CallToolResult, serverName string, latency time.Duration) *mcp.CallToolResult {
+	if result == nil {
+		return nil
+	}
+	if _, exists := result.Meta[resultMetaKey]; exists {
+		return result
+	}
+
+	annotated := *result
+	annotated.Meta = make(map[string]interface{}, len(result.Meta)+1)
+	for key, value := range result.Meta {
+		annotated.Meta[key] = value
+	}
+	annotated.Meta[resultMetaKey] = map[string]interface{}{"server": serverName, "latencyMs": latency.Milliseconds()}
+	return &annotated
+}
@@ -0,0 +1,220 @@
+package aggregator
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestParseCallToolResult(t *testing.T) {
+	tests := []struct {
+		name      string
+		shape     string
+		raw       string
+		wantTexts []string
+		wantError bool
+		wantErr   bool
+	}{
+		{
+			name:      "Canonical result",
+			shape:     config.ResultShapeCanonical,
+			raw:       `{"content":[{"type":"text","text":"hello"},{"type":"text","text":"world"}]}`,
+			wantTexts: []string{"hello", "world"},
+		},
+		{
+			name:      "Canonical error result keeps isError",
+			shape:     "",
+			raw:       `{"content":[{"type":"text","text":"boom"}],"isError":true}`,
+			wantTexts: []string{"boom"},
+			wantError: true,
+		},
+		{
+			name:    "Canonical rejects top-level text",
+			shape:   config.ResultShapeCanonical,
+			raw:     `{"text":"hello"}`,
+			wantErr: true,
+		},
+		{
+			name:    "Canonical rejects a single content object",
+			shape:   "",
+			raw:     `{"content":{"type":"text","text":"hello"}}`,
+			wantErr: true,
+		},
+		{
+			name:      "Lenient maps top-level text",
+			shape:     config.ResultShapeLenient,
+			raw:       `{"text":"hello"}`,
+			wantTexts: []string{"hello"},
+		},
+		{
+			name:      "Lenient maps a single content object",
+			shape:     config.ResultShapeLenient,
+			raw:       `{"content":{"text":"hello"}}`,
+			wantTexts: []string{"hello"},
+		},
+		{
+			name:      "Lenient maps string content",
+			shape:     config.ResultShapeLenient,
+			raw:       `{"content":"hello"}`,
+			wantTexts: []string{"hello"},
+		},
+		{
+			name:      "Lenient maps a bare string result",
+			shape:     config.ResultShapeLenient,
+			raw:       `"hello"`,
+			wantTexts: []string{"hello"},
+		},
+		{
+			name:      "Lenient passes unknown blocks on as JSON text",
+			shape:     config.ResultShapeLenient,
+			raw:       `{"content":[{"type":"json","json":{"a":1}}]}`,
+			wantTexts: []string{`{"type":"json","json":{"a":1}}`},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			result, err := parseCallToolResult(json.RawMessage(tt.raw), tt.shape)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("parseCallToolResult() error = %v, wantErr %v", err, tt.wantErr)
+			}
+			if tt.wantErr {
+				return
+			}
+
+			if result.IsError != tt.wantError {
+				t.Errorf("IsError = %v, want %v", result.IsError, tt.wantError)
+			}
+			if len(result.Content) != len(tt.wantTexts) {
+				t.Fatalf("Got %d content blocks, want %d", len(result.Content), len(tt.wantTexts))
+			}
+			for i, want := range tt.wantTexts {
+				text, ok := result.Content[i].(mcp.TextContent)
+				if !ok {
+					t.Fatalf("Content[%d] is %T, want mcp.TextContent", i, result.Content[i])
+				}
+				if text.Text != want {
+					t.Errorf("Content[%d] = %q, want %q", i, text.Text, want)
+				}
+			}
+		})
+	}
+}
+
+func TestParseContentTypes(t *testing.T) {
+	tests := []struct {
+		name    string
+		raw     string
+		want    mcp.Content
+		wantErr bool
+	}{
+		{
+			name: "Image",
+			raw:  `{"type":"image","data":"aGVsbG8=","mimeType":"image/png"}`,
+			want: mcp.ImageContent{Type: "image", Data: "aGVsbG8=", MIMEType: "image/png"},
+		},
+		{
+			name: "Text resource",
+			raw:  `{"type":"resource","resource":{"uri":"file:///a.txt","mimeType":"text/plain","text":"hello"}}`,
+			want: mcp.EmbeddedResource{
+				Type:     "resource",
+				Resource: mcp.TextResourceContents{URI: "file:///a.txt", MIMEType: "text/plain", Text: "hello"},
+			},
+		},
+		{
+			name: "Blob resource",
+			raw:  `{"type":"resource","resource":{"uri":"file:///a.bin","mimeType":"application/octet-stream","blob":"AAE="}}`,
+			want: mcp.EmbeddedResource{
+				Type:     "resource",
+				Resource: mcp.BlobResourceContents{URI: "file:///a.bin", MIMEType: "application/octet-stream", Blob: "AAE="},
+			},
+		},
+		{
+			name:    "Resource without contents",
+			raw:     `{"type":"resource"}`,
+			wantErr: true,
+		},
+		{
+			name:    "Unsupported type",
+			raw:     `{"type":"video","data":"AAE="}`,
+			wantErr: true,
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got, err := parseContent(json.RawMessage(tt.raw), false)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("parseContent() error = %v, wantErr %v", err, tt.wantErr)
+			}
+			if tt.wantErr {
+				return
+			}
+			if !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("parseContent() = %#v, want %#v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestCallToolResultRoundTrip(t *testing.T) {
+	raws := []string{
+		`{"content":[{"type":"text","text":"hello"}]}`,
+		`{"content":[{"type":"text","text":"boom"}],"isError":true}`,
+		`{"_meta":{"trace":"abc"},"content":[` +
+			`{"type":"text","text":"{\"a\":1}"},` +
+			`{"type":"image","data":"aGVsbG8=","mimeType":"image/png"},` +
+			`{"type":"resource","resource":{"uri":"file:///a.txt","mimeType":"text/plain","text":"hello"}},` +
+			`{"type":"resource","resource":{"uri":"file:///a.bin","mimeType":"application/octet-stream","blob":"AAE="}}` +
+			`]}`,
+	}
+
+	for _, raw := range raws {
+		result, err := parseCallToolResult(json.RawMessage(raw), config.ResultShapeCanonical)
+		if err != nil {
+			t.Fatalf("parseCallToolResult(%s) error = %v", raw, err)
+		}
+		encoded, err := json.Marshal(result)
+		if err != nil {
+			t.Fatalf("Failed to marshal result: %v", err)
+		}
+
+		var want, got interface{}
+		if err := json.Unmarshal([]byte(raw), &want); err != nil {
+			t.Fatalf("Failed to decode input: %v", err)
+		}
+		if err := json.Unmarshal(encoded, &got); err != nil {
+			t.Fatalf("Failed to decode output: %v", err)
+		}
+		if !reflect.DeepEqual(got, want) {
+			t.Errorf("Round trip of %s = %s", raw, encoded)
+		}
+	}
+}
+
+func TestWithCallMeta(t *testing.T) {
+	result := &mcp.CallToolResult{Content: []mcp.Content{textContent("hello")}}
+	result.Meta = map[string]interface{}{"trace": "abc"}
+
+	annotated := withCallMeta(result, "github", 1500*time.Millisecond)
+	want := map[string]interface{}{
+		"trace":       "abc",
+		resultMetaKey: map[string]interface{}{"server": "github", "latencyMs": int64(1500)},
+	}
+	if !reflect.DeepEqual(annotated.Meta, want) {
+		t.Errorf("_meta = %v, want %v", annotated.Meta, want)
+	}
+	if len(result.Meta) != 1 {
+		t.Errorf("The server's result was changed: _meta = %v", result.Meta)
+	}
+
+	// An entry of the same name the server set is kept
+	result.Meta[resultMetaKey] = "the server's own"
+	if got := withCallMeta(result, "github", time.Second).Meta[resultMetaKey]; got != "the server's own" {
+		t.Errorf("_meta.%s = %v, want the server's entry", resultMetaKey, got)
+	}
+}
@@ -0,0 +1,55 @@
+package aggregator
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// SamplingMethod is the request a server sends to have the client sample its LLM
+const SamplingMethod = "sampling/createMessage"
+
+// ClientError is the client's error response to a request relayed from a server, passed back to it as is
+type ClientError struct {
+	Code    int
+	Message string
+	Data    json.RawMessage
+}
+
+// Error implements the error interface
+func (e *ClientError) Error() string {
+	return fmt.Sprintf("client error %d: %s", e.Code, e.Message)
+}
+
+// OnSampling registers the function relaying the sampling requests of servers to the client
+func (a *MCPAggregator) OnSampling(relay func(ctx context.Context, params json.RawMessage) (json.RawMessage, error)) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.sampling = relay
+}
+
+// handleServerRequest answers a request a server sent to the aggregator as its client
+func (a *MCPAggregator) handleServerRequest(ctx context.Context, serverName, method string, params json.RawMessage) (json.RawMessage, error) {
+	if method != SamplingMethod {
+		return nil, &ClientError{Code: mcp.METHOD_NOT_FOUND, Message: fmt.Sprintf("method %s not supported", method)}
+	}
+
+	a.mu.RLock()
+	relay := a.sampling
+	clientKnown := a.upstreamInfo != nil
+	clientSamples := a.upstreamCapabilities.Sampling != nil
+	a.mu.RUnlock()
+
+	if relay == nil {
+		return nil, &ClientError{Code: mcp.METHOD_NOT_FOUND, Message: "sampling is not available: no client is connected"}
+	}
+	if clientKnown && !clientSamples {
+		return nil, &ClientError{Code: mcp.METHOD_NOT_FOUND, Message: "sampling is not supported by the client"}
+	}
+
+	logger.Debug("Relaying sampling request of server %s to the client", serverName)
+	return relay(ctx, params)
+}
@@ -0,0 +1,115 @@
+package aggregator
+
+import (
+	"errors"
+	"fmt"
+	"os/exec"
+	"sort"
+	"strings"
+	"time"
+)
+
+// How a server stopped when it was shut down
+const (
+	StoppedExited       = "exited"       // The process exited on its own once its stdin closed
+	StoppedTerminated   = "terminated"   // The process only exited after SIGTERM
+	StoppedKilled       = "killed"       // The process ignored SIGTERM for the grace period and was killed
+	StoppedDisconnected = "disconnected" // The connection to a remote server was closed
+)
+
+// ServerExit describes how a server stopped when the aggregator shut down
+type ServerExit struct {
+	Name     string
+	Stopped  string        // exited, terminated, killed or disconnected
+	ExitCode int           // The process's exit code, -1 when a signal ended it or there is no process
+	Grace    time.Duration // How long the process got after each shutdown step
+	Err      error         // Why stopping failed, other than the process exiting with an error code
+}
+
+// exitReporter is implemented by clients that know how their server stopped once they are closed
+type exitReporter interface {
+	exitStatus() ServerExit
+}
+
+// Clean reports whether the server stopped on its own without an error
+func (e ServerExit) Clean() bool {
+	if e.Err != nil {
+		return false
+	}
+	return e.Stopped == StoppedDisconnected || (e.Stopped == StoppedExited && e.ExitCode == 0)
+}
+
+// String describes how the server stopped, e.g. "github exited with code 0"
+func (e ServerExit) String() string {
+	var description string
+	switch e.Stopped {
+	case StoppedKilled:
+		description = fmt.Sprintf("%s was killed after ignoring SIGTERM for %v", e.Name, e.Grace)
+	case StoppedTerminated:
+		description = fmt.Sprintf("%s didn't exit within %v and was terminated with SIGTERM", e.Name, e.Grace)
+		if e.ExitCode >= 0 {
+			description += fmt.Sprintf(", exiting with code %d", e.ExitCode)
+		}
+	case StoppedDisconnected:
+		description = fmt.Sprintf("%s was disconnected", e.Name)
+	default:
+		description = fmt.Sprintf("%s exited with code %d", e.Name, e.ExitCode)
+	}
+	if e.Err != nil {
+		description += fmt.Sprintf(": %v", e.Err)
+	}
+	return description
+}
+
+// SummarizeExits describes how the servers stopped in a single line, naming those that didn't stop cleanly
+func SummarizeExits(exits []ServerExit) string {
+	if len(exits) == 0 {
+		return "no servers were running"
+	}
+
+	clean := 0
+	var problems []string
+	for _, exit := range exits {
+		if exit.Clean() {
+			clean++
+		} else {
+			problems = append(problems, exit.String())
+		}
+	}
+	summary := fmt.Sprintf("%d of %d servers stopped cleanly", clean, len(exits))
+	if len(problems) > 0 {
+		summary += "; " + strings.Join(problems, "; ")
+	}
+	return summary
+}
+
+// clientExit reports how the server behind a closed client stopped, given the error closing it returned
+func clientExit(name string, c MCPClient, closeErr error) ServerExit {
+	if bounded, ok := c.(*boundedClient); ok {
+		c = bounded.MCPClient
+	}
+	if reporter, ok := c.(exitReporter); ok {
+		exit := reporter.exitStatus()
+		exit.Name = name
+		return exit
+	}
+	return ServerExit{Name: name, Stopped: StoppedDisconnected, ExitCode: -1, Err: closeErr}
+}
+
+// sortExits orders exits by server name, for a stable summary
+func sortExits(exits []ServerExit) {
+	sort.Slice(exits, func(i, j int) bool { return exits[i].Name < exits[j].Name })
+}
+
+// exitStatus reports how the server process stopped after Close
+func (c *stdioClient) exitStatus() ServerExit {
+	exit := ServerExit{Name: c.serverName, Stopped: c.stopped, ExitCode: -1, Grace: c.shutdownGrace}
+	if state := c.cmd.ProcessState; state != nil {
+		exit.ExitCode = state.ExitCode()
+	}
+	var exitErr *exec.ExitError
+	if c.closeErr != nil && !errors.As(c.closeErr, &exitErr) {
+		exit.Err = c.closeErr
+	}
+	return exit
+}
@@ -0,0 +1,25 @@
+package aggregator
+
+import (
+	"context"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/client"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// The mcp-go SSE client already speaks to a server the way the aggregator needs
+var _ MCPClient = (*client.SSEMCPClient)(nil)
+
+// newSSEClient connects to a remote server over HTTP and server-sent events
+func newSSEClient(ctx context.Context, serverCfg *config.ServerConfig) (*client.SSEMCPClient, error) {
+	sseClient, err := client.NewSSEMCPClient(serverCfg.URL)
+	if err != nil {
+		return nil, fmt.Errorf("failed to create SSE client: %w", err)
+	}
+	if err := sseClient.Start(ctx); err != nil {
+		sseClient.Close()
+		return nil, fmt.Errorf("failed to connect to %s: %w", serverCfg.URL, err)
+	}
+	return sseClient, nil
+}
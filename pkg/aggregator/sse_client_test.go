@@ -0,0 +1,77 @@
+package aggregator
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/mark3labs/mcp-go/server"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestSSEServer(t *testing.T) {
+	remote := server.NewMCPServer("remote", "1.0.0", server.WithToolCapabilities(true))
+	remote.AddTool(mcp.NewTool("echo", mcp.WithDescription("Echo the text argument")), func(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+		return mcp.NewToolResultText(request.Params.Arguments["text"].(string)), nil
+	})
+	testServer := server.NewTestServer(remote)
+	defer testServer.Close()
+
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{{Name: "remote", URL: testServer.URL + "/sse"}}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	result, err := callEcho(agg, "remote_echo", "over http")
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if text, ok := result.Content[0].(mcp.TextContent); !ok || text.Text != "over http" {
+		t.Errorf("CallTool() content = %+v, want the echoed text", result.Content)
+	}
+}
+
+func TestReconnectRemoteServer(t *testing.T) {
+	remote := server.NewMCPServer("remote", "1.0.0", server.WithToolCapabilities(true))
+	remote.AddTool(mcp.NewTool("echo", mcp.WithDescription("Echo the text argument")), func(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+		return mcp.NewToolResultText(request.Params.Arguments["text"].(string)), nil
+	})
+	testServer := server.NewTestServer(remote)
+	defer testServer.Close()
+
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{{Name: "remote", URL: testServer.URL + "/sse", ReconnectBackoffMs: 1000}}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	agg.mu.RLock()
+	serverCfg, lostClient := agg.configs["remote"], agg.clients["remote"]
+	agg.mu.RUnlock()
+	go agg.reconnectServer(serverCfg, lostClient, errors.New("connection reset"))
+
+	// Calls fail fast while the connection is restored, and the tools stay listed
+	waitFor(t, 5*time.Second, "the server to be unavailable", func() bool {
+		_, err := callEcho(agg, "remote_echo", "lost")
+		var unavailable *unavailableError
+		return errors.As(err, &unavailable)
+	})
+	if got := len(agg.GetTools()); got != 1 {
+		t.Errorf("Got %d tools while reconnecting, want the server's tool kept", got)
+	}
+
+	waitFor(t, 10*time.Second, "the server to be reconnected", func() bool {
+		result, err := callEcho(agg, "remote_echo", "back")
+		return err == nil && len(result.Content) == 1
+	})
+	if status := agg.Status()[0]; !status.Running || status.LastError == "" {
+		t.Errorf("Status after reconnecting = %+v, want running with the lost connection as last error", status)
+	}
+}
@@ -0,0 +1,144 @@
+package aggregator
+
+import (
+	"context"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// defaultStaleToolsWindow is how long the last known tools of a restarting server stay listed by default
+const defaultStaleToolsWindow = 30 * time.Second
+
+// restartWindow is a server being restarted, whose last known tools stay listed as stale meanwhile
+type restartWindow struct {
+	tools map[string]toolMapping // The server's tools when it went down, by exposed name
+	back  chan struct{}          // Closed once the server is running again or its stale tools are dropped
+}
+
+// staleToolsWindowLocked returns how long the tools of a restarting server stay listed, 0 when they are dropped at once
+func (a *MCPAggregator) staleToolsWindowLocked() time.Duration {
+	switch {
+	case a.cfg.StaleToolsMs < 0:
+		return 0
+	case a.cfg.StaleToolsMs > 0:
+		return time.Duration(a.cfg.StaleToolsMs) * time.Millisecond
+	default:
+		return defaultStaleToolsWindow
+	}
+}
+
+// keepStaleTools keeps listing the tools of a server that is about to be restarted, and holds calls to them
+// until the server is back, for at most the stale tools window
+func (a *MCPAggregator) keepStaleTools(serverName string) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+
+	window := a.staleToolsWindowLocked()
+	if window <= 0 || a.restarting[serverName] != nil {
+		return
+	}
+	restart := &restartWindow{tools: make(map[string]toolMapping), back: make(chan struct{})}
+	for exposedName, mapping := range a.tools {
+		// Deduplicated tools are served by their other providers meanwhile
+		if mapping.serverName == serverName && len(mapping.replicas) == 0 {
+			restart.tools[exposedName] = mapping
+		}
+	}
+	a.restarting[serverName] = restart
+
+	time.AfterFunc(window, func() {
+		a.mu.Lock()
+		expired := a.restarting[serverName] == restart
+		if expired {
+			a.closeRestartWindowLocked(serverName)
+		}
+		a.mu.Unlock()
+		if expired {
+			logger.Error("Warning: server %s isn't back after %v, dropping its tools", serverName, window)
+			a.notifyToolsChanged()
+		}
+	})
+}
+
+// endStaleTools stops listing the stale tools of a server, if any, and lets the calls held for it go on
+func (a *MCPAggregator) endStaleTools(serverName string) {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	a.closeRestartWindowLocked(serverName)
+}
+
+// closeRestartWindowLocked forgets the restart window of a server and releases the calls waiting on it
+func (a *MCPAggregator) closeRestartWindowLocked(serverName string) {
+	restart, exists := a.restarting[serverName]
+	if !exists {
+		return
+	}
+	delete(a.restarting, serverName)
+	close(restart.back)
+	a.toolsCache = nil
+}
+
+// listedToolsLocked returns the registered tools along with the stale tools of restarting servers, by exposed name,
+// except the ones whose names are reserved for built-in tools
+func (a *MCPAggregator) listedToolsLocked() map[string]toolMapping {
+	if len(a.restarting) == 0 && len(a.reserved) == 0 {
+		return a.tools
+	}
+	listed := make(map[string]toolMapping, len(a.tools))
+	for _, restart := range a.restarting {
+		for exposedName, mapping := range restart.tools {
+			listed[exposedName] = mapping
+		}
+	}
+	for exposedName, mapping := range a.tools {
+		listed[exposedName] = mapping
+	}
+	for exposedName := range a.reserved {
+		delete(listed, exposedName)
+	}
+	return listed
+}
+
+// StaleTools returns the exposed names of the tools listed only while their server restarts
+func (a *MCPAggregator) StaleTools() map[string]bool {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	stale := make(map[string]bool)
+	for _, restart := range a.restarting {
+		for exposedName := range restart.tools {
+			if _, registered := a.tools[exposedName]; !registered {
+				stale[exposedName] = true
+			}
+		}
+	}
+	return stale
+}
+
+// waitForRestart holds a call to an exposed tool while the server it comes from restarts
+func (a *MCPAggregator) waitForRestart(ctx context.Context, exposedName string) error {
+	a.mu.RLock()
+	var restart *restartWindow
+	if mapping, exists := a.tools[exposedName]; exists {
+		restart = a.restarting[mapping.serverName]
+	} else {
+		for _, window := range a.restarting {
+			if _, listed := window.tools[exposedName]; listed {
+				restart = window
+			}
+		}
+	}
+	a.mu.RUnlock()
+
+	if restart == nil {
+		return nil
+	}
+	logger.Debug("Holding the call of %s until its server is back", exposedName)
+	select {
+	case <-restart.back:
+		return nil
+	case <-ctx.Done():
+		return ctx.Err()
+	}
+}
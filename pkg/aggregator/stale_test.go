@@ -0,0 +1,89 @@
+package aggregator
+
+import (
+	"context"
+	"testing"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// restartTools simulates the restart of a server: its tools are forgotten and discovered again later
+func restartTools(t *testing.T, agg *MCPAggregator, serverName string) {
+	t.Helper()
+	agg.keepStaleTools(serverName)
+	agg.mu.Lock()
+	agg.removeToolsLocked(serverName)
+	agg.mu.Unlock()
+}
+
+func TestStaleToolsDuringRestart(t *testing.T) {
+	agg := NewMCPAggregator()
+	agg.clients["backend"] = &MockClient{Tools: []mcp.Tool{{Name: "query"}}}
+	agg.configs["backend"] = &config.ServerConfig{Name: "backend", Command: "backend-command"}
+	if err := agg.discoverTools(context.Background(), "backend"); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+
+	restartTools(t, agg, "backend")
+	if tools := agg.GetTools(); len(tools) != 1 || tools[0].Name != "backend_query" {
+		t.Fatalf("GetTools() during the restart = %v, want the stale backend_query", tools)
+	}
+	if stale := agg.StaleTools(); !stale["backend_query"] {
+		t.Errorf("StaleTools() = %v, want backend_query", stale)
+	}
+
+	// A call during the restart is held until the server is back
+	called := make(chan error, 1)
+	go func() {
+		request := mcp.CallToolRequest{}
+		request.Params.Name = "backend_query"
+		_, err := agg.CallTool(context.Background(), request)
+		called <- err
+	}()
+	select {
+	case err := <-called:
+		t.Fatalf("CallTool() returned %v before the server was back", err)
+	case <-time.After(50 * time.Millisecond):
+	}
+
+	if err := agg.discoverTools(context.Background(), "backend"); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+	agg.endStaleTools("backend")
+	select {
+	case err := <-called:
+		if err != nil {
+			t.Errorf("CallTool() error = %v", err)
+		}
+	case <-time.After(5 * time.Second):
+		t.Fatalf("CallTool() is still held after the server is back")
+	}
+	if stale := agg.StaleTools(); len(stale) != 0 {
+		t.Errorf("StaleTools() after the restart = %v, want none", stale)
+	}
+}
+
+func TestStaleToolsExpire(t *testing.T) {
+	agg := NewMCPAggregator()
+	agg.cfg = &config.Config{StaleToolsMs: 50}
+	agg.clients["backend"] = &MockClient{Tools: []mcp.Tool{{Name: "query"}}}
+	agg.configs["backend"] = &config.ServerConfig{Name: "backend", Command: "backend-command"}
+	if err := agg.discoverTools(context.Background(), "backend"); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+
+	restartTools(t, agg, "backend")
+	waitFor(t, 5*time.Second, "the stale tools to expire", func() bool { return len(agg.GetTools()) == 0 })
+
+	// Without a stale window the tools are dropped at once
+	agg.cfg = &config.Config{StaleToolsMs: -1}
+	if err := agg.discoverTools(context.Background(), "backend"); err != nil {
+		t.Fatalf("discoverTools() error = %v", err)
+	}
+	restartTools(t, agg, "backend")
+	if tools := agg.GetTools(); len(tools) != 0 {
+		t.Errorf("GetTools() with staleToolsMs -1 = %v, want none", tools)
+	}
+}
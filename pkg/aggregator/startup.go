@@ -0,0 +1,123 @@
+package aggregator
+
+import (
+	"context"
+	"errors"
+	"sync"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// registrationTurnKey marks a context whose server registers its tools only once the turn of the server
+// started before it is over, so tool name collisions are settled in start order however long startup takes
+type registrationTurnKey struct{}
+
+// registrationTurn is the place of a server in the order servers starting together register their tools in
+type registrationTurn struct {
+	previous <-chan struct{} // Closed once the turn of the server started before is over, nil for the first
+	done     chan struct{}   // Closed once the server registered its tools, or failed before it could
+	once     sync.Once
+}
+
+// newRegistrationTurn returns the turn coming after previous, or the first turn if previous is nil
+func newRegistrationTurn(previous *registrationTurn) *registrationTurn {
+	turn := &registrationTurn{done: make(chan struct{})}
+	if previous != nil {
+		turn.previous = previous.done
+	}
+	return turn
+}
+
+// end lets the server started next register its tools
+func (t *registrationTurn) end() {
+	t.once.Do(func() { close(t.done) })
+}
+
+// withRegistrationTurn returns a context whose server registers its tools in the given turn
+func withRegistrationTurn(ctx context.Context, turn *registrationTurn) context.Context {
+	return context.WithValue(ctx, registrationTurnKey{}, turn)
+}
+
+// waitForRegistrationTurn waits until the server of ctx may register its tools
+func waitForRegistrationTurn(ctx context.Context) error {
+	turn, _ := ctx.Value(registrationTurnKey{}).(*registrationTurn)
+	if turn == nil || turn.previous == nil {
+		return nil
+	}
+	select {
+	case <-turn.previous:
+		return nil
+	case <-ctx.Done():
+		return ctx.Err()
+	}
+}
+
+// endRegistrationTurn ends the turn of the server of ctx once it registered its tools, so the next server
+// doesn't wait for this one to finish starting, such as to pass its self-test or ready check
+func endRegistrationTurn(ctx context.Context) {
+	if turn, _ := ctx.Value(registrationTurnKey{}).(*registrationTurn); turn != nil {
+		turn.end()
+	}
+}
+
+// startServers starts servers concurrently, each after the servers it depends on, which come before it.
+// A server that fails doesn't keep the others from starting. It returns the error of the first server
+// in the list whose process couldn't be started at all, if any.
+func (a *MCPAggregator) startServers(ctx context.Context, servers []*config.ServerConfig) error {
+	errs := make([]error, len(servers))
+	finished := make([]chan struct{}, len(servers)) // Closed once a server is started or failed
+	positions := make(map[string]int, len(servers))
+
+	var wg sync.WaitGroup
+	var previous *registrationTurn
+	for i, serverCfg := range servers {
+		positions[serverCfg.Name] = i
+		finished[i] = make(chan struct{})
+
+		turn := newRegistrationTurn(previous)
+		previous = turn
+		var dependencies []int
+		for _, name := range serverCfg.DependsOn {
+			if position, eager := positions[name]; eager {
+				dependencies = append(dependencies, position)
+			}
+		}
+
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			// A server failing before its turn ends it, but only once the turns before it are over
+			defer func() {
+				if turn.previous != nil {
+					<-turn.previous
+				}
+				turn.end()
+			}()
+			defer close(finished[i])
+
+			for _, position := range dependencies {
+				<-finished[position]
+				if errs[position] != nil {
+					logger.Error("Warning: server %s depends on server %s, which failed to start", serverCfg.Name, servers[position].Name)
+				}
+			}
+
+			if err := a.startServer(withRegistrationTurn(ctx, turn), serverCfg); err != nil {
+				errs[i] = err
+				a.recordServerError(serverCfg.Name, err)
+				// Continue with other servers even if this one isn't usable
+				logger.Error("Continuing with other servers...")
+			}
+		}()
+	}
+	wg.Wait()
+
+	for _, err := range errs {
+		var spawnErr *spawnError
+		if errors.As(err, &spawnErr) {
+			return err
+		}
+	}
+	return nil
+}
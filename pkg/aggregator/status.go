@@ -0,0 +1,72 @@
+package aggregator
+
+import "time"
+
+// ServerStatus describes the state of a configured server at runtime
+type ServerStatus struct {
+	Name      string     `json:"name"`
+	Running   bool       `json:"running"`
+	Healthy   bool       `json:"healthy"`
+	Tools     int        `json:"tools"`
+	PID       int        `json:"pid,omitempty"`
+	LastError string     `json:"lastError,omitempty"`
+	LastPing  *time.Time `json:"lastPing,omitempty"` // When the server last answered a keepalive ping
+}
+
+// processClient is implemented by clients whose server runs as a local process
+type processClient interface {
+	PID() int
+	Done() <-chan struct{}
+}
+
+// clientProcess returns the process behind a client, nil for remote servers
+func clientProcess(c MCPClient) processClient {
+	if bounded, ok := c.(*boundedClient); ok {
+		c = bounded.MCPClient
+	}
+	process, _ := c.(processClient)
+	return process
+}
+
+// Status reports the state of every configured server, in config order
+func (a *MCPAggregator) Status() []ServerStatus {
+	a.mu.RLock()
+	defer a.mu.RUnlock()
+
+	toolCounts := make(map[string]int)
+	for _, mapping := range a.tools {
+		for _, serverName := range toolProviders(mapping) {
+			toolCounts[serverName]++
+		}
+	}
+
+	statuses := make([]ServerStatus, 0, len(a.cfg.Servers))
+	for _, serverCfg := range a.cfg.Servers {
+		status := ServerStatus{
+			Name:    serverCfg.Name,
+			Healthy: a.isHealthyLocked(serverCfg.Name),
+			Tools:   toolCounts[serverCfg.Name],
+		}
+		if health, exists := a.health[serverCfg.Name]; exists {
+			status.LastError = health.lastError
+			if !health.lastPing.IsZero() {
+				lastPing := health.lastPing
+				status.LastPing = &lastPing
+			}
+		}
+		if client, exists := a.clients[serverCfg.Name]; exists {
+			status.Running = !a.unavailable[serverCfg.Name]
+			if process := clientProcess(client); process != nil {
+				status.PID = process.PID()
+				select {
+				case <-process.Done():
+					// Exited and waiting to be restarted
+					status.Running = false
+				default:
+				}
+			}
+		}
+		statuses = append(statuses, status)
+	}
+	return statuses
+}
@@ -0,0 +1,573 @@
+package aggregator
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"sync"
+	"sync/atomic"
+	"syscall"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/framing"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+	"github.com/nazar256/combine-mcp/pkg/redact"
+)
+
+// rpcRequest is an outgoing JSON-RPC request, or a notification when ID is nil
+type rpcRequest struct {
+	JSONRPC string      `json:"jsonrpc"`
+	ID      *int64      `json:"id,omitempty"`
+	Method  string      `json:"method"`
+	Params  interface{} `json:"params,omitempty"`
+}
+
+// rpcError is a JSON-RPC error object
+type rpcError struct {
+	Code    int             `json:"code"`
+	Message string          `json:"message"`
+	Data    json.RawMessage `json:"data,omitempty"`
+}
+
+// Error implements the error interface
+func (e *rpcError) Error() string {
+	return fmt.Sprintf("server error %d: %s", e.Code, e.Message)
+}
+
+// rpcMessage is any incoming JSON-RPC message: a response, a notification or a request
+type rpcMessage struct {
+	JSONRPC string          `json:"jsonrpc"`
+	ID      json.RawMessage `json:"id,omitempty"`
+	Method  string          `json:"method,omitempty"`
+	Params  json.RawMessage `json:"params,omitempty"`
+	Result  json.RawMessage `json:"result,omitempty"`
+	Error   *rpcError       `json:"error,omitempty"`
+}
+
+// rpcResponse is an outgoing response to a request initiated by a server
+type rpcResponse struct {
+	JSONRPC string          `json:"jsonrpc"`
+	ID      json.RawMessage `json:"id"`
+	Result  interface{}     `json:"result,omitempty"`
+	Error   *rpcError       `json:"error,omitempty"`
+}
+
+// stdioClient speaks JSON-RPC to a child MCP server over its stdin and stdout.
+// Responses are matched to waiting requests by id, so concurrent calls are safe.
+type stdioClient struct {
+	serverName  string
+	resultShape string
+	framing     string
+
+	cmd     *exec.Cmd
+	stdin   io.WriteCloser
+	writeMu sync.Mutex
+
+	nextID  int64
+	mu      sync.Mutex
+	pending map[int64]chan *rpcMessage
+	readErr error
+	done    chan struct{}
+
+	onNotification func(method string, params json.RawMessage)
+	onRequest      func(ctx context.Context, method string, params json.RawMessage) (json.RawMessage, error)
+
+	shutdownGrace time.Duration
+	closeOnce     sync.Once
+	closeErr      error
+	stopped       string // How the process stopped once it was closed, see ServerExit
+	onClosed      func() // Called once the process was shut down, set before the client is shared
+}
+
+// defaultShutdownGrace is how long a server gets to exit after each shutdown step
+const defaultShutdownGrace = 5 * time.Second
+
+// newStdioClient starts the server process and the goroutine reading its output
+func newStdioClient(serverCfg *config.ServerConfig, env []string) (*stdioClient, error) {
+	cmd := exec.Command(serverCfg.Command, serverCfg.Args...)
+	cmd.Env = append(serverCfg.InheritedEnv(os.Environ()), env...)
+	cmd.Dir = serverCfg.Cwd // Empty runs the server where the aggregator runs
+	cmd.Stderr = os.Stderr // Server diagnostics must never reach our stdout
+	// The server leads a process group of its own, so the processes it starts are signalled along with it
+	cmd.SysProcAttr = &syscall.SysProcAttr{Setpgid: true}
+
+	stdin, err := cmd.StdinPipe()
+	if err != nil {
+		return nil, fmt.Errorf("failed to create stdin pipe: %w", err)
+	}
+	stdout, err := cmd.StdoutPipe()
+	if err != nil {
+		return nil, fmt.Errorf("failed to create stdout pipe: %w", err)
+	}
+	if err := cmd.Start(); err != nil {
+		return nil, fmt.Errorf("failed to start command: %w", err)
+	}
+
+	c := &stdioClient{
+		serverName:  serverCfg.Name,
+		resultShape: serverCfg.ResultShape,
+		framing:     serverCfg.Framing,
+		cmd:         cmd,
+		stdin:       stdin,
+		pending:     make(map[int64]chan *rpcMessage),
+		done:        make(chan struct{}),
+	}
+	c.shutdownGrace = defaultShutdownGrace
+	if serverCfg.ShutdownGraceMs > 0 {
+		c.shutdownGrace = time.Duration(serverCfg.ShutdownGraceMs) * time.Millisecond
+	}
+	go c.readMessages(stdout)
+
+	return c, nil
+}
+
+// readMessages dispatches every message the server writes until its output closes
+func (c *stdioClient) readMessages(stdout io.Reader) {
+	reader := framing.NewReader(stdout, c.framing)
+	for {
+		message, err := reader.ReadMessage()
+		if err != nil {
+			if err != io.EOF && !errors.Is(err, os.ErrClosed) {
+				logger.Error("Error reading from server %s: %v", c.serverName, err)
+			}
+			c.mu.Lock()
+			c.readErr = &serverExitedError{serverName: c.serverName}
+			c.mu.Unlock()
+			close(c.done)
+			return
+		}
+		c.handleMessage(message)
+	}
+}
+
+// handleMessage routes a single message from the server
+func (c *stdioClient) handleMessage(line []byte) {
+	var msg rpcMessage
+	if err := json.Unmarshal(line, &msg); err != nil {
+		logger.ServerDebug(c.serverName, "Ignoring non JSON-RPC output from server %s: %s", c.serverName, line)
+		return
+	}
+
+	if msg.Method != "" {
+		// Responses are traced by the request they answer, which knows its correlation id
+		c.trace("IN", "", line)
+		if len(msg.ID) == 0 {
+			logger.ServerDebug(c.serverName, "Notification from server %s: %s", c.serverName, msg.Method)
+			c.mu.Lock()
+			onNotification := c.onNotification
+			c.mu.Unlock()
+			if onNotification != nil {
+				onNotification(msg.Method, msg.Params)
+			}
+		} else {
+			c.handleServerRequest(&msg)
+		}
+		return
+	}
+
+	var id int64
+	if err := json.Unmarshal(msg.ID, &id); err != nil {
+		logger.ServerDebug(c.serverName, "Ignoring response with unexpected id %s from server %s", msg.ID, c.serverName)
+		return
+	}
+
+	c.mu.Lock()
+	ch, exists := c.pending[id]
+	delete(c.pending, id)
+	c.mu.Unlock()
+
+	if !exists {
+		logger.ServerDebug(c.serverName, "Ignoring response to unknown request %d from server %s", id, c.serverName)
+		return
+	}
+	ch <- &msg
+}
+
+// handleServerRequest answers requests the server sends to us as its client.
+// Requests other than ping are answered from their own goroutine, as relaying them may take long.
+func (c *stdioClient) handleServerRequest(msg *rpcMessage) {
+	c.mu.Lock()
+	onRequest := c.onRequest
+	c.mu.Unlock()
+
+	if msg.Method == "ping" || onRequest == nil {
+		response := rpcResponse{JSONRPC: mcp.JSONRPC_VERSION, ID: msg.ID}
+		if msg.Method == "ping" {
+			response.Result = struct{}{}
+		} else {
+			response.Error = &rpcError{Code: mcp.METHOD_NOT_FOUND, Message: fmt.Sprintf("method %s not supported", msg.Method)}
+		}
+		c.answer(msg.Method, response)
+		return
+	}
+
+	go func() {
+		// Stop waiting for the answer once the server is gone
+		ctx, cancel := context.WithCancel(context.Background())
+		defer cancel()
+		go func() {
+			select {
+			case <-c.done:
+				cancel()
+			case <-ctx.Done():
+			}
+		}()
+
+		response := rpcResponse{JSONRPC: mcp.JSONRPC_VERSION, ID: msg.ID}
+		result, err := onRequest(ctx, msg.Method, msg.Params)
+		var clientErr *ClientError
+		switch {
+		case errors.As(err, &clientErr):
+			response.Error = &rpcError{Code: clientErr.Code, Message: clientErr.Message, Data: clientErr.Data}
+		case err != nil:
+			response.Error = &rpcError{Code: mcp.INTERNAL_ERROR, Message: err.Error()}
+		default:
+			response.Result = result
+		}
+		c.answer(msg.Method, response)
+	}()
+}
+
+// answer sends the response to a request from the server
+func (c *stdioClient) answer(method string, response rpcResponse) {
+	if err := c.send(response, ""); err != nil {
+		logger.Error("Failed to answer %s request from server %s: %v", method, c.serverName, err)
+	}
+}
+
+// send writes a single JSON-RPC message to the server, tracing it under the given correlation id
+func (c *stdioClient) send(message interface{}, correlationID string) error {
+	data, err := json.Marshal(message)
+	if err != nil {
+		return err
+	}
+	c.trace("OUT", correlationID, data)
+
+	c.writeMu.Lock()
+	defer c.writeMu.Unlock()
+	_, err = c.stdin.Write(framing.Encode(c.framing, data))
+	return err
+}
+
+// notify sends a JSON-RPC notification to the server
+func (c *stdioClient) notify(method string, params interface{}) error {
+	return c.send(rpcRequest{JSONRPC: mcp.JSONRPC_VERSION, Method: method, Params: params}, "")
+}
+
+// trace logs a message exchanged with the server at trace level with secrets masked, tagged with the
+// correlation id of the upstream request it was exchanged for, if any
+func (c *stdioClient) trace(direction, correlationID string, message []byte) {
+	if logger.GetLevel() < config.LogLevelTrace {
+		return
+	}
+	if correlationID == "" {
+		correlationID = "-"
+	}
+	logger.ServerTrace(c.serverName, "%s server %s [%s]: %s", direction, c.serverName, correlationID, redact.JSON(message))
+}
+
+// request sends a JSON-RPC request to the server and waits for the matching response
+func (c *stdioClient) request(ctx context.Context, method string, params interface{}) (json.RawMessage, error) {
+	id := atomic.AddInt64(&c.nextID, 1)
+	ch := make(chan *rpcMessage, 1)
+
+	c.mu.Lock()
+	if c.readErr != nil {
+		err := c.readErr
+		c.mu.Unlock()
+		return nil, err
+	}
+	c.pending[id] = ch
+	c.mu.Unlock()
+
+	defer func() {
+		c.mu.Lock()
+		delete(c.pending, id)
+		c.mu.Unlock()
+	}()
+
+	correlationID := CorrelationID(ctx)
+	if err := c.send(rpcRequest{JSONRPC: mcp.JSONRPC_VERSION, ID: &id, Method: method, Params: params}, correlationID); err != nil {
+		// A broken pipe usually means the server died, which the reader reports more clearly
+		select {
+		case <-c.done:
+			c.mu.Lock()
+			defer c.mu.Unlock()
+			return nil, c.readErr
+		default:
+		}
+		return nil, fmt.Errorf("failed to send %s to server %s: %w", method, c.serverName, err)
+	}
+
+	select {
+	case msg := <-ch:
+		c.traceResponse(correlationID, msg)
+		return responseResult(msg)
+	case <-c.done:
+		// The response may have arrived right before the output closed
+		select {
+		case msg := <-ch:
+			c.traceResponse(correlationID, msg)
+			return responseResult(msg)
+		default:
+		}
+		c.mu.Lock()
+		defer c.mu.Unlock()
+		return nil, c.readErr
+	case <-ctx.Done():
+		// Tell the server to stop working on it; the handshake itself must not be cancelled
+		if method != "initialize" {
+			cancelled := map[string]interface{}{"requestId": id, "reason": ctx.Err().Error()}
+			if err := c.notify("notifications/cancelled", cancelled); err != nil {
+				logger.ServerDebug(c.serverName, "Failed to cancel request %d of server %s: %v", id, c.serverName, err)
+			}
+		}
+		return nil, ctx.Err()
+	}
+}
+
+// traceResponse traces the response to a request under the request's correlation id
+func (c *stdioClient) traceResponse(correlationID string, msg *rpcMessage) {
+	if logger.GetLevel() < config.LogLevelTrace {
+		return
+	}
+	if data, err := json.Marshal(msg); err == nil {
+		c.trace("IN", correlationID, data)
+	}
+}
+
+// responseResult extracts the result of a response or converts its error
+func responseResult(msg *rpcMessage) (json.RawMessage, error) {
+	if msg.Error != nil {
+		return nil, msg.Error
+	}
+	return msg.Result, nil
+}
+
+// Initialize performs the MCP handshake with the server
+func (c *stdioClient) Initialize(ctx context.Context, request mcp.InitializeRequest) (*mcp.InitializeResult, error) {
+	raw, err := c.request(ctx, "initialize", request.Params)
+	if err != nil {
+		return nil, err
+	}
+
+	var result mcp.InitializeResult
+	if err := json.Unmarshal(raw, &result); err != nil {
+		return nil, fmt.Errorf("invalid initialize result from server %s: %w", c.serverName, err)
+	}
+
+	if err := c.notify("notifications/initialized", nil); err != nil {
+		return nil, fmt.Errorf("failed to send initialized notification to server %s: %w", c.serverName, err)
+	}
+
+	return &result, nil
+}
+
+// ListTools lists the tools provided by the server
+func (c *stdioClient) ListTools(ctx context.Context, request mcp.ListToolsRequest) (*mcp.ListToolsResult, error) {
+	raw, err := c.request(ctx, "tools/list", request.Params)
+	if err != nil {
+		return nil, err
+	}
+
+	var result mcp.ListToolsResult
+	if err := json.Unmarshal(raw, &result); err != nil {
+		return nil, fmt.Errorf("invalid tools/list result from server %s: %w", c.serverName, err)
+	}
+	return &result, nil
+}
+
+// CallTool invokes a tool on the server
+func (c *stdioClient) CallTool(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	raw, err := c.request(ctx, "tools/call", request.Params)
+	if err != nil {
+		return nil, err
+	}
+
+	result, err := parseCallToolResult(raw, c.resultShape)
+	if err != nil {
+		return nil, fmt.Errorf("invalid tools/call result from server %s: %w", c.serverName, err)
+	}
+	return result, nil
+}
+
+// ListResources lists the resources provided by the server
+func (c *stdioClient) ListResources(ctx context.Context, request mcp.ListResourcesRequest) (*mcp.ListResourcesResult, error) {
+	raw, err := c.request(ctx, "resources/list", request.Params)
+	if err != nil {
+		return nil, err
+	}
+
+	var result mcp.ListResourcesResult
+	if err := json.Unmarshal(raw, &result); err != nil {
+		return nil, fmt.Errorf("invalid resources/list result from server %s: %w", c.serverName, err)
+	}
+	return &result, nil
+}
+
+// ReadResource reads a resource from the server
+func (c *stdioClient) ReadResource(ctx context.Context, request mcp.ReadResourceRequest) (*mcp.ReadResourceResult, error) {
+	raw, err := c.request(ctx, "resources/read", request.Params)
+	if err != nil {
+		return nil, err
+	}
+
+	var parsed struct {
+		Meta     map[string]interface{} `json:"_meta,omitempty"`
+		Contents []json.RawMessage      `json:"contents"`
+	}
+	if err := json.Unmarshal(raw, &parsed); err != nil {
+		return nil, fmt.Errorf("invalid resources/read result from server %s: %w", c.serverName, err)
+	}
+
+	result := &mcp.ReadResourceResult{Contents: make([]mcp.ResourceContents, 0, len(parsed.Contents))}
+	result.Meta = parsed.Meta
+	for _, item := range parsed.Contents {
+		contents, err := parseResourceContents(item)
+		if err != nil {
+			return nil, fmt.Errorf("invalid resources/read result from server %s: %w", c.serverName, err)
+		}
+		result.Contents = append(result.Contents, contents)
+	}
+	return result, nil
+}
+
+// ListPrompts lists the prompts provided by the server
+func (c *stdioClient) ListPrompts(ctx context.Context, request mcp.ListPromptsRequest) (*mcp.ListPromptsResult, error) {
+	raw, err := c.request(ctx, "prompts/list", request.Params)
+	if err != nil {
+		return nil, err
+	}
+
+	var result mcp.ListPromptsResult
+	if err := json.Unmarshal(raw, &result); err != nil {
+		return nil, fmt.Errorf("invalid prompts/list result from server %s: %w", c.serverName, err)
+	}
+	return &result, nil
+}
+
+// GetPrompt gets a prompt from the server
+func (c *stdioClient) GetPrompt(ctx context.Context, request mcp.GetPromptRequest) (*mcp.GetPromptResult, error) {
+	raw, err := c.request(ctx, "prompts/get", request.Params)
+	if err != nil {
+		return nil, err
+	}
+
+	var parsed struct {
+		Meta        map[string]interface{} `json:"_meta,omitempty"`
+		Description string                 `json:"description,omitempty"`
+		Messages    []struct {
+			Role    mcp.Role        `json:"role"`
+			Content json.RawMessage `json:"content"`
+		} `json:"messages"`
+	}
+	if err := json.Unmarshal(raw, &parsed); err != nil {
+		return nil, fmt.Errorf("invalid prompts/get result from server %s: %w", c.serverName, err)
+	}
+
+	result := &mcp.GetPromptResult{
+		Description: parsed.Description,
+		Messages:    make([]mcp.PromptMessage, 0, len(parsed.Messages)),
+	}
+	result.Meta = parsed.Meta
+	for _, message := range parsed.Messages {
+		content, err := parseContent(message.Content, c.resultShape == config.ResultShapeLenient)
+		if err != nil {
+			return nil, fmt.Errorf("invalid prompts/get result from server %s: %w", c.serverName, err)
+		}
+		result.Messages = append(result.Messages, mcp.PromptMessage{Role: message.Role, Content: content})
+	}
+	return result, nil
+}
+
+// SetLevel changes the server's logging level
+func (c *stdioClient) SetLevel(ctx context.Context, request mcp.SetLevelRequest) error {
+	_, err := c.request(ctx, "logging/setLevel", request.Params)
+	return err
+}
+
+// Ping checks that the server still answers requests
+func (c *stdioClient) Ping(ctx context.Context) error {
+	_, err := c.request(ctx, "ping", nil)
+	return err
+}
+
+// OnNotification registers a callback invoked with every notification from the server
+func (c *stdioClient) OnNotification(callback func(method string, params json.RawMessage)) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.onNotification = callback
+}
+
+// OnRequest registers a callback answering requests from the server other than ping
+func (c *stdioClient) OnRequest(callback func(ctx context.Context, method string, params json.RawMessage) (json.RawMessage, error)) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.onRequest = callback
+}
+
+// PID returns the process id of the server
+func (c *stdioClient) PID() int {
+	return c.cmd.Process.Pid
+}
+
+// Done returns a channel that is closed once the server's output closes, usually because it exited
+func (c *stdioClient) Done() <-chan struct{} {
+	return c.done
+}
+
+// Close shuts the server down, see shutdown; calling it again returns the first result
+func (c *stdioClient) Close() error {
+	c.closeOnce.Do(func() {
+		c.closeErr = c.shutdown()
+		if c.onClosed != nil {
+			c.onClosed()
+		}
+	})
+	return c.closeErr
+}
+
+// shutdown stops the server the way the MCP stdio transport describes: close its stdin and let it
+// exit on its own, then send SIGTERM, and only kill it once it ignored that for the grace period too
+func (c *stdioClient) shutdown() error {
+	c.stdin.Close()
+
+	waited := make(chan error, 1)
+	go func() { waited <- c.cmd.Wait() }()
+
+	select {
+	case err := <-waited:
+		c.stopped = StoppedExited
+		return err
+	case <-time.After(c.shutdownGrace):
+	}
+
+	logger.Info("Server %s didn't exit within %v, terminating it", c.serverName, c.shutdownGrace)
+	if err := c.signalGroup(syscall.SIGTERM); err != nil {
+		logger.ServerDebug(c.serverName, "Failed to send SIGTERM to server %s: %v", c.serverName, err)
+	}
+	select {
+	case err := <-waited:
+		c.stopped = StoppedTerminated
+		return err
+	case <-time.After(c.shutdownGrace):
+	}
+
+	logger.Error("Server %s didn't exit after SIGTERM, killing it", c.serverName)
+	if err := c.signalGroup(syscall.SIGKILL); err != nil {
+		logger.Error("Failed to kill server %s: %v", c.serverName, err)
+	}
+	c.stopped = StoppedKilled
+	return <-waited
+}
+
+// signalGroup sends a signal to the server's process group, reaching the processes it started too
+func (c *stdioClient) signalGroup(sig syscall.Signal) error {
+	return syscall.Kill(-c.cmd.Process.Pid, sig)
+}
@@ -0,0 +1,1195 @@
+package aggregator
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"os/signal"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync"
+	"syscall"
+	"testing"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/framing"
+)
+
+// helperServerConfig returns a server config that runs this test binary as a fake MCP server.
+// The mode selects how the fake server behaves, see runFakeServer.
+func helperServerConfig(name, mode string) config.ServerConfig {
+	return config.ServerConfig{
+		Name:    name,
+		Command: os.Args[0],
+		Args:    []string{"-test.run=TestHelperProcess", "--"},
+		Env: map[string]string{
+			"GO_WANT_HELPER_PROCESS": "1",
+			"HELPER_MODE":            mode,
+		},
+	}
+}
+
+// TestHelperProcess isn't a real test, it's the fake MCP server started by helperServerConfig
+func TestHelperProcess(t *testing.T) {
+	if os.Getenv("GO_WANT_HELPER_PROCESS") != "1" {
+		return
+	}
+	runFakeServer(os.Getenv("HELPER_MODE"))
+	os.Exit(0)
+}
+
+// runFakeServer answers MCP requests on stdin until it closes.
+// When HELPER_PID_FILE is set, the server first writes its pid to that file. Modes:
+//   - "silent" never answers anything
+//   - "crash" exits as soon as a tool is called
+//   - "stubborn" ignores SIGTERM and keeps running after its stdin closes
+//   - "malformed" answers tools/list with something that isn't a tool list
+//   - "future" answers the handshake with a protocol version the aggregator doesn't speak
+//   - "framed" is a working server speaking Content-Length framing and writing multi-line JSON
+//   - "slow" is a working server that takes half a second to answer the handshake
+//   - "strict" is a working server that only lists its tools once the client sent notifications/initialized
+//   - anything else is a working server with a greet prompt and a single echo tool, which answers after
+//     delayMs milliseconds when that argument is given, so answers can arrive out of order. Echoing "grow"
+//     adds a second tool and notifies the client that the tool list changed. A call with a progress token
+//     reports progress before it is answered. Echoing "sample" asks the client to sample and answers with the
+//     sampled text, or with an error result when sampling failed. Echoing "hang" makes the server stop answering
+//     anything while it keeps running. Echoing "cancelled" answers with the number of requests the client
+//     cancelled so far. Echoing "stray" first answers the last call given a delayMs under ids that don't quite
+//     match its own: the id as a string and the id plus 1000
+func runFakeServer(mode string) {
+	if path := os.Getenv("HELPER_PID_FILE"); path != "" {
+		os.WriteFile(path, []byte(strconv.Itoa(os.Getpid())), 0o600)
+	}
+	if mode == "stubborn" {
+		signal.Ignore(syscall.SIGTERM)
+		defer time.Sleep(time.Hour)
+	}
+
+	messageFraming := config.FramingNDJSON
+	if mode == "framed" {
+		messageFraming = config.FramingContentLength
+	}
+	var encodeMu sync.Mutex
+	reply := func(message interface{}) {
+		data, _ := json.Marshal(message)
+		if mode == "framed" {
+			data, _ = json.MarshalIndent(message, "", "  ")
+		}
+		encodeMu.Lock()
+		defer encodeMu.Unlock()
+		os.Stdout.Write(framing.Encode(messageFraming, data))
+	}
+
+	tools := []interface{}{
+		map[string]interface{}{
+			"name":        "echo",
+			"description": "Echo the text argument",
+			"inputSchema": map[string]interface{}{
+				"type":       "object",
+				"properties": map[string]interface{}{"text": map[string]interface{}{"type": "string"}},
+			},
+		},
+	}
+
+	// Tool calls waiting for the answer to the sampling request they sent, by the id of that request
+	sampling := make(map[string]json.RawMessage)
+	// Set once the server wedged itself, after which it reads but never answers
+	hung := false
+	// Set once the client confirmed the handshake with notifications/initialized
+	initialized := false
+	// Number of requests the client cancelled with notifications/cancelled
+	cancelled := 0
+	// Id of the last call answered after a delay
+	var delayedID json.RawMessage
+
+	reader := framing.NewReader(os.Stdin, messageFraming)
+	for {
+		message, err := reader.ReadMessage()
+		if err != nil {
+			break
+		}
+
+		var request struct {
+			ID     json.RawMessage `json:"id"`
+			Method string          `json:"method"`
+			Params struct {
+				Name      string                 `json:"name"`
+				Arguments map[string]interface{} `json:"arguments"`
+				Meta      struct {
+					ProgressToken interface{} `json:"progressToken"`
+				} `json:"_meta"`
+			} `json:"params"`
+			Result struct {
+				Content struct {
+					Text string `json:"text"`
+				} `json:"content"`
+			} `json:"result"`
+			Error *struct {
+				Message string `json:"message"`
+			} `json:"error"`
+		}
+		if err := json.Unmarshal(message, &request); err != nil || len(request.ID) == 0 {
+			initialized = initialized || request.Method == "notifications/initialized"
+			if request.Method == "notifications/cancelled" {
+				cancelled++
+			}
+			continue // Notifications need no answer
+		}
+		if mode == "silent" || hung {
+			continue
+		}
+
+		if request.Method == "" {
+			callID, exists := sampling[string(request.ID)]
+			if !exists {
+				continue
+			}
+			delete(sampling, string(request.ID))
+			text, isError := request.Result.Content.Text, false
+			if request.Error != nil {
+				text, isError = request.Error.Message, true
+			}
+			reply(map[string]interface{}{
+				"jsonrpc": "2.0",
+				"id":      callID,
+				"result": map[string]interface{}{
+					"content": []interface{}{map[string]interface{}{"type": "text", "text": text}},
+					"isError": isError,
+				},
+			})
+			continue
+		}
+
+		var result interface{}
+		switch request.Method {
+		case "initialize":
+			if mode == "slow" {
+				time.Sleep(500 * time.Millisecond)
+			}
+			protocolVersion := "2024-11-05"
+			if mode == "future" {
+				protocolVersion = "2999-01-01"
+			}
+			result = map[string]interface{}{
+				"protocolVersion": protocolVersion,
+				"capabilities":    map[string]interface{}{"tools": map[string]interface{}{}, "prompts": map[string]interface{}{}},
+				"serverInfo":      map[string]interface{}{"name": "helper", "version": "1.0.0"},
+			}
+		case "tools/list":
+			if mode == "malformed" {
+				result = map[string]interface{}{"tools": "not a list"}
+				break
+			}
+			if mode == "strict" && !initialized {
+				reply(map[string]interface{}{
+					"jsonrpc": "2.0",
+					"id":      request.ID,
+					"error":   map[string]interface{}{"code": -32002, "message": "server not initialized"},
+				})
+				continue
+			}
+			result = map[string]interface{}{"tools": tools}
+		case "prompts/list":
+			result = map[string]interface{}{
+				"prompts": []interface{}{
+					map[string]interface{}{
+						"name":        "greet",
+						"description": "Greet someone",
+						"arguments":   []interface{}{map[string]interface{}{"name": "name", "required": true}},
+					},
+				},
+			}
+		case "prompts/get":
+			result = map[string]interface{}{
+				"messages": []interface{}{
+					map[string]interface{}{
+						"role":    "user",
+						"content": map[string]interface{}{"type": "text", "text": fmt.Sprintf("Hello, %v", request.Params.Arguments["name"])},
+					},
+				},
+			}
+		case "tools/call":
+			if mode == "crash" {
+				os.Exit(1)
+			}
+			if text, _ := request.Params.Arguments["text"].(string); text == "fail" {
+				result = map[string]interface{}{
+					"content": []interface{}{map[string]interface{}{"type": "text", "text": "failed on request"}},
+					"isError": true,
+				}
+				break
+			}
+			if token := request.Params.Meta.ProgressToken; token != nil {
+				reply(map[string]interface{}{
+					"jsonrpc": "2.0",
+					"method":  "notifications/progress",
+					"params":  map[string]interface{}{"progressToken": token, "progress": 1, "total": 2},
+				})
+			}
+			if text, _ := request.Params.Arguments["text"].(string); text == "sample" {
+				samplingID := fmt.Sprintf(`"sample-%d"`, len(sampling)+1)
+				sampling[samplingID] = request.ID
+				reply(map[string]interface{}{
+					"jsonrpc": "2.0",
+					"id":      json.RawMessage(samplingID),
+					"method":  "sampling/createMessage",
+					"params": map[string]interface{}{
+						"messages":  []interface{}{map[string]interface{}{"role": "user", "content": map[string]interface{}{"type": "text", "text": "Say hi"}}},
+						"maxTokens": 10,
+					},
+				})
+				continue
+			}
+			if text, _ := request.Params.Arguments["text"].(string); text == "cancelled" {
+				request.Params.Arguments["text"] = fmt.Sprintf("cancelled %d", cancelled)
+			}
+			if text, _ := request.Params.Arguments["text"].(string); text == "hang" {
+				hung = true
+				continue
+			}
+			if text, _ := request.Params.Arguments["text"].(string); text == "stray" && delayedID != nil {
+				var id int64
+				json.Unmarshal(delayedID, &id)
+				for _, strayID := range []interface{}{string(delayedID), id + 1000} {
+					reply(map[string]interface{}{
+						"jsonrpc": "2.0",
+						"id":      strayID,
+						"result":  map[string]interface{}{"content": []interface{}{map[string]interface{}{"type": "text", "text": "stray response"}}},
+					})
+				}
+			}
+			if text, _ := request.Params.Arguments["text"].(string); text == "grow" && len(tools) == 1 {
+				tools = append(tools, map[string]interface{}{
+					"name":        "grown",
+					"inputSchema": map[string]interface{}{"type": "object"},
+				})
+				reply(map[string]interface{}{"jsonrpc": "2.0", "method": "notifications/tools/list_changed"})
+			}
+			result = map[string]interface{}{
+				"content": []interface{}{
+					map[string]interface{}{"type": "text", "text": fmt.Sprintf("%v", request.Params.Arguments["text"])},
+				},
+			}
+		case "ping":
+			result = map[string]interface{}{}
+		default:
+			reply(map[string]interface{}{
+				"jsonrpc": "2.0",
+				"id":      request.ID,
+				"error":   map[string]interface{}{"code": -32601, "message": "method not found"},
+			})
+			continue
+		}
+		response := map[string]interface{}{"jsonrpc": "2.0", "id": request.ID, "result": result}
+		if delay, _ := request.Params.Arguments["delayMs"].(float64); delay > 0 {
+			delayedID = request.ID
+			go func() {
+				time.Sleep(time.Duration(delay) * time.Millisecond)
+				reply(response)
+			}()
+			continue
+		}
+		reply(response)
+	}
+}
+
+func TestInitializeHandshake(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	if got := agg.ProtocolVersion("helper"); got != "2024-11-05" {
+		t.Errorf("ProtocolVersion() = %q, want 2024-11-05", got)
+	}
+	if agg.capabilities["helper"].Tools == nil {
+		t.Errorf("Tools capability of the server wasn't stored")
+	}
+}
+
+func TestIncompatibleProtocolVersion(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{
+		helperServerConfig("future", "future"),
+		helperServerConfig("helper", ""),
+	}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	if got := agg.ProtocolVersion("future"); got != "" {
+		t.Errorf("ProtocolVersion(future) = %q, want the server left out", got)
+	}
+	tools := agg.GetTools()
+	if len(tools) != 1 || tools[0].Name != "helper_echo" {
+		t.Errorf("GetTools() = %v, want only the compatible server's tool", tools)
+	}
+}
+
+func TestInitializeTimeout(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	silent := helperServerConfig("silent", "silent")
+	silent.InitTimeoutMs = 200
+	cfg := &config.Config{Servers: []config.ServerConfig{silent}}
+
+	start := time.Now()
+	err := agg.Initialize(context.Background(), cfg)
+	if err == nil {
+		t.Fatalf("Initialize() succeeded with a server that never answers")
+	}
+	if elapsed := time.Since(start); elapsed > 10*time.Second {
+		t.Errorf("Initialize() took %v, want it to give up after the configured timeout", elapsed)
+	}
+	if _, exists := agg.clients["silent"]; exists {
+		t.Errorf("Server that never answered was kept")
+	}
+}
+
+func TestInitializedNotification(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("strict", "strict")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	// The server only lists its tools after the handshake was confirmed
+	if tools := agg.GetTools(); len(tools) != 1 || tools[0].Name != "strict_echo" {
+		t.Errorf("GetTools() = %v, want strict_echo", tools)
+	}
+}
+
+func TestConcurrentStartup(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	prefixTools := false
+	registry := helperServerConfig("registry", "slow")
+	proxy := helperServerConfig("proxy", "slow")
+	proxy.DependsOn = []string{"registry"}
+	cfg := &config.Config{PrefixTools: &prefixTools, Servers: []config.ServerConfig{
+		helperServerConfig("first", "slow"),
+		helperServerConfig("second", ""),
+		helperServerConfig("third", "slow"),
+		helperServerConfig("fourth", "slow"),
+		proxy,
+		registry,
+	}}
+
+	start := time.Now()
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+	// Only the proxy waits for another slow server, the others start at the same time
+	if elapsed := time.Since(start); elapsed > 2*time.Second {
+		t.Errorf("Initialize() took %v, want slow servers to start concurrently", elapsed)
+	}
+	if len(agg.clients) != len(cfg.Servers) {
+		t.Errorf("Started %d servers, want %d", len(agg.clients), len(cfg.Servers))
+	}
+
+	// The server first in config order keeps the colliding tool name even though it started last
+	if mapping := agg.tools["echo"]; mapping.serverName != "first" {
+		t.Errorf("echo routes to server %q, want first", mapping.serverName)
+	}
+}
+
+func TestRegistrationTurnEndsBeforeReadiness(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	warming := helperServerConfig("warming", "")
+	warming.ReadyCheck = &config.ReadyCheckConfig{DelayMs: 3000}
+	cfg := &config.Config{Servers: []config.ServerConfig{warming, helperServerConfig("quick", "")}}
+	initialized := make(chan error, 1)
+	go func() { initialized <- agg.Initialize(context.Background(), cfg) }()
+
+	// The server started second only waits for the first one to register its tools, not to get ready
+	waitFor(t, 2*time.Second, "the tool of the server started second to be callable", func() bool {
+		_, err := callEcho(agg, "quick_echo", "hello")
+		return err == nil
+	})
+	select {
+	case <-initialized:
+		t.Errorf("The first server got ready before the tool of the second one was callable")
+	default:
+	}
+	if err := <-initialized; err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+}
+
+func TestToolDiscovery(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{
+		helperServerConfig("helper", ""),
+		helperServerConfig("broken", "malformed"),
+	}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	tools := agg.GetTools()
+	if len(tools) != 1 {
+		t.Fatalf("Got %d tools, want only the one of the working server", len(tools))
+	}
+	tool := tools[0]
+	if tool.Name != "helper_echo" {
+		t.Errorf("Tool name = %q, want helper_echo", tool.Name)
+	}
+	if tool.Description != "[helper] Echo the text argument" {
+		t.Errorf("Tool description = %q", tool.Description)
+	}
+	if _, exists := tool.InputSchema.Properties["text"]; !exists {
+		t.Errorf("Tool schema lost its properties: %+v", tool.InputSchema)
+	}
+}
+
+func TestUndiscoverableServerIsStopped(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	pidPath := filepath.Join(t.TempDir(), "broken.pid")
+	broken := helperServerConfig("broken", "malformed")
+	broken.Env["HELPER_PID_FILE"] = pidPath
+	if err := agg.Initialize(context.Background(), &config.Config{Servers: []config.ServerConfig{broken}}); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	data, err := os.ReadFile(pidPath)
+	if err != nil {
+		t.Fatalf("ReadFile() error = %v", err)
+	}
+	pid, err := strconv.Atoi(string(data))
+	if err != nil {
+		t.Fatalf("Atoi() error = %v", err)
+	}
+	waitFor(t, 5*time.Second, "the process of the server failing to list its tools to exit", func() bool {
+		return !processRunning(pid)
+	})
+	if status := agg.Status()[0]; status.Running {
+		t.Errorf("Status = %+v, want the server not running", status)
+	}
+}
+
+func TestLazyServer(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	lazy := helperServerConfig("helper", "")
+	lazy.Lazy = true
+	cfg := &config.Config{Servers: []config.ServerConfig{lazy}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+	if got := len(agg.GetTools()); got != 0 {
+		t.Fatalf("Got %d tools before the lazy server was needed, want 0", got)
+	}
+	if status := agg.Status()[0]; status.Running {
+		t.Errorf("Status of the lazy server = %+v, want not running", status)
+	}
+
+	// Calling one of its tools starts it
+	result, err := callEcho(agg, "helper_echo", "hello")
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if text := result.Content[0].(mcp.TextContent).Text; text != "hello" {
+		t.Errorf("CallTool() = %q, want hello", text)
+	}
+	if got := len(agg.GetTools()); got != 1 {
+		t.Errorf("Got %d tools after the lazy server started, want 1", got)
+	}
+	if agg.StartLazyServers(context.Background()) {
+		t.Errorf("StartLazyServers() = true, want false once every lazy server started")
+	}
+}
+
+func TestLazyServersStartOnlyWhenCalled(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	alpha := helperServerConfig("alpha", "")
+	alpha.Lazy = true
+	beta := helperServerConfig("beta", "")
+	beta.Lazy = true
+	if err := agg.Initialize(context.Background(), &config.Config{Servers: []config.ServerConfig{alpha, beta}}); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+	running := func() map[string]bool {
+		running := make(map[string]bool)
+		for _, status := range agg.Status() {
+			running[status.Name] = status.Running
+		}
+		return running
+	}
+
+	// A name no server's prefix matches, such as a typo, starts nothing
+	var notFound *ToolNotFoundError
+	if _, err := callEcho(agg, "gamma_echo", "hello"); !errors.As(err, &notFound) {
+		t.Errorf("CallTool() of an unknown tool error = %v, want a tool not found error", err)
+	}
+	if got := running(); got["alpha"] || got["beta"] {
+		t.Errorf("Running servers after calling an unknown tool = %v, want none", got)
+	}
+
+	// Only the server whose tool is called starts
+	if _, err := callEcho(agg, "alpha_echo", "hello"); err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if got := running(); !got["alpha"] || got["beta"] {
+		t.Errorf("Running servers after calling a tool of alpha = %v, want only alpha", got)
+	}
+}
+
+func TestToolCacheFile(t *testing.T) {
+	cachePath := filepath.Join(t.TempDir(), "tools.json")
+	newConfig := func(args ...string) *config.Config {
+		helper := helperServerConfig("helper", "")
+		helper.Args = append(helper.Args, args...)
+		return &config.Config{Servers: []config.ServerConfig{helper}, ToolCacheFile: cachePath}
+	}
+
+	// The first run discovers the tools and caches them on shutdown
+	first := NewMCPAggregator()
+	if err := first.Initialize(context.Background(), newConfig()); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+	first.Close()
+	if _, err := os.Stat(cachePath); err != nil {
+		t.Fatalf("Tool cache wasn't written: %v", err)
+	}
+
+	// The next run serves the cached tools without starting the server until a tool is called
+	cached := NewMCPAggregator()
+	defer cached.Close()
+	if err := cached.Initialize(context.Background(), newConfig()); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+	if tools := cached.GetTools(); len(tools) != 1 || tools[0].Name != "helper_echo" {
+		t.Fatalf("GetTools() = %+v, want the cached helper_echo", tools)
+	}
+	if status := cached.Status()[0]; status.Running {
+		t.Errorf("Status of the server with cached tools = %+v, want not running", status)
+	}
+	result, err := callEcho(cached, "helper_echo", "hello")
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if text := result.Content[0].(mcp.TextContent).Text; text != "hello" {
+		t.Errorf("CallTool() = %q, want hello", text)
+	}
+
+	// A server whose args changed is started to discover its tools again
+	changed := NewMCPAggregator()
+	defer changed.Close()
+	if err := changed.Initialize(context.Background(), newConfig("-test.v")); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+	if status := changed.Status()[0]; !status.Running {
+		t.Errorf("Status of the changed server = %+v, want running", status)
+	}
+}
+
+// callEcho calls the echo tool of the helper server through the aggregator
+func callEcho(agg *MCPAggregator, toolName, text string) (*mcp.CallToolResult, error) {
+	request := mcp.CallToolRequest{}
+	request.Params.Name = toolName
+	request.Params.Arguments = map[string]interface{}{"text": text}
+	return agg.CallTool(context.Background(), request)
+}
+
+func TestCallToolRouting(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	result, err := callEcho(agg, "helper_echo", "hello")
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if text, ok := result.Content[0].(mcp.TextContent); !ok || text.Text != "hello" {
+		t.Errorf("CallTool() content = %+v, want the echoed text", result.Content)
+	}
+	if result.IsError {
+		t.Errorf("CallTool() result unexpectedly has isError")
+	}
+
+	result, err = callEcho(agg, "helper_echo", "fail")
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if !result.IsError {
+		t.Errorf("CallTool() lost the isError flag of the server's result")
+	}
+}
+
+func TestContentLengthFramedServer(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	serverCfg := helperServerConfig("helper", "framed")
+	serverCfg.Framing = config.FramingContentLength
+	if err := agg.Initialize(context.Background(), &config.Config{Servers: []config.ServerConfig{serverCfg}}); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	result, err := callEcho(agg, "helper_echo", "multi\nline")
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if text, ok := result.Content[0].(mcp.TextContent); !ok || text.Text != "multi\nline" {
+		t.Errorf("CallTool() content = %+v, want the echoed text", result.Content)
+	}
+}
+
+func TestCallToolOnDeadServer(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	crashy := helperServerConfig("crashy", "crash")
+	crashy.MaxRestarts = -1
+	cfg := &config.Config{Servers: []config.ServerConfig{crashy}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	// The first call kills the server, later calls find a broken pipe
+	for i := 0; i < 3; i++ {
+		_, err := callEcho(agg, "crashy_echo", "hello")
+		if err == nil {
+			t.Fatalf("Call %d to a dead server succeeded", i)
+		}
+		if !strings.Contains(err.Error(), "no longer running") {
+			t.Errorf("Call %d error = %v, want it to say the server is no longer running", i, err)
+		}
+	}
+}
+
+func TestServerDiesMidCall(t *testing.T) {
+	const callers = 3
+
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	helper := helperServerConfig("helper", "")
+	helper.MaxRestarts = -1
+	cfg := &config.Config{Servers: []config.ServerConfig{helper}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	errs := make(chan error, callers)
+	for i := 0; i < callers; i++ {
+		go func() {
+			request := mcp.CallToolRequest{}
+			request.Params.Name = "helper_echo"
+			request.Params.Arguments = map[string]interface{}{"text": "slow", "delayMs": 10000}
+			_, err := agg.CallTool(context.Background(), request)
+			errs <- err
+		}()
+	}
+	waitFor(t, 5*time.Second, "all calls to be in flight", func() bool { return agg.PendingRequests()["helper"] == callers })
+
+	// Every call waiting on the killed server fails with an error naming it, long before its answer was due
+	if err := syscall.Kill(agg.Status()[0].PID, syscall.SIGKILL); err != nil {
+		t.Fatalf("Failed to kill the server: %v", err)
+	}
+	for i := 0; i < callers; i++ {
+		select {
+		case err := <-errs:
+			var exited *serverExitedError
+			if !errors.As(err, &exited) || exited.serverName != "helper" {
+				t.Errorf("CallTool() error = %v, want the server to have exited", err)
+			}
+		case <-time.After(5 * time.Second):
+			t.Fatal("A call kept waiting after its server died")
+		}
+	}
+}
+
+func TestConcurrentCallsGetTheirOwnResponses(t *testing.T) {
+	const callers = 40
+
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	var wg sync.WaitGroup
+	for i := 0; i < callers; i++ {
+		wg.Add(1)
+		go func(i int) {
+			defer wg.Done()
+
+			// Earlier calls answer later, so responses come back in reverse order
+			want := fmt.Sprintf("call-%d", i)
+			request := mcp.CallToolRequest{}
+			request.Params.Name = "helper_echo"
+			request.Params.Arguments = map[string]interface{}{"text": want, "delayMs": (callers - i) * 5}
+			result, err := agg.CallTool(context.Background(), request)
+			if err != nil {
+				t.Errorf("CallTool(%s) error = %v", want, err)
+				return
+			}
+			if text, ok := result.Content[0].(mcp.TextContent); !ok || text.Text != want {
+				t.Errorf("CallTool(%s) content = %+v", want, result.Content)
+			}
+		}(i)
+	}
+	wg.Wait()
+}
+
+func TestStrayResponsesCompleteNoRequest(t *testing.T) {
+	c := startHelperClient(t, helperServerConfig("helper", ""))
+	defer c.Close()
+
+	// The server reports progress on the earlier call once it got it, before it answers the later one
+	started := make(chan struct{}, 1)
+	c.OnNotification(func(method string, params json.RawMessage) {
+		if method == "notifications/progress" {
+			started <- struct{}{}
+		}
+	})
+	callText := func(arguments map[string]interface{}, meta map[string]interface{}) (string, error) {
+		raw, err := c.request(context.Background(), "tools/call", map[string]interface{}{"name": "echo", "arguments": arguments, "_meta": meta})
+		if err != nil {
+			return "", err
+		}
+		var result struct {
+			Content []struct {
+				Text string `json:"text"`
+			} `json:"content"`
+		}
+		if err := json.Unmarshal(raw, &result); err != nil || len(result.Content) != 1 {
+			return "", fmt.Errorf("unexpected result %s", raw)
+		}
+		return result.Content[0].Text, nil
+	}
+
+	earlier := make(chan string, 1)
+	go func() {
+		text, err := callText(map[string]interface{}{"text": "earlier", "delayMs": 300}, map[string]interface{}{"progressToken": "earlier"})
+		if err != nil {
+			text = err.Error()
+		}
+		earlier <- text
+	}()
+	select {
+	case <-started:
+	case <-time.After(5 * time.Second):
+		t.Fatal("Timed out waiting for the server to get the earlier call")
+	}
+
+	if text, err := callText(map[string]interface{}{"text": "stray"}, nil); err != nil || text != "stray" {
+		t.Errorf("Later call = %q, %v, want its own answer", text, err)
+	}
+	if text := <-earlier; text != "earlier" {
+		t.Errorf("Earlier call = %q, want its own answer rather than a response under a mismatched id", text)
+	}
+}
+
+func TestCallToolTimeout(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	slow := helperServerConfig("slow", "")
+	slow.TimeoutMs = 100
+	cfg := &config.Config{Servers: []config.ServerConfig{slow}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	request := mcp.CallToolRequest{}
+	request.Params.Name = "slow_echo"
+	request.Params.Arguments = map[string]interface{}{"text": "late", "delayMs": 5000}
+	_, err := agg.CallTool(context.Background(), request)
+	if err == nil {
+		t.Fatalf("CallTool() succeeded despite the timeout")
+	}
+	if !strings.Contains(err.Error(), "server slow timed out") {
+		t.Errorf("CallTool() error = %v, want a timeout error naming the server", err)
+	}
+}
+
+func TestCancelledCallIsCancelledOnServer(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	ctx, cancel := context.WithCancel(context.Background())
+	time.AfterFunc(50*time.Millisecond, cancel)
+	request := mcp.CallToolRequest{}
+	request.Params.Name = "helper_echo"
+	request.Params.Arguments = map[string]interface{}{"text": "slow", "delayMs": 10000}
+	started := time.Now()
+	if _, err := agg.CallTool(ctx, request); !errors.Is(err, context.Canceled) {
+		t.Fatalf("CallTool() error = %v, want context.Canceled", err)
+	}
+	if elapsed := time.Since(started); elapsed > 5*time.Second {
+		t.Errorf("CallTool() returned %v after it was cancelled", elapsed)
+	}
+
+	request.Params.Arguments = map[string]interface{}{"text": "cancelled"}
+	result, err := agg.CallTool(context.Background(), request)
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if got := result.Content[0].(mcp.TextContent).Text; got != "cancelled 1" {
+		t.Errorf("Server answered %q, want it to have been sent notifications/cancelled once", got)
+	}
+}
+
+// waitFor polls condition until it holds or the timeout expires
+func waitFor(t *testing.T, timeout time.Duration, what string, condition func() bool) {
+	t.Helper()
+	deadline := time.Now().Add(timeout)
+	for !condition() {
+		if time.Now().After(deadline) {
+			t.Fatalf("Timed out waiting for %s", what)
+		}
+		time.Sleep(10 * time.Millisecond)
+	}
+}
+
+func TestRestartCrashedServer(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	crashy := helperServerConfig("crashy", "crash")
+	crashy.MaxRestarts = 1
+	cfg := &config.Config{Servers: []config.ServerConfig{crashy}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	currentClient := func() MCPClient {
+		agg.mu.RLock()
+		defer agg.mu.RUnlock()
+		return agg.clients["crashy"]
+	}
+	original := currentClient()
+
+	// The crash is followed by a restart that brings the tools back
+	if _, err := callEcho(agg, "crashy_echo", "hello"); err == nil {
+		t.Fatalf("Call to a crashing server succeeded")
+	}
+	waitFor(t, 10*time.Second, "the restart", func() bool {
+		current := currentClient()
+		return current != nil && current != original && len(agg.GetTools()) == 1
+	})
+
+	// Another crash exceeds maxRestarts and the server is dropped
+	if _, err := callEcho(agg, "crashy_echo", "hello"); err == nil {
+		t.Fatalf("Call to a crashing server succeeded")
+	}
+	waitFor(t, 10*time.Second, "the server to be dropped", func() bool {
+		return currentClient() == nil
+	})
+	if got := len(agg.GetTools()); got != 0 {
+		t.Errorf("Got %d tools of a dropped server, want 0", got)
+	}
+}
+
+func TestKeepAliveRestartsWedgedServer(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	wedgy := helperServerConfig("wedgy", "")
+	wedgy.PingIntervalMs = 100
+	if err := agg.Initialize(context.Background(), &config.Config{Servers: []config.ServerConfig{wedgy}}); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	waitFor(t, 5*time.Second, "a successful ping", func() bool {
+		return agg.Status()[0].LastPing != nil
+	})
+	pid := agg.Status()[0].PID
+
+	// The server keeps running but stops answering, which only the keepalive notices
+	request := mcp.CallToolRequest{}
+	request.Params.Name = "wedgy_echo"
+	request.Params.Arguments = map[string]interface{}{"text": "hang"}
+	ctx, cancel := context.WithTimeout(context.Background(), 50*time.Millisecond)
+	defer cancel()
+	if _, err := agg.CallTool(ctx, request); err == nil {
+		t.Fatalf("Call that wedges the server succeeded")
+	}
+
+	waitFor(t, 10*time.Second, "the wedged server to be restarted", func() bool {
+		status := agg.Status()[0]
+		return status.PID != 0 && status.PID != pid && status.Running
+	})
+	waitFor(t, 5*time.Second, "the restarted server to answer", func() bool {
+		_, err := callEcho(agg, "wedgy_echo", "hello")
+		return err == nil
+	})
+}
+
+func TestPrompts(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	prompts := agg.GetPrompts()
+	if len(prompts) != 1 {
+		t.Fatalf("Got %d prompts, want 1", len(prompts))
+	}
+	if prompts[0].Name != "helper_greet" || prompts[0].Description != "[helper] Greet someone" {
+		t.Errorf("Prompt = %+v, want helper_greet with a prefixed description", prompts[0])
+	}
+	if len(prompts[0].Arguments) != 1 || prompts[0].Arguments[0].Name != "name" {
+		t.Errorf("Prompt arguments = %+v, want the server's name argument", prompts[0].Arguments)
+	}
+
+	request := mcp.GetPromptRequest{}
+	request.Params.Name = "helper_greet"
+	request.Params.Arguments = map[string]string{"name": "Ada"}
+	result, err := agg.GetPrompt(context.Background(), request)
+	if err != nil {
+		t.Fatalf("GetPrompt() error = %v", err)
+	}
+	if len(result.Messages) != 1 {
+		t.Fatalf("Got %d messages, want 1", len(result.Messages))
+	}
+	if text, ok := result.Messages[0].Content.(mcp.TextContent); !ok || text.Text != "Hello, Ada" {
+		t.Errorf("Message content = %+v, want the greeting with the passed argument", result.Messages[0].Content)
+	}
+}
+
+func TestToolListChangedNotification(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	changed := make(chan struct{}, 10)
+	agg.OnToolsChanged(func() { changed <- struct{}{} })
+
+	if _, err := callEcho(agg, "helper_echo", "grow"); err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	waitFor(t, 5*time.Second, "the grown tool", func() bool { return len(agg.GetTools()) == 2 })
+
+	tools := agg.GetTools()
+	if tools[0].Name != "helper_echo" || tools[1].Name != "helper_grown" {
+		t.Errorf("Tools = %s, %s, want helper_echo, helper_grown", tools[0].Name, tools[1].Name)
+	}
+	select {
+	case <-changed:
+	case <-time.After(5 * time.Second):
+		t.Error("Tools changed callback wasn't invoked")
+	}
+}
+
+func TestStatus(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	silent := helperServerConfig("silent", "silent")
+	silent.InitTimeoutMs = 200
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", ""), silent}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	statuses := agg.Status()
+	if len(statuses) != 2 {
+		t.Fatalf("Got %d statuses, want one per configured server", len(statuses))
+	}
+	helper := statuses[0]
+	if helper.Name != "helper" || !helper.Running || !helper.Healthy || helper.Tools != 1 || helper.PID <= 0 || helper.LastError != "" {
+		t.Errorf("Status of the working server = %+v, want running and healthy with 1 tool and a pid", helper)
+	}
+	failed := statuses[1]
+	if failed.Name != "silent" || failed.Running || failed.Tools != 0 || failed.LastError == "" {
+		t.Errorf("Status of the server that never answered = %+v, want not running with its error", failed)
+	}
+}
+
+func TestProgressPassthrough(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	progress := make(chan json.RawMessage, 1)
+	agg.OnProgress(func(params json.RawMessage) { progress <- params })
+
+	var request mcp.CallToolRequest
+	if err := json.Unmarshal([]byte(`{"method":"tools/call","params":{"name":"helper_echo","arguments":{"text":"hi"},"_meta":{"progressToken":"call-1"}}}`), &request); err != nil {
+		t.Fatalf("Failed to decode request: %v", err)
+	}
+	if _, err := agg.CallTool(context.Background(), request); err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+
+	select {
+	case params := <-progress:
+		var notification struct {
+			ProgressToken string  `json:"progressToken"`
+			Progress      float64 `json:"progress"`
+		}
+		if err := json.Unmarshal(params, &notification); err != nil || notification.ProgressToken != "call-1" || notification.Progress != 1 {
+			t.Errorf("Progress params = %s, want the server's progress with the client's token", params)
+		}
+	case <-time.After(5 * time.Second):
+		t.Error("Progress notification wasn't forwarded")
+	}
+}
+
+// startHelperClient starts the fake MCP server directly, without the aggregator around it
+func startHelperClient(t *testing.T, serverCfg config.ServerConfig) *stdioClient {
+	t.Helper()
+	var env []string
+	for key, value := range serverCfg.Env {
+		env = append(env, key+"="+value)
+	}
+	c, err := newStdioClient(&serverCfg, env)
+	if err != nil {
+		t.Fatalf("newStdioClient() error = %v", err)
+	}
+	return c
+}
+
+func TestSamplingRelay(t *testing.T) {
+	agg := NewMCPAggregator()
+	defer agg.Close()
+
+	relayed := make(chan json.RawMessage, 1)
+	agg.OnSampling(func(ctx context.Context, params json.RawMessage) (json.RawMessage, error) {
+		relayed <- params
+		return json.RawMessage(`{"role":"assistant","content":{"type":"text","text":"hi"},"model":"test-model"}`), nil
+	})
+
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("helper", "")}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+
+	request := mcp.CallToolRequest{}
+	request.Params.Name = "helper_echo"
+	request.Params.Arguments = map[string]interface{}{"text": "sample"}
+
+	// The server's sampling request reaches the client and the client's answer reaches the server
+	result, err := agg.CallTool(context.Background(), request)
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if text := result.Content[0].(mcp.TextContent).Text; result.IsError || text != "hi" {
+		t.Errorf("CallTool() = %q (isError %v), want the sampled text", text, result.IsError)
+	}
+	select {
+	case params := <-relayed:
+		if !strings.Contains(string(params), "Say hi") {
+			t.Errorf("Relayed params = %s, want the server's messages", params)
+		}
+	default:
+		t.Error("The sampling request wasn't relayed")
+	}
+
+	// A client that didn't declare sampling isn't asked; the server gets an error instead
+	agg.SetUpstreamClient(mcp.Implementation{Name: "plain-client", Version: "1.0.0"}, mcp.ClientCapabilities{})
+	result, err = agg.CallTool(context.Background(), request)
+	if err != nil {
+		t.Fatalf("CallTool() error = %v", err)
+	}
+	if text := result.Content[0].(mcp.TextContent).Text; !result.IsError || !strings.Contains(text, "not supported by the client") {
+		t.Errorf("CallTool() = %q (isError %v), want the sampling error", text, result.IsError)
+	}
+}
+
+func TestGracefulShutdown(t *testing.T) {
+	// A server that exits once its stdin closes doesn't need to be signalled
+	polite := startHelperClient(t, helperServerConfig("polite", ""))
+	start := time.Now()
+	if err := polite.Close(); err != nil {
+		t.Errorf("Close() error = %v, want a clean exit", err)
+	}
+	if elapsed := time.Since(start); elapsed >= defaultShutdownGrace {
+		t.Errorf("Close() took %v for a server that exits on its own", elapsed)
+	}
+
+	// A server ignoring both its stdin and SIGTERM is killed after the grace periods
+	stubbornCfg := helperServerConfig("stubborn", "stubborn")
+	stubbornCfg.ShutdownGraceMs = 100
+	stubborn := startHelperClient(t, stubbornCfg)
+	time.Sleep(100 * time.Millisecond) // Let it install its signal handling
+	start = time.Now()
+	if err := stubborn.Close(); err == nil {
+		t.Errorf("Close() reported a clean exit for a killed server")
+	}
+	if elapsed := time.Since(start); elapsed > 5*time.Second {
+		t.Errorf("Close() took %v, want the server killed after the grace periods", elapsed)
+	}
+	// Closing again doesn't wait a second time
+	if err := stubborn.Close(); err == nil {
+		t.Errorf("Second Close() lost the result of the first")
+	}
+}
+
+func TestShutdownReport(t *testing.T) {
+	stubbornCfg := helperServerConfig("stubborn", "stubborn")
+	stubbornCfg.ShutdownGraceMs = 100
+	agg := NewMCPAggregator()
+	cfg := &config.Config{Servers: []config.ServerConfig{helperServerConfig("polite", ""), stubbornCfg}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+	time.Sleep(100 * time.Millisecond) // Let the stubborn server install its signal handling
+
+	exits := agg.Close()
+	if len(exits) != 2 {
+		t.Fatalf("Close() = %v, want an exit for each server", exits)
+	}
+	if polite := exits[0]; polite.Name != "polite" || polite.Stopped != StoppedExited || polite.ExitCode != 0 || !polite.Clean() {
+		t.Errorf("exit of polite = %+v, want a clean exit with code 0", polite)
+	}
+	if stubborn := exits[1]; stubborn.Name != "stubborn" || stubborn.Stopped != StoppedKilled || stubborn.ExitCode != -1 || stubborn.Clean() {
+		t.Errorf("exit of stubborn = %+v, want it killed", stubborn)
+	}
+
+	want := "1 of 2 servers stopped cleanly; stubborn was killed after ignoring SIGTERM for 100ms"
+	if summary := SummarizeExits(exits); summary != want {
+		t.Errorf("SummarizeExits() = %q, want %q", summary, want)
+	}
+	if summary := SummarizeExits(agg.Close()); summary != "no servers were running" {
+		t.Errorf("SummarizeExits() after closing again = %q, want no servers", summary)
+	}
+}
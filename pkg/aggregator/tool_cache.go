@@ -0,0 +1,104 @@
+package aggregator
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// toolCache is the content of the tool cache file: the tools every server listed when it last ran
+type toolCache struct {
+	Servers map[string]cachedTools `json:"servers"`
+}
+
+// cachedTools are the tools a server listed, valid while its fingerprint matches its config
+type cachedTools struct {
+	Fingerprint string     `json:"fingerprint"`
+	Tools       []mcp.Tool `json:"tools"`
+}
+
+// serverFingerprint identifies what a server runs, so cached tools of a changed server aren't used
+func serverFingerprint(serverCfg *config.ServerConfig) string {
+	data, _ := json.Marshal([]interface{}{serverCfg.Command, serverCfg.Args, serverCfg.URL})
+	sum := sha256.Sum256(data)
+	return hex.EncodeToString(sum[:])
+}
+
+// loadToolCache reads the tool cache file; a missing or unreadable file is an empty cache
+func loadToolCache(path string) map[string]cachedTools {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		if !errors.Is(err, os.ErrNotExist) {
+			logger.Error("Failed to read tool cache %s: %v", path, err)
+		}
+		return nil
+	}
+
+	var cache toolCache
+	if err := json.Unmarshal(data, &cache); err != nil {
+		logger.Error("Ignoring malformed tool cache %s: %v", path, err)
+		return nil
+	}
+	return cache.Servers
+}
+
+// useCachedTools registers the cached tools of a server, reporting whether they are valid for its config
+func (a *MCPAggregator) useCachedTools(cache map[string]cachedTools, serverCfg *config.ServerConfig) bool {
+	cached, exists := cache[serverCfg.Name]
+	if !exists {
+		return false
+	}
+	if cached.Fingerprint != serverFingerprint(serverCfg) {
+		logger.Info("Cached tools of server %s are outdated, discovering them again", serverCfg.Name)
+		return false
+	}
+	if err := a.registerTools(serverCfg.Name, serverCfg, cached.Tools); err != nil {
+		logger.Error("Failed to register cached tools of server %s: %v", serverCfg.Name, err)
+		return false
+	}
+	return true
+}
+
+// saveToolCache writes the tools every server listed to the tool cache file, if one is configured
+func (a *MCPAggregator) saveToolCache() error {
+	a.mu.RLock()
+	path := a.cfg.ToolCacheFile
+	cache := toolCache{Servers: make(map[string]cachedTools)}
+	for i := range a.cfg.Servers {
+		serverCfg := &a.cfg.Servers[i]
+		if tools, exists := a.discovered[serverCfg.Name]; exists {
+			cache.Servers[serverCfg.Name] = cachedTools{Fingerprint: serverFingerprint(serverCfg), Tools: tools}
+		}
+	}
+	a.mu.RUnlock()
+
+	if path == "" {
+		return nil
+	}
+
+	data, err := json.MarshalIndent(cache, "", "  ")
+	if err != nil {
+		return fmt.Errorf("failed to encode tool cache: %w", err)
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0755); err != nil {
+		return fmt.Errorf("failed to create tool cache directory: %w", err)
+	}
+
+	// Replace the file at once, so a crash while writing never leaves a truncated cache
+	tmp := path + ".tmp"
+	if err := os.WriteFile(tmp, data, 0644); err != nil {
+		return fmt.Errorf("failed to write tool cache: %w", err)
+	}
+	if err := os.Rename(tmp, path); err != nil {
+		return fmt.Errorf("failed to write tool cache: %w", err)
+	}
+	return nil
+}
@@ -0,0 +1,89 @@
+package aggregator
+
+import (
+	"encoding/json"
+	"fmt"
+	"unicode/utf8"
+
+	"github.com/mark3labs/mcp-go/mcp"
+)
+
+// truncationMarker ends the text of a tool result that was cut down to its server's maxResponseBytes
+const truncationMarker = "\n\n[Result truncated: showing %d of %d bytes]"
+
+// contentSize returns how many bytes a content block adds to a result: its text for text blocks,
+// its JSON encoding for any other block
+func contentSize(content mcp.Content) int {
+	if text, ok := content.(mcp.TextContent); ok {
+		return len(text.Text)
+	}
+	encoded, _ := json.Marshal(content)
+	return len(encoded)
+}
+
+// truncateResult cuts the content of a tool result down to limit bytes, unless limit is 0. The text
+// block the limit falls into is cut short and the blocks after it are dropped; the result then ends
+// with a truncation marker and sets _meta.truncated. Results within the limit are returned as they are.
+func truncateResult(result *mcp.CallToolResult, limit int) *mcp.CallToolResult {
+	if result == nil || limit <= 0 {
+		return result
+	}
+	total := 0
+	for _, content := range result.Content {
+		total += contentSize(content)
+	}
+	if total <= limit {
+		return result
+	}
+
+	kept := make([]mcp.Content, 0, len(result.Content)+1)
+	shown := 0
+	for _, content := range result.Content {
+		size := contentSize(content)
+		if shown+size <= limit {
+			kept = append(kept, content)
+			shown += size
+			continue
+		}
+		if text, ok := content.(mcp.TextContent); ok && shown < limit {
+			text.Text = cutText(text.Text, limit-shown)
+			kept = append(kept, text)
+			shown += len(text.Text)
+		}
+		break
+	}
+
+	marker := fmt.Sprintf(truncationMarker, shown, total)
+	if last, ok := lastText(kept); ok {
+		last.Text += marker
+		kept[len(kept)-1] = last
+	} else {
+		kept = append(kept, textContent(marker))
+	}
+
+	truncated := *result
+	truncated.Content = kept
+	truncated.Meta = make(map[string]interface{}, len(result.Meta)+1)
+	for key, value := range result.Meta {
+		truncated.Meta[key] = value
+	}
+	truncated.Meta["truncated"] = true
+	return &truncated
+}
+
+// lastText returns the last content block if it is a text block
+func lastText(content []mcp.Content) (mcp.TextContent, bool) {
+	if len(content) == 0 {
+		return mcp.TextContent{}, false
+	}
+	text, ok := content[len(content)-1].(mcp.TextContent)
+	return text, ok
+}
+
+// cutText cuts text down to at most limit bytes without splitting a UTF-8 character
+func cutText(text string, limit int) string {
+	for limit > 0 && !utf8.RuneStart(text[limit]) {
+		limit--
+	}
+	return text[:limit]
+}
@@ -0,0 +1,72 @@
+package aggregator
+
+import (
+	"fmt"
+	"strings"
+	"testing"
+
+	"github.com/mark3labs/mcp-go/mcp"
+)
+
+func TestTruncateResult(t *testing.T) {
+	image := mcp.ImageContent{Type: "image", Data: "aGVsbG8=", MIMEType: "image/png"}
+	tests := []struct {
+		name      string
+		content   []mcp.Content
+		limit     int
+		wantTexts []string // text of each block after truncation
+	}{
+		{
+			name:      "Within the limit",
+			content:   []mcp.Content{textContent("hello")},
+			limit:     5,
+			wantTexts: []string{"hello"},
+		},
+		{
+			name:      "No limit",
+			content:   []mcp.Content{textContent(strings.Repeat("a", 100))},
+			wantTexts: []string{strings.Repeat("a", 100)},
+		},
+		{
+			name:      "Cut inside a text block",
+			content:   []mcp.Content{textContent("hello"), textContent("world"), textContent("again")},
+			limit:     7,
+			wantTexts: []string{"hello", "wo\n\n[Result truncated: showing 7 of 15 bytes]"},
+		},
+		{
+			name:      "Cut between multi-byte characters",
+			content:   []mcp.Content{textContent("héllo")},
+			limit:     2,
+			wantTexts: []string{"h\n\n[Result truncated: showing 1 of 6 bytes]"},
+		},
+		{
+			name:      "Non-text block over the limit",
+			content:   []mcp.Content{image, textContent("after")},
+			limit:     10,
+			wantTexts: []string{fmt.Sprintf(truncationMarker, 0, contentSize(image)+5)},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			result := &mcp.CallToolResult{Content: tt.content}
+			got := truncateResult(result, tt.limit)
+			if len(got.Content) != len(tt.wantTexts) {
+				t.Fatalf("truncateResult() has %d blocks, want %d", len(got.Content), len(tt.wantTexts))
+			}
+			for i, want := range tt.wantTexts {
+				if text, _ := got.Content[i].(mcp.TextContent); text.Text != want {
+					t.Errorf("Block %d text = %q, want %q", i, text.Text, want)
+				}
+			}
+
+			truncated, _ := got.Meta["truncated"].(bool)
+			if wantTruncated := got != result; truncated != wantTruncated {
+				t.Errorf("_meta.truncated = %v, want %v", truncated, wantTruncated)
+			}
+			if len(result.Content) != len(tt.content) || result.Meta != nil {
+				t.Errorf("truncateResult() changed the result it was given")
+			}
+		})
+	}
+}
@@ -0,0 +1,117 @@
+package config
+
+import (
+	"fmt"
+	"os"
+	"strings"
+)
+
+// splitCommandLine tokenizes a command line the way a POSIX shell would split words,
+// honoring single quotes, double quotes and backslash escapes. No expansion is performed.
+func splitCommandLine(line string) ([]string, error) {
+	var tokens []string
+	var current strings.Builder
+	inToken := false
+
+	for i := 0; i < len(line); i++ {
+		c := line[i]
+		switch {
+		case c == ' ' || c == '\t' || c == '\n':
+			if inToken {
+				tokens = append(tokens, current.String())
+				current.Reset()
+				inToken = false
+			}
+		case c == '\'':
+			inToken = true
+			end := strings.IndexByte(line[i+1:], '\'')
+			if end < 0 {
+				return nil, fmt.Errorf("unterminated single quote in %q", line)
+			}
+			current.WriteString(line[i+1 : i+1+end])
+			i += end + 1
+		case c == '"':
+			inToken = true
+			closed := false
+			for i++; i < len(line); i++ {
+				if line[i] == '"' {
+					closed = true
+					break
+				}
+				if line[i] == '\\' && i+1 < len(line) && strings.IndexByte("\"\\$`", line[i+1]) >= 0 {
+					i++
+				}
+				current.WriteByte(line[i])
+			}
+			if !closed {
+				return nil, fmt.Errorf("unterminated double quote in %q", line)
+			}
+		case c == '\\':
+			inToken = true
+			if i+1 < len(line) {
+				i++
+				current.WriteByte(line[i])
+			}
+		default:
+			inToken = true
+			current.WriteByte(c)
+		}
+	}
+
+	if inToken {
+		tokens = append(tokens, current.String())
+	}
+	return tokens, nil
+}
+
+// applyCommandLine splits a server's commandLine into its command and args, which it can't be given
+// together with
+func applyCommandLine(server *ServerConfig) error {
+	if server.CommandLine == "" {
+		return nil
+	}
+	if server.Command != "" || len(server.Args) > 0 {
+		return fmt.Errorf("server %s has both commandLine and command or args: use one of them", server.Name)
+	}
+
+	tokens, err := splitCommandLine(server.CommandLine)
+	if err != nil {
+		return fmt.Errorf("server %s: failed to split commandLine: %w", server.Name, err)
+	}
+	if len(tokens) == 0 {
+		return fmt.Errorf("server %s has an empty commandLine", server.Name)
+	}
+	server.Command = tokens[0]
+	server.Args = tokens[1:]
+	return nil
+}
+
+// normalizeCommand detects a full command line put into the command field.
+// Unless splitCommand is set this is an error, because the whole string would
+// otherwise be treated as the name of a single executable.
+func normalizeCommand(server *ServerConfig) error {
+	if !strings.ContainsAny(server.Command, " \t") || len(server.Args) > 0 {
+		return nil
+	}
+
+	// A path to an existing executable may legitimately contain spaces
+	if _, err := os.Stat(server.Command); err == nil {
+		return nil
+	}
+
+	if !server.SplitCommand {
+		return fmt.Errorf("server %s command %q contains spaces: put the program in \"command\" and its arguments in \"args\", give the whole line as \"commandLine\", or set \"splitCommand\": true", server.Name, server.Command)
+	}
+
+	tokens, err := splitCommandLine(server.Command)
+	if err != nil {
+		return fmt.Errorf("server %s: failed to split command: %w", server.Name, err)
+	}
+	if len(tokens) == 0 {
+		return fmt.Errorf("server %s missing command", server.Name)
+	}
+
+	server.Command = tokens[0]
+	server.Args = tokens[1:]
+	return nil
+}
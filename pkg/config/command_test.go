@@ -0,0 +1,179 @@
+package config
+
+import (
+	"reflect"
+	"strings"
+	"testing"
+)
+
+func TestSplitCommandLine(t *testing.T) {
+	tests := []struct {
+		name    string
+		line    string
+		want    []string
+		wantErr bool
+	}{
+		{
+			name: "Plain words",
+			line: "npx -y @shortcut/mcp",
+			want: []string{"npx", "-y", "@shortcut/mcp"},
+		},
+		{
+			name: "Double quotes keep spaces",
+			line: `server --name "a b"`,
+			want: []string{"server", "--name", "a b"},
+		},
+		{
+			name: "Single quotes are literal",
+			line: `echo 'a "b" \c'`,
+			want: []string{"echo", `a "b" \c`},
+		},
+		{
+			name: "Escaped quote inside double quotes",
+			line: `say "a \"b\""`,
+			want: []string{"say", `a "b"`},
+		},
+		{
+			name: "Backslash escapes a space",
+			line: `run a\ b`,
+			want: []string{"run", "a b"},
+		},
+		{
+			name: "Extra whitespace is ignored",
+			line: "  uvx   mcp-server  ",
+			want: []string{"uvx", "mcp-server"},
+		},
+		{
+			name:    "Unterminated double quote",
+			line:    `run "oops`,
+			wantErr: true,
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got, err := splitCommandLine(tt.line)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("splitCommandLine(%q) error = %v, wantErr %v", tt.line, err, tt.wantErr)
+			}
+			if !tt.wantErr && !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("splitCommandLine(%q) = %q, want %q", tt.line, got, tt.want)
+			}
+		})
+	}
+}
+
+func TestNormalizeCommand(t *testing.T) {
+	tests := []struct {
+		name        string
+		server      ServerConfig
+		wantCommand string
+		wantArgs    []string
+		wantErr     string
+	}{
+		{
+			name:        "Command without spaces is untouched",
+			server:      ServerConfig{Name: "github", Command: "npx", Args: []string{"-y", "server"}},
+			wantCommand: "npx",
+			wantArgs:    []string{"-y", "server"},
+		},
+		{
+			name:    "Command line without splitCommand is an error",
+			server:  ServerConfig{Name: "github", Command: "npx -y server"},
+			wantErr: "splitCommand",
+		},
+		{
+			name:        "Command line with splitCommand is tokenized",
+			server:      ServerConfig{Name: "github", Command: `npx -y server --flag "a b"`, SplitCommand: true},
+			wantCommand: "npx",
+			wantArgs:    []string{"-y", "server", "--flag", "a b"},
+		},
+		{
+			name:        "Explicit args leave the command alone",
+			server:      ServerConfig{Name: "github", Command: "my server", Args: []string{"--flag"}},
+			wantCommand: "my server",
+			wantArgs:    []string{"--flag"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			server := tt.server
+			err := normalizeCommand(&server)
+			if tt.wantErr != "" {
+				if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+					t.Fatalf("normalizeCommand() error = %v, want error containing %q", err, tt.wantErr)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatalf("normalizeCommand() error = %v", err)
+			}
+			if server.Command != tt.wantCommand || !reflect.DeepEqual(server.Args, tt.wantArgs) {
+				t.Errorf("normalizeCommand() = %q %q, want %q %q", server.Command, server.Args, tt.wantCommand, tt.wantArgs)
+			}
+		})
+	}
+}
+
+func TestApplyCommandLine(t *testing.T) {
+	tests := []struct {
+		name        string
+		server      ServerConfig
+		wantCommand string
+		wantArgs    []string
+		wantErr     string
+	}{
+		{
+			name:        "Command line is tokenized",
+			server:      ServerConfig{Name: "github", CommandLine: `npx -y @scope/server --flag "a b"`},
+			wantCommand: "npx",
+			wantArgs:    []string{"-y", "@scope/server", "--flag", "a b"},
+		},
+		{
+			name:        "Structured form is untouched",
+			server:      ServerConfig{Name: "github", Command: "npx", Args: []string{"-y", "server"}},
+			wantCommand: "npx",
+			wantArgs:    []string{"-y", "server"},
+		},
+		{
+			name:    "Command line with command",
+			server:  ServerConfig{Name: "github", CommandLine: "npx -y server", Command: "npx"},
+			wantErr: "server github has both commandLine and command or args",
+		},
+		{
+			name:    "Command line with args",
+			server:  ServerConfig{Name: "github", CommandLine: "npx", Args: []string{"-y"}},
+			wantErr: "both commandLine and command or args",
+		},
+		{
+			name:    "Only spaces",
+			server:  ServerConfig{Name: "github", CommandLine: "   "},
+			wantErr: "empty commandLine",
+		},
+		{
+			name:    "Unterminated quote",
+			server:  ServerConfig{Name: "github", CommandLine: `npx "server`},
+			wantErr: "unterminated double quote",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			server := tt.server
+			err := applyCommandLine(&server)
+			if tt.wantErr != "" {
+				if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+					t.Fatalf("applyCommandLine() error = %v, want error containing %q", err, tt.wantErr)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatalf("applyCommandLine() error = %v", err)
+			}
+			if server.Command != tt.wantCommand || !reflect.DeepEqual(server.Args, tt.wantArgs) {
+				t.Errorf("applyCommandLine() = %q %q, want %q %q", server.Command, server.Args, tt.wantCommand, tt.wantArgs)
+			}
+		})
+	}
+}
@@ -1,10 +1,14 @@
 package config
 
 import (
-	"encoding/json"
+	"errors"
 	"fmt"
 	"os"
+	"path"
+	"path/filepath"
+	"sort"
 	"strconv"
+	"strings"
 )
 
 const (
@@ -30,70 +34,300 @@ const (
 	LogLevelTrace
 )
 
-// ToolsConfig represents the tool filtering configuration for a server
+// ToolsConfig represents the tool filtering configuration for a server.
+// Entries are tool names or glob patterns such as create_*.
 type ToolsConfig struct {
 	Allowed []string `json:"allowed,omitempty"`
+	Denied  []string `json:"denied,omitempty"`
+}
+
+// SelfTestConfig describes a tool call that must succeed before a server is considered ready
+type SelfTestConfig struct {
+	Tool      string                 `json:"tool"`
+	Arguments map[string]interface{} `json:"arguments,omitempty"`
+}
+
+// ReadyCheckConfig describes how to tell that a server that listed its tools is ready to serve calls: after
+// an optional delay, a tool call that is retried until it succeeds
+type ReadyCheckConfig struct {
+	Tool      string                 `json:"tool,omitempty"`
+	Arguments map[string]interface{} `json:"arguments,omitempty"`
+	DelayMs   int                    `json:"delayMs,omitempty"`   // Time to wait before the first call, or before the server is ready without a tool
+	TimeoutMs int                    `json:"timeoutMs,omitempty"` // How long the server may take to get ready, 0 for the default
+}
+
+// RouteConfig points an exposed tool name at a specific tool of a specific server
+type RouteConfig struct {
+	Server string `json:"server"`
+	Tool   string `json:"tool"`
 }
 
 // ServerConfig represents the configuration for a single MCP server
 type ServerConfig struct {
-	Name    string            `json:"name"`
-	Command string            `json:"command"`
-	Args    []string          `json:"args,omitempty"`
-	Env     map[string]string `json:"env,omitempty"`
-	Tools   *ToolsConfig      `json:"tools,omitempty"` // Optional tool filtering
+	Name                  string            `json:"name"`
+	Command               string            `json:"command,omitempty"`
+	URL                   string            `json:"url,omitempty"`       // Remote server url, instead of command
+	Transport             string            `json:"transport,omitempty"` // stdio or sse, derived from command or url when omitted
+	Args                  []string          `json:"args,omitempty"`
+	Env                   map[string]string `json:"env,omitempty"`
+	EnvFile               string            `json:"envFile,omitempty"`               // File of NAME=value lines merged under env, relative to the config file
+	Tools                 *ToolsConfig      `json:"tools,omitempty"`                 // Optional tool filtering
+	MaxInFlight           int               `json:"maxInFlight,omitempty"`           // Max outstanding requests to the server, 0 for the default
+	MaxConcurrency        int               `json:"maxConcurrency,omitempty"`        // Max tool calls running on the server at once, 0 for no limit
+	SplitCommand          bool              `json:"splitCommand,omitempty"`          // Split a command line with spaces into command and args
+	CommandLine           string            `json:"commandLine,omitempty"`           // Full command line split shell-style into command and args, instead of them
+	ResultShape           string            `json:"resultShape,omitempty"`           // canonical (default) or lenient tool result parsing
+	MinTools              int               `json:"minTools,omitempty"`              // Minimum number of tools the server must list
+	MinToolsAction        string            `json:"minToolsAction,omitempty"`        // warn (default) or fail when minTools isn't met
+	SelfTest              *SelfTestConfig   `json:"selfTest,omitempty"`              // Optional tool call run after discovery
+	ReadyCheck            *ReadyCheckConfig `json:"readyCheck,omitempty"`            // Optional probe the server's tools stay hidden until it passes
+	InitTimeoutMs         int               `json:"initTimeoutMs,omitempty"`         // Handshake timeout in milliseconds, 0 for the default
+	TimeoutMs             int               `json:"timeoutMs,omitempty"`             // Tool call timeout in milliseconds, 0 for the default
+	MaxResponseBytes      int               `json:"maxResponseBytes,omitempty"`      // Tool result content kept before it's truncated, 0 for the global limit
+	MaxRestarts           int               `json:"maxRestarts,omitempty"`           // Restarts after crashes, 0 for the default, negative to disable
+	ShutdownGraceMs       int               `json:"shutdownGraceMs,omitempty"`       // Time to exit after each shutdown step in milliseconds, 0 for the default
+	ReconnectBackoffMs    int               `json:"reconnectBackoffMs,omitempty"`    // First delay before reconnecting a remote server, 0 for the default
+	MaxReconnectBackoffMs int               `json:"maxReconnectBackoffMs,omitempty"` // Longest delay between reconnects, 0 for the default
+	PingIntervalMs        int               `json:"pingIntervalMs,omitempty"`        // Keepalive ping interval in milliseconds, 0 disables it for local servers
+	Lazy                  bool              `json:"lazy,omitempty"`                  // Start the server when it's first needed instead of at startup
+	DependsOn             []string          `json:"dependsOn,omitempty"`             // Servers started before this one
+	Cwd                   string            `json:"cwd,omitempty"`                   // Working directory of the server process, relative to the config file
+	InheritEnv            *InheritEnv       `json:"inheritEnv,omitempty"`            // Aggregator environment variables the server inherits, all by default
+	DescriptionTemplate   string            `json:"descriptionTemplate,omitempty"`   // Template of the server's tool descriptions, the global one by default
+	Framing               string            `json:"framing,omitempty"`               // ndjson (default) or content-length framing of messages on the server's stdio
+	Tags                  []string          `json:"tags,omitempty"`                  // Labels a tools/list request can select the server's tools by
+	Weight                int               `json:"weight,omitempty"`                // Share of round-robin calls of deduplicated tools, 0 for the default of 1
+}
+
+// RoundRobinWeight returns the number of turns the server takes in each round of round-robin calls
+func (s *ServerConfig) RoundRobinWeight() int {
+	if s.Weight == 0 {
+		return 1
+	}
+	return s.Weight
+}
+
+// HasAnyTag reports whether the server carries any of the tags
+func (s *ServerConfig) HasAnyTag(tags map[string]bool) bool {
+	for _, tag := range s.Tags {
+		if tags[tag] {
+			return true
+		}
+	}
+	return false
+}
+
+// Actions taken when a server lists fewer tools than its minTools setting
+const (
+	// MinToolsActionWarn logs a warning and keeps the server (default)
+	MinToolsActionWarn = "warn"
+	// MinToolsActionFail treats the server as failed to initialize
+	MinToolsActionFail = "fail"
+)
+
+// Policies for a tool exposed under the same name as a tool of another server
+const (
+	// ToolCollisionSkip logs an error and keeps the tool registered first (default)
+	ToolCollisionSkip = "skip"
+	// ToolCollisionFail treats the server registering the second tool as failed to initialize
+	ToolCollisionFail = "fail"
+)
+
+// Precedences between a built-in tool and a server tool or route of the same name
+const (
+	// ToolPrecedenceRoutedFirst exposes the server tool or route and hides the built-in tool (default)
+	ToolPrecedenceRoutedFirst = "routedFirst"
+	// ToolPrecedenceBuiltinFirst exposes the built-in tool and hides the server tool or route
+	ToolPrecedenceBuiltinFirst = "builtinFirst"
+)
+
+// Dedupe policies for identical tools, same name and input schema, listed by several servers
+const (
+	// DedupeToolsOff exposes every server's copy of a tool (default)
+	DedupeToolsOff = "off"
+	// DedupeToolsFirstHealthy exposes one copy and calls the first healthy server listing it, in config order
+	DedupeToolsFirstHealthy = "first-healthy"
+	// DedupeToolsRoundRobin exposes one copy and spreads calls over the healthy servers listing it
+	DedupeToolsRoundRobin = "round-robin"
+)
+
+// Result shapes tell how strictly a server's tool results are parsed
+const (
+	// ResultShapeCanonical only accepts results with a content array (default)
+	ResultShapeCanonical = "canonical"
+	// ResultShapeLenient also maps known non-canonical result shapes to a canonical result
+	ResultShapeLenient = "lenient"
+)
+
+// Framings delimit JSON-RPC messages on stdin and stdout
+const (
+	// FramingNDJSON writes every message as a single line of JSON (default)
+	FramingNDJSON = "ndjson"
+	// FramingContentLength precedes every message with LSP-style headers announcing its length
+	FramingContentLength = "content-length"
+)
+
+// Formats of the log file
+const (
+	// LogFormatCompact writes every message as a line prefixed with its level, date and time (default)
+	LogFormatCompact = "compact"
+	// LogFormatPretty writes every message after a timestamp with milliseconds and its aligned level
+	LogFormatPretty = "pretty"
+	// LogFormatJSON writes every message as a JSON object with its time, level and message
+	LogFormatJSON = "json"
+)
+
+// How often the log file is rotated
+const (
+	// LogRotationNever keeps appending to the same log file (default)
+	LogRotationNever = "never"
+	// LogRotationHourly starts a new log file every hour
+	LogRotationHourly = "hourly"
+	// LogRotationDaily starts a new log file every day
+	LogRotationDaily = "daily"
+)
+
+// LogFileOptions are the settings of how the log file is written
+type LogFileOptions struct {
+	Format   string // compact (default), pretty or json
+	Rotation string // never (default), hourly or daily
+	MaxFiles int    // rotated log files kept, 0 for all of them
 }
 
+// Error argument modes control how much of a failed tool call's arguments is echoed back
+const (
+	// ErrorArgumentsOff never includes arguments in error responses (default)
+	ErrorArgumentsOff = "off"
+	// ErrorArgumentsKeys includes only the argument names
+	ErrorArgumentsKeys = "keys"
+	// ErrorArgumentsValues includes argument names and small, non-secret values
+	ErrorArgumentsValues = "values"
+)
+
 // Config represents the complete configuration for the MCP aggregator
 type Config struct {
-	Servers  []ServerConfig `json:"servers"`
-	LogLevel LogLevel       `json:"-"`
-	LogFile  string         `json:"-"`
+	Servers                []ServerConfig          `json:"servers"`
+	DefaultEnv             map[string]string       `json:"defaultEnv,omitempty"`             // env of every server started as a process, its own env winning
+	DefaultArgs            []string                `json:"defaultArgs,omitempty"`            // args put before the args of every server started as a process
+	ErrorArguments         string                  `json:"errorArguments,omitempty"`         // off, keys or values
+	PipelineOutput         bool                    `json:"pipelineOutput,omitempty"`         // write responses from a dedicated goroutine
+	CoalesceOutput         bool                    `json:"coalesceOutput,omitempty"`         // flush responses once per burst of requests instead of after each one
+	ToolErrorsAsContent    bool                    `json:"toolErrorsAsContent,omitempty"`    // report tool failures as isError results
+	HideUnhealthyTools     bool                    `json:"hideUnhealthyTools,omitempty"`     // hide tools of unhealthy servers from tools/list
+	UnhealthyAfterFailures int                     `json:"unhealthyAfterFailures,omitempty"` // consecutive failed calls before a server is unhealthy
+	StaleToolsMs           int                     `json:"staleToolsMs,omitempty"`           // how long a restarting server's tools stay listed, 0 for the default, negative to drop them at once
+	PropagateLogLevel      bool                    `json:"propagateLogLevel,omitempty"`      // forward logging/setLevel to servers
+	LegacyResults          bool                    `json:"legacyResults,omitempty"`          // flatten tool results into a single text block
+	Routes                 map[string]RouteConfig  `json:"routes,omitempty"`                 // exposed tool name -> server tool overrides
+	ToolOverrides          map[string]ToolOverride `json:"toolOverrides,omitempty"`          // exposed tool name -> description and input schema replacements
+	ToolNameSeparator      string                  `json:"toolNameSeparator,omitempty"`      // joins server and tool names, "_" by default
+	PrefixTools            *bool                   `json:"prefixTools,omitempty"`            // expose tools as server name, separator and tool name, true by default
+	ExposeBuiltinTools     *bool                   `json:"exposeBuiltinTools,omitempty"`     // expose the aggregator's status and metrics tools, true by default
+	ExposeReloadTool       bool                    `json:"exposeReloadTool,omitempty"`       // expose the combine_mcp_reload tool, which lets clients reload the config
+	OnToolCollision        string                  `json:"onToolCollision,omitempty"`        // skip (default) or fail when servers expose the same tool name
+	ToolPrecedence         string                  `json:"toolPrecedence,omitempty"`         // routedFirst (default) or builtinFirst when a tool has a built-in tool's name
+	RedactKeys             []string                `json:"redactKeys,omitempty"`             // extra env key substrings whose values are masked
+	ToolCacheFile          string                  `json:"toolCacheFile,omitempty"`          // where discovered tools are kept for fast restarts
+	PidFile                string                  `json:"pidFile,omitempty"`                // where server processes are recorded, so the next run reaps ones left behind
+	DescriptionTemplate    string                  `json:"descriptionTemplate,omitempty"`    // rewrites tool descriptions, "[{server}] {description}" by default
+	Framing                string                  `json:"framing,omitempty"`                // ndjson (default) or content-length framing of messages with the client
+	DedupeTools            string                  `json:"dedupeTools,omitempty"`            // off (default), first-healthy or round-robin exposure of identical tools of several servers
+	MaxResponseBytes       int                     `json:"maxResponseBytes,omitempty"`       // tool result content kept before it's truncated, 0 for no limit
+	LogFormat              string                  `json:"logFormat,omitempty"`              // compact (default), pretty or json lines in the log file
+	LogRotation            string                  `json:"logRotation,omitempty"`            // never (default), hourly or daily start of a new log file
+	LogMaxFiles            int                     `json:"logMaxFiles,omitempty"`            // rotated log files kept, 0 for all of them
+	LogLevel               LogLevel                `json:"-"`
+	ServerLogLevels        map[string]LogLevel     `json:"-"` // levels of single servers, overriding LogLevel for what concerns them
+	LogFile                string                  `json:"-"`
+	Warnings               []string                `json:"-"` // problems found while loading that don't prevent starting
+	Notes                  []string                `json:"-"` // how several config files were merged, for the log
+}
+
+// LogFileOptions returns the settings of how the log file is written
+func (c *Config) LogFileOptions() LogFileOptions {
+	return LogFileOptions{Format: c.LogFormat, Rotation: c.LogRotation, MaxFiles: c.LogMaxFiles}
+}
+
+// PrefixesToolNames reports whether tools are exposed under names prefixed with their server's name
+func (c *Config) PrefixesToolNames() bool {
+	return c.PrefixTools == nil || *c.PrefixTools
+}
+
+// ExposesBuiltinTools reports whether the aggregator's own tools are added to the tools of the servers
+func (c *Config) ExposesBuiltinTools() bool {
+	return c.ExposeBuiltinTools == nil || *c.ExposeBuiltinTools
+}
+
+// MaxResponseBytesFor returns the number of bytes of a tool result of a server kept before the rest is
+// truncated: the server's own limit, else the global one, 0 for no limit
+func (c *Config) MaxResponseBytesFor(server *ServerConfig) int {
+	if server != nil && server.MaxResponseBytes > 0 {
+		return server.MaxResponseBytes
+	}
+	return c.MaxResponseBytes
+}
+
+// DedupesTools reports whether identical tools of several servers are exposed only once
+func (c *Config) DedupesTools() bool {
+	return c.DedupeTools != "" && c.DedupeTools != DedupeToolsOff
 }
 
 // rawConfig is used to parse different config formats
 type rawConfig struct {
-	// Array format
-	Servers []ServerConfig `json:"servers"`
+	// Array format and top-level settings
+	Config
 	// Object format
-	MCPServers map[string]struct {
-		Command string            `json:"command"`
-		Args    []string          `json:"args,omitempty"`
-		Env     map[string]string `json:"env,omitempty"`
-		Tools   *ToolsConfig      `json:"tools,omitempty"`
-	} `json:"mcpServers"`
+	MCPServers map[string]ServerConfig `json:"mcpServers"`
+	// Names in MCPServers in the order the file lists them, where the format tells
+	serverNames []string
 }
 
 // GetLogLevel returns the configured log level from environment variables
 func GetLogLevel() LogLevel {
+	level, _ := GetLogLevels()
+	return level
+}
+
+// GetLogLevels returns the configured log level and the levels of single servers from environment
+// variables, see ParseLogLevels
+func GetLogLevels() (LogLevel, map[string]LogLevel) {
 	levelStr := os.Getenv(LogLevelEnvVar)
 	if levelStr == "" {
-		return LogLevelInfo // Default to info
+		return LogLevelInfo, nil // Default to info
+	}
+
+	level, serverLevels, err := ParseLogLevels(levelStr)
+	if err != nil {
+		return LogLevelInfo, nil
 	}
+	return level, serverLevels
+}
 
+// ParseLogLevel parses a log level given by name (error, info, debug, trace) or number
+func ParseLogLevel(levelStr string) (LogLevel, error) {
 	levelInt, err := strconv.Atoi(levelStr)
 	if err != nil {
 		// Handle string values
 		switch levelStr {
 		case "error":
-			return LogLevelError
+			return LogLevelError, nil
 		case "info":
-			return LogLevelInfo
+			return LogLevelInfo, nil
 		case "debug":
-			return LogLevelDebug
+			return LogLevelDebug, nil
 		case "trace":
-			return LogLevelTrace
+			return LogLevelTrace, nil
 		default:
-			return LogLevelInfo
+			return LogLevelInfo, fmt.Errorf("invalid log level %q: expected error, info, debug or trace", levelStr)
 		}
 	}
 
 	// Handle numeric values
 	level := LogLevel(levelInt)
 	if level < LogLevelError || level > LogLevelTrace {
-		return LogLevelInfo
+		return LogLevelInfo, fmt.Errorf("invalid log level %d: expected %d to %d", levelInt, LogLevelError, LogLevelTrace)
 	}
-	return level
+	return level, nil
 }
 
 // GetLogFile returns the log file path from environment variables
@@ -101,6 +335,9 @@ func GetLogFile() string {
 	return os.Getenv(LogToFileEnvVar)
 }
 
+// ErrConfigNotSet is returned when no config file is given
+var ErrConfigNotSet = errors.New("no config file given")
+
 // LoadConfig loads the configuration from the specified environment variable
 func LoadConfig(envVar string) (*Config, error) {
 	if envVar == "" {
@@ -109,9 +346,50 @@ func LoadConfig(envVar string) (*Config, error) {
 
 	configPath := os.Getenv(envVar)
 	if configPath == "" {
-		return nil, fmt.Errorf("environment variable %s not set", envVar)
+		return nil, fmt.Errorf("%w: environment variable %s not set", ErrConfigNotSet, envVar)
+	}
+
+	return LoadConfigFile(configPath)
+}
+
+// LoadConfigFile loads the configuration from the file at configPath, or from several files given as a
+// comma-separated list, which are merged in order with later files taking precedence
+func LoadConfigFile(configPath string) (*Config, error) {
+	paths := splitConfigPaths(configPath)
+	if len(paths) == 0 {
+		return nil, fmt.Errorf("no config file given")
 	}
 
+	var config Config
+	for i, filePath := range paths {
+		fileConfig, err := readConfigFile(filePath)
+		if err != nil {
+			return nil, err
+		}
+		if i == 0 {
+			config = *fileConfig
+			continue
+		}
+		config.Notes = append(config.Notes, mergeConfig(&config, fileConfig, filePath)...)
+	}
+	config.LogLevel, config.ServerLogLevels = GetLogLevels()
+	if _, _, err := ParseLogLevels(os.Getenv(LogLevelEnvVar)); err != nil && os.Getenv(LogLevelEnvVar) != "" {
+		config.Warnings = append(config.Warnings, fmt.Sprintf("%s ignored, using level info: %v", LogLevelEnvVar, err))
+	}
+	config.LogFile = GetLogFile()
+
+	if err := config.prepare(); err != nil {
+		if len(paths) > 1 {
+			err = fmt.Errorf("%w (in the config merged from %s, later files taking precedence)", err, strings.Join(paths, ", "))
+		}
+		return nil, err
+	}
+	return &config, nil
+}
+
+// readConfigFile reads a single config file, with servers in the object format converted to the array format
+// and relative working directories and env files resolved against the file's directory
+func readConfigFile(configPath string) (*Config, error) {
 	configData, err := os.ReadFile(configPath)
 	if err != nil {
 		return nil, fmt.Errorf("error reading config file: %w", err)
@@ -119,43 +397,192 @@ func LoadConfig(envVar string) (*Config, error) {
 
 	// Try to parse the config in different formats
 	var raw rawConfig
-	if err := json.Unmarshal(configData, &raw); err != nil {
-		return nil, fmt.Errorf("error parsing config file: %w", err)
+	if err := decodeConfig(configPath, configData, &raw); err != nil {
+		return nil, fmt.Errorf("error parsing config file %s: %w", configPath, err)
 	}
+	config := raw.Config
 
-	var config Config
-	config.LogLevel = GetLogLevel()
-	config.LogFile = GetLogFile()
+	// Servers in the array format take precedence over the object format
+	if len(config.Servers) == 0 && len(raw.MCPServers) > 0 {
+		// Convert the object format to our standard format, keeping the order of the file
+		for _, name := range objectServerNames(&raw) {
+			server := raw.MCPServers[name]
+			server.Name = name
+			config.Servers = append(config.Servers, server)
+		}
+	}
 
-	// Check if we have servers in the array format
-	if len(raw.Servers) > 0 {
-		config.Servers = raw.Servers
-	} else if len(raw.MCPServers) > 0 {
-		// Convert the object format to our standard format
-		for name, server := range raw.MCPServers {
-			config.Servers = append(config.Servers, ServerConfig{
-				Name:    name,
-				Command: server.Command,
-				Args:    server.Args,
-				Env:     server.Env,
-				Tools:   server.Tools,
-			})
+	// Relative working directories and env files are relative to the config file, not to where the aggregator runs
+	configDir, err := filepath.Abs(filepath.Dir(configPath))
+	if err != nil {
+		return nil, fmt.Errorf("error resolving config file directory: %w", err)
+	}
+	for i := range config.Servers {
+		server := &config.Servers[i]
+		if server.Cwd != "" && !filepath.IsAbs(server.Cwd) {
+			server.Cwd = filepath.Join(configDir, server.Cwd)
+		}
+		if server.EnvFile != "" && !filepath.IsAbs(server.EnvFile) {
+			server.EnvFile = filepath.Join(configDir, server.EnvFile)
+		}
+	}
+	return &config, nil
+}
+
+// objectServerNames returns the names of the servers in the object format in the order the file lists them,
+// followed by any the order wasn't known for, sorted by name
+func objectServerNames(raw *rawConfig) []string {
+	names := make([]string, 0, len(raw.MCPServers))
+	listed := make(map[string]bool, len(raw.MCPServers))
+	for _, name := range raw.serverNames {
+		if _, exists := raw.MCPServers[name]; exists && !listed[name] {
+			names = append(names, name)
+			listed[name] = true
+		}
+	}
+	var rest []string
+	for name := range raw.MCPServers {
+		if !listed[name] {
+			rest = append(rest, name)
 		}
 	}
+	sort.Strings(rest)
+	return append(names, rest...)
+}
 
-	if len(config.Servers) == 0 {
-		return nil, fmt.Errorf("no servers defined in config")
+// prepare expands, normalizes and validates the loaded servers and settings
+func (c *Config) prepare() error {
+	if len(c.Servers) == 0 {
+		return fmt.Errorf("no servers defined in config")
 	}
 
+	c.Warnings = append(c.Warnings, c.expandDefaults()...)
+
 	// Validate server configuration
-	for i, server := range config.Servers {
+	for i := range c.Servers {
+		server := &c.Servers[i]
 		if server.Name == "" {
-			return nil, fmt.Errorf("server at index %d missing name", i)
+			return fmt.Errorf("server at index %d missing name", i)
+		}
+		if err := applyCommandLine(server); err != nil {
+			return err
+		}
+		c.Warnings = append(c.Warnings, expandEnvReferences(server)...)
+		if err := validateTransport(server); err != nil {
+			return err
+		}
+		if server.EffectiveTransport() == TransportStdio {
+			if err := normalizeCommand(server); err != nil {
+				return err
+			}
+		}
+		if err := applyEnvOverrides(server); err != nil {
+			return err
+		}
+		if err := applyEnvFile(server); err != nil {
+			return err
 		}
-		if server.Command == "" {
-			return nil, fmt.Errorf("server %s missing command", server.Name)
+		c.applyDefaults(server)
+		switch server.MinToolsAction {
+		case "", MinToolsActionWarn, MinToolsActionFail:
+		default:
+			return fmt.Errorf("server %s has invalid minToolsAction %q: expected %s or %s", server.Name, server.MinToolsAction, MinToolsActionWarn, MinToolsActionFail)
+		}
+		switch server.ResultShape {
+		case "", ResultShapeCanonical, ResultShapeLenient:
+		default:
+			return fmt.Errorf("server %s has invalid resultShape %q: expected %s or %s", server.Name, server.ResultShape, ResultShapeCanonical, ResultShapeLenient)
+		}
+		if err := validateFraming(server.Framing); err != nil {
+			return fmt.Errorf("server %s has %w", server.Name, err)
+		}
+		if server.Tools != nil {
+			for _, pattern := range append(append([]string{}, server.Tools.Allowed...), server.Tools.Denied...) {
+				if _, err := path.Match(pattern, ""); err != nil {
+					return fmt.Errorf("server %s has invalid tool pattern %q: %w", server.Name, pattern, err)
+				}
+			}
+		}
+		if server.Weight < 0 {
+			return fmt.Errorf("server %s has a negative weight", server.Name)
+		}
+		if server.SelfTest != nil && server.SelfTest.Tool == "" {
+			return fmt.Errorf("server %s has a selfTest without a tool", server.Name)
+		}
+		if check := server.ReadyCheck; check != nil {
+			if check.Tool == "" && check.DelayMs <= 0 {
+				return fmt.Errorf("server %s has a readyCheck without a tool or delayMs", server.Name)
+			}
+			if check.DelayMs < 0 || check.TimeoutMs < 0 {
+				return fmt.Errorf("server %s has a readyCheck with a negative delayMs or timeoutMs", server.Name)
+			}
 		}
 	}
 
-	return &config, nil
+	for name, route := range c.Routes {
+		if route.Server == "" || route.Tool == "" {
+			return fmt.Errorf("route %s must name a server and a tool", name)
+		}
+	}
+
+	for name, override := range c.ToolOverrides {
+		if err := validateToolOverride(name, override); err != nil {
+			return err
+		}
+	}
+
+	switch c.ErrorArguments {
+	case "", ErrorArgumentsOff, ErrorArgumentsKeys, ErrorArgumentsValues:
+	default:
+		return fmt.Errorf("invalid errorArguments %q: expected %s, %s or %s", c.ErrorArguments, ErrorArgumentsOff, ErrorArgumentsKeys, ErrorArgumentsValues)
+	}
+
+	switch c.OnToolCollision {
+	case "", ToolCollisionSkip, ToolCollisionFail:
+	default:
+		return fmt.Errorf("invalid onToolCollision %q: expected %s or %s", c.OnToolCollision, ToolCollisionSkip, ToolCollisionFail)
+	}
+
+	switch c.ToolPrecedence {
+	case "", ToolPrecedenceRoutedFirst, ToolPrecedenceBuiltinFirst:
+	default:
+		return fmt.Errorf("invalid toolPrecedence %q: expected %s or %s", c.ToolPrecedence, ToolPrecedenceRoutedFirst, ToolPrecedenceBuiltinFirst)
+	}
+
+	if err := validateFraming(c.Framing); err != nil {
+		return err
+	}
+
+	switch c.LogFormat {
+	case "", LogFormatCompact, LogFormatPretty, LogFormatJSON:
+	default:
+		return fmt.Errorf("invalid logFormat %q: expected %s, %s or %s", c.LogFormat, LogFormatCompact, LogFormatPretty, LogFormatJSON)
+	}
+
+	switch c.LogRotation {
+	case "", LogRotationNever, LogRotationHourly, LogRotationDaily:
+	default:
+		return fmt.Errorf("invalid logRotation %q: expected %s, %s or %s", c.LogRotation, LogRotationNever, LogRotationHourly, LogRotationDaily)
+	}
+	if c.LogMaxFiles < 0 {
+		return fmt.Errorf("invalid logMaxFiles %d: expected a number of files", c.LogMaxFiles)
+	}
+
+	switch c.DedupeTools {
+	case "", DedupeToolsOff, DedupeToolsFirstHealthy, DedupeToolsRoundRobin:
+	default:
+		return fmt.Errorf("invalid dedupeTools %q: expected %s, %s or %s", c.DedupeTools, DedupeToolsOff, DedupeToolsFirstHealthy, DedupeToolsRoundRobin)
+	}
+
+	return c.validate()
+}
+
+// validateFraming checks that a framing, if set, is one of the known ones
+func validateFraming(framing string) error {
+	switch framing {
+	case "", FramingNDJSON, FramingContentLength:
+		return nil
+	default:
+		return fmt.Errorf("invalid framing %q: expected %s or %s", framing, FramingNDJSON, FramingContentLength)
+	}
 }
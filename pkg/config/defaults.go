@@ -0,0 +1,74 @@
+package config
+
+import (
+	"fmt"
+	"sort"
+)
+
+// expandDefaults replaces the environment variable references in defaultEnv and defaultArgs, returning
+// warnings for references to unset variables
+func (c *Config) expandDefaults() []string {
+	var warnings []string
+	expand := func(field, value string) string {
+		return expandReferences(value, func(reference string) {
+			warnings = append(warnings, fmt.Sprintf("%s has unresolved placeholder %s", field, reference))
+		})
+	}
+
+	for i, arg := range c.DefaultArgs {
+		c.DefaultArgs[i] = expand(fmt.Sprintf("defaultArgs arg %d", i), arg)
+	}
+	names := make([]string, 0, len(c.DefaultEnv))
+	for name := range c.DefaultEnv {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		c.DefaultEnv[name] = expand("defaultEnv "+name, c.DefaultEnv[name])
+	}
+	return warnings
+}
+
+// applyDefaults merges defaultEnv into the env of a server started as a process, its own values winning,
+// and puts defaultArgs, as one block, right after its command, before its own args, unless its args already
+// have the whole block in that order
+func (c *Config) applyDefaults(server *ServerConfig) {
+	if server.EffectiveTransport() != TransportStdio {
+		return
+	}
+
+	if len(c.DefaultEnv) > 0 {
+		env := make(map[string]string, len(c.DefaultEnv)+len(server.Env))
+		for name, value := range c.DefaultEnv {
+			env[name] = value
+		}
+		for name, value := range server.Env {
+			env[name] = value
+		}
+		server.Env = env
+	}
+
+	// Arguments such as a flag and its value only make sense together, so they are never split up
+	if len(c.DefaultArgs) > 0 && !containsSequence(server.Args, c.DefaultArgs) {
+		args := make([]string, 0, len(c.DefaultArgs)+len(server.Args))
+		args = append(args, c.DefaultArgs...)
+		server.Args = append(args, server.Args...)
+	}
+}
+
+// containsSequence reports whether args contain every element of sequence, one right after another
+func containsSequence(args, sequence []string) bool {
+	for start := 0; start+len(sequence) <= len(args); start++ {
+		matches := true
+		for i, arg := range sequence {
+			if args[start+i] != arg {
+				matches = false
+				break
+			}
+		}
+		if matches {
+			return true
+		}
+	}
+	return false
+}
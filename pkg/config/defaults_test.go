@@ -0,0 +1,75 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+)
+
+func TestServerDefaults(t *testing.T) {
+	t.Setenv("TEST_PROXY", "http://proxy:3128")
+	configPath := filepath.Join(t.TempDir(), "config.json")
+	configJSON := `{
+		"defaultEnv": {"NODE_OPTIONS": "--max-old-space-size=512", "HTTPS_PROXY": "${TEST_PROXY}"},
+		"defaultArgs": ["-y"],
+		"mcpServers": {
+			"github": {"command": "npx", "args": ["@modelcontextprotocol/server-github"], "env": {"NODE_OPTIONS": "--inspect"}},
+			"shortcut": {"command": "npx", "args": ["-y", "@shortcut/mcp"]},
+			"remote": {"url": "https://mcp.example.com/sse"}
+		}
+	}`
+	if err := os.WriteFile(configPath, []byte(configJSON), 0644); err != nil {
+		t.Fatalf("Failed to write test config file: %v", err)
+	}
+
+	cfg, err := LoadConfigFile(configPath)
+	if err != nil {
+		t.Fatalf("LoadConfigFile() error = %v", err)
+	}
+
+	want := map[string]ServerConfig{
+		"github": {
+			Args: []string{"-y", "@modelcontextprotocol/server-github"},
+			Env:  map[string]string{"NODE_OPTIONS": "--inspect", "HTTPS_PROXY": "http://proxy:3128"},
+		},
+		"shortcut": {
+			Args: []string{"-y", "@shortcut/mcp"},
+			Env:  map[string]string{"NODE_OPTIONS": "--max-old-space-size=512", "HTTPS_PROXY": "http://proxy:3128"},
+		},
+		// Servers that aren't started as a process have no use for them
+		"remote": {},
+	}
+	for _, server := range cfg.Servers {
+		if !reflect.DeepEqual(server.Args, want[server.Name].Args) {
+			t.Errorf("Args of server %s = %v, want %v", server.Name, server.Args, want[server.Name].Args)
+		}
+		if !reflect.DeepEqual(server.Env, want[server.Name].Env) {
+			t.Errorf("Env of server %s = %v, want %v", server.Name, server.Env, want[server.Name].Env)
+		}
+	}
+}
+
+func TestDefaultArgsBlock(t *testing.T) {
+	cfg := &Config{DefaultArgs: []string{"--port", "80"}}
+	tests := []struct {
+		name string
+		args []string
+		want []string
+	}{
+		{name: "Added before the own args", args: []string{"serve"}, want: []string{"--port", "80", "serve"}},
+		{name: "Value alone doesn't count", args: []string{"serve", "80"}, want: []string{"--port", "80", "serve", "80"}},
+		{name: "Flag alone doesn't count", args: []string{"--port", "8080"}, want: []string{"--port", "80", "--port", "8080"}},
+		{name: "Whole block already there", args: []string{"serve", "--port", "80"}, want: []string{"serve", "--port", "80"}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			server := ServerConfig{Name: "server", Command: "server-command", Args: tt.args}
+			cfg.applyDefaults(&server)
+			if !reflect.DeepEqual(server.Args, tt.want) {
+				t.Errorf("Args = %v, want %v", server.Args, tt.want)
+			}
+		})
+	}
+}
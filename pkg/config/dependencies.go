@@ -0,0 +1,65 @@
+package config
+
+import (
+	"fmt"
+	"strings"
+)
+
+// StartOrder returns the servers in the order they are started: every server after the servers it
+// depends on, and otherwise in config order. It fails on unknown dependencies and dependency cycles.
+func (c *Config) StartOrder() ([]*ServerConfig, error) {
+	byName := make(map[string]*ServerConfig, len(c.Servers))
+	for i := range c.Servers {
+		byName[c.Servers[i].Name] = &c.Servers[i]
+	}
+
+	order := make([]*ServerConfig, 0, len(c.Servers))
+	started := make(map[string]bool, len(c.Servers))
+	starting := make(map[string]bool)
+	var path []string
+
+	var visit func(server *ServerConfig) error
+	visit = func(server *ServerConfig) error {
+		if started[server.Name] {
+			return nil
+		}
+		path = append(path, server.Name)
+		defer func() { path = path[:len(path)-1] }()
+		if starting[server.Name] {
+			return fmt.Errorf("servers depend on each other: %s", strings.Join(path[cycleStart(path):], " -> "))
+		}
+		starting[server.Name] = true
+
+		for _, name := range server.DependsOn {
+			dependency, exists := byName[name]
+			if !exists {
+				return fmt.Errorf("server %s depends on unknown server %s", server.Name, name)
+			}
+			if err := visit(dependency); err != nil {
+				return err
+			}
+		}
+
+		started[server.Name] = true
+		order = append(order, server)
+		return nil
+	}
+
+	for i := range c.Servers {
+		if err := visit(&c.Servers[i]); err != nil {
+			return nil, err
+		}
+	}
+	return order, nil
+}
+
+// cycleStart returns where the cycle closed by the last server of a dependency path begins
+func cycleStart(path []string) int {
+	last := path[len(path)-1]
+	for i, name := range path[:len(path)-1] {
+		if name == last {
+			return i
+		}
+	}
+	return 0
+}
@@ -0,0 +1,55 @@
+package config
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestStartOrder(t *testing.T) {
+	tests := []struct {
+		name    string
+		servers []ServerConfig
+		want    string
+	}{
+		{
+			name:    "No dependencies keep config order",
+			servers: []ServerConfig{{Name: "github"}, {Name: "shortcut"}},
+			want:    "github,shortcut",
+		},
+		{
+			name: "Dependencies start first",
+			servers: []ServerConfig{
+				{Name: "proxy", DependsOn: []string{"registry"}},
+				{Name: "github"},
+				{Name: "gateway", DependsOn: []string{"proxy", "github"}},
+				{Name: "registry"},
+			},
+			want: "registry,proxy,github,gateway",
+		},
+		{
+			name:    "Self dependency",
+			servers: []ServerConfig{{Name: "github", DependsOn: []string{"github"}}},
+			want:    "error: servers depend on each other: github -> github",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			config := Config{Servers: tt.servers}
+			order, err := config.StartOrder()
+			var got string
+			if err != nil {
+				got = "error: " + err.Error()
+			} else {
+				names := make([]string, 0, len(order))
+				for _, server := range order {
+					names = append(names, server.Name)
+				}
+				got = strings.Join(names, ",")
+			}
+			if got != tt.want {
+				t.Errorf("StartOrder() = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
@@ -0,0 +1,50 @@
+package config
+
+import (
+	"fmt"
+	"regexp"
+	"strings"
+)
+
+// DefaultDescriptionTemplate names the server a tool comes from at the start of its description
+const DefaultDescriptionTemplate = "[{server}] {description}"
+
+// descriptionPlaceholderPattern matches the placeholders of a description template
+var descriptionPlaceholderPattern = regexp.MustCompile(`\{[^{}]*\}`)
+
+// descriptionPlaceholders are the placeholders a description template may use
+var descriptionPlaceholders = map[string]bool{
+	"{server}":      true,
+	"{tool}":        true,
+	"{description}": true,
+}
+
+// DescriptionTemplateFor returns the description template of a server's tools: its own, else the global one, else the default
+func (c *Config) DescriptionTemplateFor(server *ServerConfig) string {
+	if server != nil && server.DescriptionTemplate != "" {
+		return server.DescriptionTemplate
+	}
+	if c.DescriptionTemplate != "" {
+		return c.DescriptionTemplate
+	}
+	return DefaultDescriptionTemplate
+}
+
+// DescribeTool fills a description template with a server name, the tool's name on that server and its description
+func DescribeTool(template, serverName, toolName, description string) string {
+	return strings.NewReplacer(
+		"{server}", serverName,
+		"{tool}", toolName,
+		"{description}", description,
+	).Replace(template)
+}
+
+// validateDescriptionTemplate rejects templates with placeholders other than {server}, {tool} and {description}
+func validateDescriptionTemplate(template string) error {
+	for _, placeholder := range descriptionPlaceholderPattern.FindAllString(template, -1) {
+		if !descriptionPlaceholders[placeholder] {
+			return fmt.Errorf("description template %q has unknown placeholder %s: expected {server}, {tool} or {description}", template, placeholder)
+		}
+	}
+	return nil
+}
@@ -0,0 +1,39 @@
+package config
+
+import "testing"
+
+func TestDescribeTool(t *testing.T) {
+	tests := []struct {
+		name   string
+		config Config
+		server ServerConfig
+		want   string
+	}{
+		{
+			name:   "Default template",
+			server: ServerConfig{Name: "github"},
+			want:   "[github] Create an issue",
+		},
+		{
+			name:   "Global template",
+			config: Config{DescriptionTemplate: "{description} (from {server}, as {tool})"},
+			server: ServerConfig{Name: "github"},
+			want:   "Create an issue (from github, as create_issue)",
+		},
+		{
+			name:   "Server template overrides the global one",
+			config: Config{DescriptionTemplate: "{server}: {description}"},
+			server: ServerConfig{Name: "github", DescriptionTemplate: "{description}"},
+			want:   "Create an issue",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			template := tt.config.DescriptionTemplateFor(&tt.server)
+			if got := DescribeTool(template, tt.server.Name, "create_issue", "Create an issue"); got != tt.want {
+				t.Errorf("DescribeTool() = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
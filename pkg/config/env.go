@@ -0,0 +1,89 @@
+package config
+
+import (
+	"fmt"
+	"os"
+	"regexp"
+	"sort"
+	"strconv"
+	"strings"
+)
+
+// envReferencePattern matches ${VAR} and $VAR references to environment variables
+var envReferencePattern = regexp.MustCompile(`\$\{([A-Za-z_][A-Za-z0-9_]*)\}|\$([A-Za-z_][A-Za-z0-9_]*)`)
+
+// ServerTimeoutEnvVarFormat names the variable overriding a server's timeoutMs, given its env name
+const ServerTimeoutEnvVarFormat = "MCP_SERVER_%s_TIMEOUT_MS"
+
+// ServerCwdEnvVarFormat names the variable overriding a server's cwd, given its env name
+const ServerCwdEnvVarFormat = "MCP_SERVER_%s_CWD"
+
+// ServerEnvName converts a server name to the form used in environment variable names,
+// upper-casing it and replacing anything but letters and digits with underscores
+func ServerEnvName(serverName string) string {
+	return strings.Map(func(r rune) rune {
+		switch {
+		case r >= 'a' && r <= 'z':
+			return r - 'a' + 'A'
+		case r >= 'A' && r <= 'Z', r >= '0' && r <= '9':
+			return r
+		default:
+			return '_'
+		}
+	}, serverName)
+}
+
+// applyEnvOverrides applies per-server settings given through environment variables
+func applyEnvOverrides(server *ServerConfig) error {
+	envVar := fmt.Sprintf(ServerTimeoutEnvVarFormat, ServerEnvName(server.Name))
+	if value := os.Getenv(envVar); value != "" {
+		timeoutMs, err := strconv.Atoi(value)
+		if err != nil || timeoutMs < 0 {
+			return fmt.Errorf("invalid %s %q: expected a number of milliseconds", envVar, value)
+		}
+		server.TimeoutMs = timeoutMs
+	}
+	if cwd := os.Getenv(fmt.Sprintf(ServerCwdEnvVarFormat, ServerEnvName(server.Name))); cwd != "" {
+		server.Cwd = cwd
+	}
+	return nil
+}
+
+// expandEnvReferences replaces ${VAR} and $VAR references in a server's args and env values with
+// the value of the environment variable. References to unset variables are left as they are and
+// returned as warnings.
+func expandEnvReferences(server *ServerConfig) []string {
+	var warnings []string
+	expand := func(field, value string) string {
+		return expandReferences(value, func(reference string) {
+			warnings = append(warnings, fmt.Sprintf("server %s has %s with unresolved placeholder %s", server.Name, field, reference))
+		})
+	}
+
+	for i, arg := range server.Args {
+		server.Args[i] = expand(fmt.Sprintf("arg %d", i), arg)
+	}
+
+	envNames := make([]string, 0, len(server.Env))
+	for name := range server.Env {
+		envNames = append(envNames, name)
+	}
+	sort.Strings(envNames)
+	for _, name := range envNames {
+		server.Env[name] = expand("env "+name, server.Env[name])
+	}
+	return warnings
+}
+
+// expandReferences replaces the ${VAR} and $VAR references in a value, passing those to unset
+// variables, which are left as they are, to unresolved
+func expandReferences(value string, unresolved func(reference string)) string {
+	return envReferencePattern.ReplaceAllStringFunc(value, func(reference string) string {
+		match := envReferencePattern.FindStringSubmatch(reference)
+		if resolved, ok := os.LookupEnv(match[1] + match[2]); ok {
+			return resolved
+		}
+		unresolved(reference)
+		return reference
+	})
+}
@@ -0,0 +1,64 @@
+package config
+
+import (
+	"bufio"
+	"bytes"
+	"fmt"
+	"os"
+	"strings"
+)
+
+// parseEnvFile parses the KEY=VALUE lines of an env file. Blank lines and lines starting with # are
+// skipped, an export prefix is allowed and a value may be wrapped in single or double quotes. Values are
+// taken literally, without expanding references to other variables.
+func parseEnvFile(data []byte) (map[string]string, error) {
+	env := make(map[string]string)
+	scanner := bufio.NewScanner(bytes.NewReader(data))
+	for lineNumber := 1; scanner.Scan(); lineNumber++ {
+		line := strings.TrimSpace(scanner.Text())
+		if line == "" || strings.HasPrefix(line, "#") {
+			continue
+		}
+		line = strings.TrimPrefix(line, "export ")
+
+		name, value, found := strings.Cut(line, "=")
+		name = strings.TrimSpace(name)
+		if !found || name == "" || strings.ContainsAny(name, " \t") {
+			return nil, fmt.Errorf("line %d: expected NAME=value", lineNumber)
+		}
+		value = strings.TrimSpace(value)
+		if len(value) >= 2 && (value[0] == '"' || value[0] == '\'') && value[len(value)-1] == value[0] {
+			value = value[1 : len(value)-1]
+		}
+		env[name] = value
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	return env, nil
+}
+
+// applyEnvFile merges the variables of a server's envFile into its env, the values set in env winning
+func applyEnvFile(server *ServerConfig) error {
+	if server.EnvFile == "" {
+		return nil
+	}
+	if server.EffectiveTransport() != TransportStdio {
+		return fmt.Errorf("server %s has an envFile but isn't started as a process", server.Name)
+	}
+
+	data, err := os.ReadFile(server.EnvFile)
+	if err != nil {
+		return fmt.Errorf("server %s has an unreadable envFile: %w", server.Name, err)
+	}
+	fileEnv, err := parseEnvFile(data)
+	if err != nil {
+		return fmt.Errorf("server %s has an invalid envFile %s: %w", server.Name, server.EnvFile, err)
+	}
+
+	for name, value := range server.Env {
+		fileEnv[name] = value
+	}
+	server.Env = fileEnv
+	return nil
+}
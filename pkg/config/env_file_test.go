@@ -0,0 +1,55 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+func TestParseEnvFile(t *testing.T) {
+	data := "# GitHub credentials\n\nGITHUB_TOKEN=ghp_$ecret\nexport GITHUB_ORG = \"acme corp\"\nSINGLE='it''s'\nEMPTY=\n"
+	env, err := parseEnvFile([]byte(data))
+	if err != nil {
+		t.Fatalf("parseEnvFile() error = %v", err)
+	}
+	want := map[string]string{"GITHUB_TOKEN": "ghp_$ecret", "GITHUB_ORG": "acme corp", "SINGLE": "it''s", "EMPTY": ""}
+	if !reflect.DeepEqual(env, want) {
+		t.Errorf("parseEnvFile() = %v, want %v", env, want)
+	}
+
+	if _, err := parseEnvFile([]byte("GOOD=1\nnot a variable\n")); err == nil || !strings.Contains(err.Error(), "line 2") {
+		t.Errorf("parseEnvFile() error = %v, want one naming line 2", err)
+	}
+}
+
+func TestEnvFile(t *testing.T) {
+	dir := t.TempDir()
+	if err := os.WriteFile(filepath.Join(dir, "github.env"), []byte("GITHUB_TOKEN=from-file\nGITHUB_ORG=acme\n"), 0600); err != nil {
+		t.Fatalf("Failed to write env file: %v", err)
+	}
+	configPath := filepath.Join(dir, "config.json")
+	configJSON := `{"mcpServers": {"github": {"command": "npx", "envFile": "github.env", "env": {"GITHUB_ORG": "inline"}}}}`
+	if err := os.WriteFile(configPath, []byte(configJSON), 0644); err != nil {
+		t.Fatalf("Failed to write test config file: %v", err)
+	}
+
+	cfg, err := LoadConfigFile(configPath)
+	if err != nil {
+		t.Fatalf("LoadConfigFile() error = %v", err)
+	}
+	want := map[string]string{"GITHUB_TOKEN": "from-file", "GITHUB_ORG": "inline"}
+	if !reflect.DeepEqual(cfg.Servers[0].Env, want) {
+		t.Errorf("Env = %v, want %v", cfg.Servers[0].Env, want)
+	}
+
+	// A missing file keeps the config from loading
+	configJSON = `{"mcpServers": {"github": {"command": "npx", "envFile": "missing.env"}}}`
+	if err := os.WriteFile(configPath, []byte(configJSON), 0644); err != nil {
+		t.Fatalf("Failed to write test config file: %v", err)
+	}
+	if _, err := LoadConfigFile(configPath); err == nil || !strings.Contains(err.Error(), "envFile") {
+		t.Errorf("LoadConfigFile() error = %v, want one about the envFile", err)
+	}
+}
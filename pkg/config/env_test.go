@@ -0,0 +1,119 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+)
+
+func TestServerEnvName(t *testing.T) {
+	tests := []struct {
+		input    string
+		expected string
+	}{
+		{input: "shortcut", expected: "SHORTCUT"},
+		{input: "my-server.v2", expected: "MY_SERVER_V2"},
+		{input: "Already_OK9", expected: "ALREADY_OK9"},
+	}
+
+	for _, tt := range tests {
+		if got := ServerEnvName(tt.input); got != tt.expected {
+			t.Errorf("ServerEnvName(%q) = %q, want %q", tt.input, got, tt.expected)
+		}
+	}
+}
+
+func TestTimeoutEnvOverride(t *testing.T) {
+	configPath := filepath.Join(t.TempDir(), "config.json")
+	configJSON := `{"mcpServers": {"slow-server": {"command": "/path/to/slow", "timeoutMs": 1000}}}`
+	if err := os.WriteFile(configPath, []byte(configJSON), 0644); err != nil {
+		t.Fatalf("Failed to write test config file: %v", err)
+	}
+	t.Setenv("TEST_CONFIG", configPath)
+
+	cfg, err := LoadConfig("TEST_CONFIG")
+	if err != nil {
+		t.Fatalf("LoadConfig() error = %v", err)
+	}
+	if got := cfg.Servers[0].TimeoutMs; got != 1000 {
+		t.Errorf("TimeoutMs = %d, want 1000 from the file", got)
+	}
+
+	t.Setenv("MCP_SERVER_SLOW_SERVER_TIMEOUT_MS", "5000")
+	cfg, err = LoadConfig("TEST_CONFIG")
+	if err != nil {
+		t.Fatalf("LoadConfig() error = %v", err)
+	}
+	if got := cfg.Servers[0].TimeoutMs; got != 5000 {
+		t.Errorf("TimeoutMs = %d, want 5000 from the environment", got)
+	}
+
+	t.Setenv("MCP_SERVER_SLOW_SERVER_TIMEOUT_MS", "soon")
+	if _, err := LoadConfig("TEST_CONFIG"); err == nil {
+		t.Errorf("LoadConfig() accepted an invalid timeout override")
+	}
+}
+
+func TestExpandEnvReferences(t *testing.T) {
+	t.Setenv("TEST_GH_PAT", "ghp_123")
+	t.Setenv("TEST_EMPTY", "")
+	os.Unsetenv("TEST_UNSET")
+
+	server := &ServerConfig{
+		Name: "github",
+		Args: []string{"--token=$TEST_GH_PAT", "${TEST_UNSET}", "plain"},
+		Env: map[string]string{
+			"GITHUB_TOKEN": "${TEST_GH_PAT}",
+			"EMPTY":        "[$TEST_EMPTY]",
+			"UNSET":        "$TEST_UNSET-suffix",
+		},
+	}
+	warnings := expandEnvReferences(server)
+
+	wantArgs := []string{"--token=ghp_123", "${TEST_UNSET}", "plain"}
+	if !reflect.DeepEqual(server.Args, wantArgs) {
+		t.Errorf("Args = %v, want %v", server.Args, wantArgs)
+	}
+	wantEnv := map[string]string{"GITHUB_TOKEN": "ghp_123", "EMPTY": "[]", "UNSET": "$TEST_UNSET-suffix"}
+	if !reflect.DeepEqual(server.Env, wantEnv) {
+		t.Errorf("Env = %v, want %v", server.Env, wantEnv)
+	}
+	wantWarnings := []string{
+		"server github has arg 1 with unresolved placeholder ${TEST_UNSET}",
+		"server github has env UNSET with unresolved placeholder $TEST_UNSET",
+	}
+	if !reflect.DeepEqual(warnings, wantWarnings) {
+		t.Errorf("warnings = %v, want %v", warnings, wantWarnings)
+	}
+}
+
+func TestCwd(t *testing.T) {
+	configDir := t.TempDir()
+	configPath := filepath.Join(configDir, "config.json")
+	configJSON := `{"mcpServers": {
+		"relative": {"command": "/path/to/server", "cwd": "data"},
+		"absolute": {"command": "/path/to/server", "cwd": "/srv/data"},
+		"default": {"command": "/path/to/server"}
+	}}`
+	if err := os.WriteFile(configPath, []byte(configJSON), 0644); err != nil {
+		t.Fatalf("Failed to write test config file: %v", err)
+	}
+	t.Setenv("MCP_SERVER_DEFAULT_CWD", "override")
+
+	cfg, err := LoadConfigFile(configPath)
+	if err != nil {
+		t.Fatalf("LoadConfigFile() error = %v", err)
+	}
+
+	want := map[string]string{
+		"relative": filepath.Join(configDir, "data"),
+		"absolute": "/srv/data",
+		"default":  filepath.Join(configDir, "override"),
+	}
+	for _, server := range cfg.Servers {
+		if server.Cwd != want[server.Name] {
+			t.Errorf("Cwd of server %s = %q, want %q", server.Name, server.Cwd, want[server.Name])
+		}
+	}
+}
@@ -0,0 +1,150 @@
+package config
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"path/filepath"
+	"strings"
+
+	"github.com/BurntSushi/toml"
+	"gopkg.in/yaml.v3"
+)
+
+// decodeConfig parses config data in the format given by the file extension of configPath.
+// Files with any other extension, or none, are read as JSON.
+func decodeConfig(configPath string, data []byte, raw *rawConfig) error {
+	switch strings.ToLower(filepath.Ext(configPath)) {
+	case ".yaml", ".yml":
+		return decodeYAML(data, raw)
+	case ".toml":
+		return decodeTOML(data, raw)
+	default:
+		return decodeJSON(data, raw)
+	}
+}
+
+// decodeJSON parses JSON config data, noting the order of the servers in the object format
+func decodeJSON(data []byte, raw *rawConfig) error {
+	if err := json.Unmarshal(data, raw); err != nil {
+		return err
+	}
+	raw.serverNames = jsonServerNames(data)
+	return nil
+}
+
+// jsonServerNames returns the keys of the mcpServers object of a JSON config in the order they're written
+func jsonServerNames(data []byte) []string {
+	decoder := json.NewDecoder(bytes.NewReader(data))
+	if token, err := decoder.Token(); err != nil || token != json.Delim('{') {
+		return nil
+	}
+	for decoder.More() {
+		key, err := decoder.Token()
+		if err != nil {
+			return nil
+		}
+		if key != "mcpServers" {
+			var skipped json.RawMessage
+			if err := decoder.Decode(&skipped); err != nil {
+				return nil
+			}
+			continue
+		}
+
+		if token, err := decoder.Token(); err != nil || token != json.Delim('{') {
+			return nil
+		}
+		var names []string
+		for decoder.More() {
+			name, err := decoder.Token()
+			if err != nil {
+				return nil
+			}
+			var skipped json.RawMessage
+			if err := decoder.Decode(&skipped); err != nil {
+				return nil
+			}
+			names = append(names, name.(string))
+		}
+		return names
+	}
+	return nil
+}
+
+// decodeYAML parses YAML config data. The document is converted to JSON first so
+// that the same field names and parsing rules apply as for JSON configs.
+func decodeYAML(data []byte, raw *rawConfig) error {
+	var document interface{}
+	if err := yaml.Unmarshal(data, &document); err != nil {
+		return err
+	}
+	if err := decodeDocument(document, raw); err != nil {
+		return err
+	}
+
+	// The order of mappings is only kept by the nodes of the document
+	var node yaml.Node
+	if err := yaml.Unmarshal(data, &node); err == nil {
+		raw.serverNames = yamlServerNames(&node)
+	}
+	return nil
+}
+
+// yamlServerNames returns the keys of the mcpServers mapping of a YAML config in the order they're written
+func yamlServerNames(document *yaml.Node) []string {
+	if document.Kind != yaml.DocumentNode || len(document.Content) == 0 {
+		return nil
+	}
+	root := document.Content[0]
+	if root.Kind != yaml.MappingNode {
+		return nil
+	}
+	for i := 0; i+1 < len(root.Content); i += 2 {
+		if root.Content[i].Value != "mcpServers" {
+			continue
+		}
+		servers := root.Content[i+1]
+		if servers.Kind == yaml.AliasNode {
+			servers = servers.Alias
+		}
+		if servers.Kind != yaml.MappingNode {
+			return nil
+		}
+		names := make([]string, 0, len(servers.Content)/2)
+		for j := 0; j+1 < len(servers.Content); j += 2 {
+			names = append(names, servers.Content[j].Value)
+		}
+		return names
+	}
+	return nil
+}
+
+// decodeTOML parses TOML config data, converted to JSON first like YAML
+func decodeTOML(data []byte, raw *rawConfig) error {
+	var document map[string]interface{}
+	metadata, err := toml.Decode(string(data), &document)
+	if err != nil {
+		return err
+	}
+	if err := decodeDocument(document, raw); err != nil {
+		return err
+	}
+
+	// The keys of a TOML document are listed in the order they're written
+	for _, key := range metadata.Keys() {
+		if len(key) == 2 && key[0] == "mcpServers" {
+			raw.serverNames = append(raw.serverNames, key[1])
+		}
+	}
+	return nil
+}
+
+// decodeDocument decodes a generic config document by way of its JSON representation
+func decodeDocument(document interface{}, raw *rawConfig) error {
+	jsonData, err := json.Marshal(document)
+	if err != nil {
+		return fmt.Errorf("config can't be represented as JSON: %w", err)
+	}
+	return json.Unmarshal(jsonData, raw)
+}
@@ -0,0 +1,118 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+)
+
+func TestConfigFormats(t *testing.T) {
+	jsonConfig := `{"mcpServers": {"shortcut": {"command": "npx", "args": ["-y", "@shortcut/mcp"], "tools": {"allowed": ["get-*"]}, "timeoutMs": 5000}}, "toolNameSeparator": "__"}`
+	yamlConfig := `
+mcpServers:
+  shortcut:
+    command: npx
+    args: ["-y", "@shortcut/mcp"]
+    tools:
+      allowed:
+        - get-*
+    timeoutMs: 5000
+toolNameSeparator: __
+`
+	tomlConfig := `
+toolNameSeparator = "__"
+
+[mcpServers.shortcut]
+command = "npx"
+args = ["-y", "@shortcut/mcp"]
+timeoutMs = 5000
+
+[mcpServers.shortcut.tools]
+allowed = ["get-*"]
+`
+	want := &Config{
+		Servers: []ServerConfig{{
+			Name:      "shortcut",
+			Command:   "npx",
+			Args:      []string{"-y", "@shortcut/mcp"},
+			Tools:     &ToolsConfig{Allowed: []string{"get-*"}},
+			TimeoutMs: 5000,
+		}},
+		ToolNameSeparator: "__",
+		LogLevel:          LogLevelInfo,
+	}
+
+	tests := []struct {
+		name     string
+		fileName string
+		content  string
+		wantErr  bool
+	}{
+		{name: "json", fileName: "config.json", content: jsonConfig},
+		{name: "json without extension", fileName: "config", content: jsonConfig},
+		{name: "yaml", fileName: "config.yaml", content: yamlConfig},
+		{name: "yml", fileName: "config.YML", content: yamlConfig},
+		{name: "invalid yaml", fileName: "config.yaml", content: "mcpServers: [", wantErr: true},
+		{name: "toml", fileName: "config.toml", content: tomlConfig},
+		{name: "invalid toml", fileName: "config.toml", content: "[mcpServers", wantErr: true},
+		{name: "unknown extension", fileName: "mcp.conf", content: jsonConfig},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			configPath := filepath.Join(t.TempDir(), tt.fileName)
+			if err := os.WriteFile(configPath, []byte(tt.content), 0644); err != nil {
+				t.Fatalf("Failed to write config file: %v", err)
+			}
+			t.Setenv("TEST_CONFIG", configPath)
+			t.Setenv(LogLevelEnvVar, "")
+			t.Setenv(LogToFileEnvVar, "")
+
+			got, err := LoadConfig("TEST_CONFIG")
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("LoadConfig() error = %v, wantErr %v", err, tt.wantErr)
+			}
+			if !tt.wantErr && !reflect.DeepEqual(got, want) {
+				t.Errorf("LoadConfig() = %+v, want %+v", got, want)
+			}
+		})
+	}
+}
+
+// TestObjectFormatServerOrder loads servers in the object format, which would all expose the same tools, many
+// times and checks they keep the order of the file, which decides the server that keeps a colliding tool name
+func TestObjectFormatServerOrder(t *testing.T) {
+	wantNames := []string{"zeta", "alpha", "mid", "beta", "omega"}
+	tests := []struct {
+		fileName string
+		content  string
+	}{
+		{fileName: "config.json", content: `{"mcpServers": {"zeta": {"command": "npx"}, "alpha": {"command": "npx"}, "mid": {"command": "npx"}, "beta": {"command": "npx"}, "omega": {"command": "npx"}}}`},
+		{fileName: "config.yaml", content: "mcpServers:\n  zeta: {command: npx}\n  alpha: {command: npx}\n  mid: {command: npx}\n  beta: {command: npx}\n  omega: {command: npx}\n"},
+		{fileName: "config.toml", content: "[mcpServers.zeta]\ncommand = \"npx\"\n[mcpServers.alpha]\ncommand = \"npx\"\n[mcpServers.mid]\ncommand = \"npx\"\n[mcpServers.beta]\ncommand = \"npx\"\n[mcpServers.omega]\ncommand = \"npx\"\n"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.fileName, func(t *testing.T) {
+			configPath := filepath.Join(t.TempDir(), tt.fileName)
+			if err := os.WriteFile(configPath, []byte(tt.content), 0644); err != nil {
+				t.Fatalf("Failed to write config file: %v", err)
+			}
+
+			for i := 0; i < 50; i++ {
+				cfg, err := LoadConfigFile(configPath)
+				if err != nil {
+					t.Fatalf("LoadConfigFile() error = %v", err)
+				}
+				names := make([]string, 0, len(cfg.Servers))
+				for _, server := range cfg.Servers {
+					names = append(names, server.Name)
+				}
+				if !reflect.DeepEqual(names, wantNames) {
+					t.Fatalf("Load %d got servers %v, want %v", i, names, wantNames)
+				}
+			}
+		})
+	}
+}
@@ -0,0 +1,58 @@
+package config
+
+import (
+	"encoding/json"
+	"fmt"
+	"strings"
+)
+
+// InheritEnv selects the environment variables of the aggregator a server inherits.
+// In config it is either true or false, inheriting all or none, or a list of variable names.
+type InheritEnv struct {
+	All   bool
+	Names []string
+}
+
+// UnmarshalJSON accepts a boolean or a list of variable names
+func (e *InheritEnv) UnmarshalJSON(data []byte) error {
+	var all bool
+	if err := json.Unmarshal(data, &all); err == nil {
+		*e = InheritEnv{All: all}
+		return nil
+	}
+
+	var names []string
+	if err := json.Unmarshal(data, &names); err != nil {
+		return fmt.Errorf("inheritEnv must be true, false or a list of variable names")
+	}
+	*e = InheritEnv{Names: names}
+	return nil
+}
+
+// MarshalJSON writes the form UnmarshalJSON reads
+func (e InheritEnv) MarshalJSON() ([]byte, error) {
+	if e.Names != nil {
+		return json.Marshal(e.Names)
+	}
+	return json.Marshal(e.All)
+}
+
+// InheritedEnv returns the variables of environ, given as KEY=value, the server inherits.
+// Without inheritEnv the server inherits all of them.
+func (s *ServerConfig) InheritedEnv(environ []string) []string {
+	if s.InheritEnv == nil || (s.InheritEnv.All && s.InheritEnv.Names == nil) {
+		return environ
+	}
+
+	inherited := make([]string, 0, len(s.InheritEnv.Names))
+	for _, variable := range environ {
+		key, _, _ := strings.Cut(variable, "=")
+		for _, name := range s.InheritEnv.Names {
+			if key == name {
+				inherited = append(inherited, variable)
+				break
+			}
+		}
+	}
+	return inherited
+}
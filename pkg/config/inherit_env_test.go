@@ -0,0 +1,60 @@
+package config
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+)
+
+func TestInheritEnv(t *testing.T) {
+	environ := []string{"PATH=/usr/bin", "HOME=/home/user", "AWS_SECRET=secret", "HTTP_PROXY=http://proxy:3128"}
+
+	tests := []struct {
+		name    string
+		config  string
+		want    []string
+		wantErr bool
+	}{
+		{
+			name:   "Omitted inherits everything",
+			config: `{"name": "server", "command": "server"}`,
+			want:   environ,
+		},
+		{
+			name:   "True inherits everything",
+			config: `{"name": "server", "command": "server", "inheritEnv": true}`,
+			want:   environ,
+		},
+		{
+			name:   "False inherits nothing",
+			config: `{"name": "server", "command": "server", "inheritEnv": false}`,
+			want:   []string{},
+		},
+		{
+			name:   "List inherits the named variables",
+			config: `{"name": "server", "command": "server", "inheritEnv": ["PATH", "HTTP_PROXY", "MISSING"]}`,
+			want:   []string{"PATH=/usr/bin", "HTTP_PROXY=http://proxy:3128"},
+		},
+		{
+			name:    "Other values are rejected",
+			config:  `{"name": "server", "command": "server", "inheritEnv": "PATH"}`,
+			wantErr: true,
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var server ServerConfig
+			err := json.Unmarshal([]byte(tt.config), &server)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("Unmarshal() error = %v, wantErr %v", err, tt.wantErr)
+			}
+			if tt.wantErr {
+				return
+			}
+			if got := server.InheritedEnv(environ); !reflect.DeepEqual(got, tt.want) {
+				t.Errorf("InheritedEnv() = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
@@ -0,0 +1,44 @@
+package config
+
+import (
+	"fmt"
+	"strings"
+)
+
+// ParseLogLevels parses a log level optionally followed by comma-separated server=level directives,
+// e.g. "info,github=trace", which set the level of the messages a server exchanges and the details
+// logged about it. Without a bare level the default level is info.
+func ParseLogLevels(spec string) (LogLevel, map[string]LogLevel, error) {
+	level := LogLevelInfo
+	var serverLevels map[string]LogLevel
+	hasLevel := false
+	for _, directive := range strings.Split(spec, ",") {
+		directive = strings.TrimSpace(directive)
+		serverName, value, targeted := strings.Cut(directive, "=")
+		if !targeted {
+			if hasLevel {
+				return LogLevelInfo, nil, fmt.Errorf("invalid log level %q: only one level may be given without a server", spec)
+			}
+			parsed, err := ParseLogLevel(directive)
+			if err != nil {
+				return LogLevelInfo, nil, err
+			}
+			level, hasLevel = parsed, true
+			continue
+		}
+
+		serverName, value = strings.TrimSpace(serverName), strings.TrimSpace(value)
+		if serverName == "" || value == "" {
+			return LogLevelInfo, nil, fmt.Errorf("invalid log level directive %q: expected server=level", directive)
+		}
+		serverLevel, err := ParseLogLevel(value)
+		if err != nil {
+			return LogLevelInfo, nil, fmt.Errorf("invalid log level of server %s: %w", serverName, err)
+		}
+		if serverLevels == nil {
+			serverLevels = make(map[string]LogLevel)
+		}
+		serverLevels[serverName] = serverLevel
+	}
+	return level, serverLevels, nil
+}
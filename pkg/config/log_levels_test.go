@@ -0,0 +1,48 @@
+package config
+
+import (
+	"reflect"
+	"strings"
+	"testing"
+)
+
+func TestParseLogLevels(t *testing.T) {
+	tests := []struct {
+		spec             string
+		wantLevel        LogLevel
+		wantServerLevels map[string]LogLevel
+		wantErr          string
+	}{
+		{spec: "debug", wantLevel: LogLevelDebug},
+		{spec: "2", wantLevel: LogLevelDebug},
+		{
+			spec:             "error, github=trace,shortcut=debug",
+			wantLevel:        LogLevelError,
+			wantServerLevels: map[string]LogLevel{"github": LogLevelTrace, "shortcut": LogLevelDebug},
+		},
+		{spec: "github=trace", wantLevel: LogLevelInfo, wantServerLevels: map[string]LogLevel{"github": LogLevelTrace}},
+		{spec: "verbose", wantErr: `invalid log level "verbose"`},
+		{spec: "info,debug", wantErr: "only one level may be given without a server"},
+		{spec: "info,github=", wantErr: `invalid log level directive "github=": expected server=level`},
+		{spec: "info,=trace", wantErr: "expected server=level"},
+		{spec: "info,github=loud", wantErr: `invalid log level of server github: invalid log level "loud"`},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.spec, func(t *testing.T) {
+			level, serverLevels, err := ParseLogLevels(tt.spec)
+			if tt.wantErr != "" {
+				if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+					t.Errorf("ParseLogLevels() error = %v, want it to contain %q", err, tt.wantErr)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatalf("ParseLogLevels() error = %v", err)
+			}
+			if level != tt.wantLevel || !reflect.DeepEqual(serverLevels, tt.wantServerLevels) {
+				t.Errorf("ParseLogLevels() = %v, %v, want %v, %v", level, serverLevels, tt.wantLevel, tt.wantServerLevels)
+			}
+		})
+	}
+}
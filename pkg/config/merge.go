@@ -0,0 +1,71 @@
+package config
+
+import (
+	"fmt"
+	"reflect"
+	"strings"
+)
+
+// ConfigPathSeparator separates the config files of a list given in MCP_CONFIG or with --config
+const ConfigPathSeparator = ","
+
+// splitConfigPaths splits a comma-separated list of config files, ignoring empty entries
+func splitConfigPaths(configPaths string) []string {
+	var paths []string
+	for _, configPath := range strings.Split(configPaths, ConfigPathSeparator) {
+		if configPath = strings.TrimSpace(configPath); configPath != "" {
+			paths = append(paths, configPath)
+		}
+	}
+	return paths
+}
+
+// mergeConfig merges a config file into the config of the files before it. A server of the same name
+// replaces the earlier one in its place, other servers are added, maps such as routes are merged key by
+// key and every other setting the file sets replaces the earlier value. It returns notes on what the file
+// replaced, for the log.
+func mergeConfig(config *Config, later *Config, laterPath string) []string {
+	var notes []string
+	for _, server := range later.Servers {
+		replaced := false
+		for i := range config.Servers {
+			if config.Servers[i].Name == server.Name {
+				config.Servers[i] = server
+				replaced = true
+				break
+			}
+		}
+		if replaced {
+			notes = append(notes, fmt.Sprintf("Server %s from %s replaces the one from an earlier config file", server.Name, laterPath))
+			continue
+		}
+		config.Servers = append(config.Servers, server)
+	}
+
+	target := reflect.ValueOf(config).Elem()
+	source := reflect.ValueOf(later).Elem()
+	for i := 0; i < target.NumField(); i++ {
+		field := target.Type().Field(i)
+		name, _, _ := strings.Cut(field.Tag.Get("json"), ",")
+		if field.Name == "Servers" || name == "-" || source.Field(i).IsZero() {
+			continue
+		}
+
+		if field.Type.Kind() == reflect.Map && !target.Field(i).IsNil() {
+			entries := source.Field(i).MapRange()
+			for entries.Next() {
+				if target.Field(i).MapIndex(entries.Key()).IsValid() {
+					notes = append(notes, fmt.Sprintf("Entry %v of %s from %s replaces the one from an earlier config file", entries.Key(), name, laterPath))
+				}
+				target.Field(i).SetMapIndex(entries.Key(), entries.Value())
+			}
+			continue
+		}
+
+		if !target.Field(i).IsZero() {
+			notes = append(notes, fmt.Sprintf("Setting %s from %s replaces the one from an earlier config file", name, laterPath))
+		}
+		target.Field(i).Set(source.Field(i))
+	}
+	return notes
+}
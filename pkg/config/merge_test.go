@@ -0,0 +1,72 @@
+package config
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestLoadMergedConfigFiles(t *testing.T) {
+	tempDir := t.TempDir()
+	writeConfig := func(name, content string) string {
+		configPath := filepath.Join(tempDir, name)
+		if err := os.WriteFile(configPath, []byte(content), 0644); err != nil {
+			t.Fatalf("Failed to write config file: %v", err)
+		}
+		return configPath
+	}
+	base := writeConfig("base.json", `{
+		"mcpServers": {
+			"github": {"command": "github-server"},
+			"shortcut": {"command": "shortcut-server", "args": ["--team"]}
+		},
+		"errorArguments": "keys",
+		"toolNameSeparator": "__",
+		"routes": {"search": {"server": "github", "tool": "search"}}
+	}`)
+	local := writeConfig("local.yaml", `
+mcpServers:
+  shortcut:
+    command: my-shortcut-server
+  notes:
+    command: notes-server
+errorArguments: values
+routes:
+  open:
+    server: notes
+    tool: open
+`)
+
+	cfg, err := LoadConfigFile(base + ", " + local)
+	if err != nil {
+		t.Fatalf("LoadConfigFile() error = %v", err)
+	}
+
+	servers := make(map[string]ServerConfig)
+	for _, server := range cfg.Servers {
+		servers[server.Name] = server
+	}
+	if len(servers) != 3 || servers["github"].Command != "github-server" || servers["notes"].Command != "notes-server" {
+		t.Errorf("Merged servers = %+v, want github, shortcut and notes", cfg.Servers)
+	}
+	if shortcut := servers["shortcut"]; shortcut.Command != "my-shortcut-server" || len(shortcut.Args) != 0 {
+		t.Errorf("shortcut = %+v, want the later file's entry as a whole", shortcut)
+	}
+	if cfg.ErrorArguments != ErrorArgumentsValues || cfg.ToolNameSeparator != "__" {
+		t.Errorf("Merged settings errorArguments %q, toolNameSeparator %q, want values and __", cfg.ErrorArguments, cfg.ToolNameSeparator)
+	}
+	if len(cfg.Routes) != 2 {
+		t.Errorf("Merged routes = %v, want search and open", cfg.Routes)
+	}
+	notes := strings.Join(cfg.Notes, "\n")
+	if !strings.Contains(notes, "Server shortcut from "+local) || !strings.Contains(notes, "Setting errorArguments from "+local) {
+		t.Errorf("Notes = %q, want the replaced server and setting", notes)
+	}
+
+	// Errors found after merging name the files that were merged
+	writeConfig("broken.json", `{"mcpServers": {"github": {"command": "github-server", "dependsOn": ["missing"]}}}`)
+	if _, err := LoadConfigFile(base + "," + filepath.Join(tempDir, "broken.json")); err == nil || !strings.Contains(err.Error(), "merged from "+base) {
+		t.Errorf("LoadConfigFile() error = %v, want it to name the merged files", err)
+	}
+}
@@ -0,0 +1,50 @@
+package config
+
+import "fmt"
+
+// ToolOverride replaces parts of a discovered tool before it is exposed, and adds to the calls of it
+type ToolOverride struct {
+	Description      string                 `json:"description,omitempty"`      // Replaces the tool's description as a whole
+	InputSchema      map[string]interface{} `json:"inputSchema,omitempty"`      // Replaces the tool's input schema
+	ArgumentDefaults map[string]interface{} `json:"argumentDefaults,omitempty"` // Arguments merged under the client's in every call
+}
+
+// validateToolOverride checks that an override's input schema is a JSON Schema object an MCP tool can take
+func validateToolOverride(name string, override ToolOverride) error {
+	schema := override.InputSchema
+	if schema == nil {
+		return nil
+	}
+
+	if schemaType, _ := schema["type"].(string); schemaType != "object" {
+		return fmt.Errorf("tool override %s has an inputSchema of type %v: expected object", name, schema["type"])
+	}
+	properties := map[string]interface{}{}
+	if raw, exists := schema["properties"]; exists {
+		var ok bool
+		if properties, ok = raw.(map[string]interface{}); !ok {
+			return fmt.Errorf("tool override %s has inputSchema properties that aren't an object", name)
+		}
+		for property, definition := range properties {
+			if _, ok := definition.(map[string]interface{}); !ok {
+				return fmt.Errorf("tool override %s has inputSchema property %s that isn't a schema object", name, property)
+			}
+		}
+	}
+	if raw, exists := schema["required"]; exists {
+		required, ok := raw.([]interface{})
+		if !ok {
+			return fmt.Errorf("tool override %s has an inputSchema required field that isn't a list", name)
+		}
+		for _, entry := range required {
+			property, ok := entry.(string)
+			if !ok {
+				return fmt.Errorf("tool override %s has an inputSchema required entry %v that isn't a property name", name, entry)
+			}
+			if _, exists := properties[property]; !exists {
+				return fmt.Errorf("tool override %s requires property %s, which its inputSchema doesn't define", name, property)
+			}
+		}
+	}
+	return nil
+}
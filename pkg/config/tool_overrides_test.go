@@ -0,0 +1,68 @@
+package config
+
+import (
+	"encoding/json"
+	"strings"
+	"testing"
+)
+
+func TestValidateToolOverride(t *testing.T) {
+	tests := []struct {
+		name     string
+		override string
+		wantErr  string
+	}{
+		{
+			name:     "Description only",
+			override: `{"description": "Search stories by keyword"}`,
+		},
+		{
+			name:     "Object schema",
+			override: `{"inputSchema": {"type": "object", "properties": {"query": {"type": "string"}}, "required": ["query"]}}`,
+		},
+		{
+			name:     "Schema of another type",
+			override: `{"inputSchema": {"type": "string"}}`,
+			wantErr:  "tool override search has an inputSchema of type string: expected object",
+		},
+		{
+			name:     "Schema without a type",
+			override: `{"inputSchema": {"properties": {}}}`,
+			wantErr:  "expected object",
+		},
+		{
+			name:     "Properties that aren't an object",
+			override: `{"inputSchema": {"type": "object", "properties": ["query"]}}`,
+			wantErr:  "properties that aren't an object",
+		},
+		{
+			name:     "Property that isn't a schema",
+			override: `{"inputSchema": {"type": "object", "properties": {"query": "string"}}}`,
+			wantErr:  "property query that isn't a schema object",
+		},
+		{
+			name:     "Required property that isn't defined",
+			override: `{"inputSchema": {"type": "object", "properties": {"query": {"type": "string"}}, "required": ["limit"]}}`,
+			wantErr:  "requires property limit",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var override ToolOverride
+			if err := json.Unmarshal([]byte(tt.override), &override); err != nil {
+				t.Fatalf("Unmarshal() error = %v", err)
+			}
+			err := validateToolOverride("search", override)
+			if tt.wantErr == "" {
+				if err != nil {
+					t.Errorf("validateToolOverride() error = %v, want nil", err)
+				}
+				return
+			}
+			if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+				t.Errorf("validateToolOverride() error = %v, want it to contain %q", err, tt.wantErr)
+			}
+		})
+	}
+}
@@ -0,0 +1,51 @@
+package config
+
+import (
+	"fmt"
+	"net/url"
+	"strings"
+)
+
+// Transports a server can be reached over
+const (
+	// TransportStdio runs the server as a subprocess and talks to it over stdin and stdout
+	TransportStdio = "stdio"
+	// TransportSSE connects to a remote server speaking JSON-RPC over HTTP and server-sent events
+	TransportSSE = "sse"
+)
+
+// EffectiveTransport returns the server's transport, derived from whether it has a url when not set
+func (s *ServerConfig) EffectiveTransport() string {
+	if s.Transport != "" {
+		return s.Transport
+	}
+	if s.URL != "" {
+		return TransportSSE
+	}
+	return TransportStdio
+}
+
+// validateTransport checks that a server has exactly what its transport needs
+func validateTransport(server *ServerConfig) error {
+	if server.Command != "" && server.URL != "" {
+		return fmt.Errorf("server %s has both command and url: a server is either a local command or a remote url", server.Name)
+	}
+
+	switch server.EffectiveTransport() {
+	case TransportStdio:
+		if strings.TrimSpace(server.Command) == "" {
+			return fmt.Errorf("server %s missing command", server.Name)
+		}
+	case TransportSSE:
+		if server.URL == "" {
+			return fmt.Errorf("server %s missing url", server.Name)
+		}
+		parsed, err := url.Parse(server.URL)
+		if err != nil || (parsed.Scheme != "http" && parsed.Scheme != "https") || parsed.Host == "" {
+			return fmt.Errorf("server %s has invalid url %q: expected an http or https url", server.Name, server.URL)
+		}
+	default:
+		return fmt.Errorf("server %s has invalid transport %q: expected %s or %s", server.Name, server.Transport, TransportStdio, TransportSSE)
+	}
+	return nil
+}
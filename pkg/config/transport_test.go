@@ -0,0 +1,69 @@
+package config
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestValidateTransport(t *testing.T) {
+	tests := []struct {
+		name          string
+		server        ServerConfig
+		wantTransport string
+		wantErr       string
+	}{
+		{
+			name:          "Command defaults to stdio",
+			server:        ServerConfig{Name: "local", Command: "npx"},
+			wantTransport: TransportStdio,
+		},
+		{
+			name:          "URL defaults to sse",
+			server:        ServerConfig{Name: "remote", URL: "https://example.com/sse"},
+			wantTransport: TransportSSE,
+		},
+		{
+			name:    "Command and URL are mutually exclusive",
+			server:  ServerConfig{Name: "both", Command: "npx", URL: "https://example.com/sse"},
+			wantErr: "both command and url",
+		},
+		{
+			name:    "SSE needs a URL",
+			server:  ServerConfig{Name: "remote", Transport: TransportSSE},
+			wantErr: "missing url",
+		},
+		{
+			name:    "Stdio needs a command",
+			server:  ServerConfig{Name: "local", Transport: TransportStdio},
+			wantErr: "missing command",
+		},
+		{
+			name:    "URL must be http or https",
+			server:  ServerConfig{Name: "remote", URL: "ftp://example.com"},
+			wantErr: "invalid url",
+		},
+		{
+			name:    "Unknown transport",
+			server:  ServerConfig{Name: "remote", Transport: "carrier-pigeon", URL: "https://example.com"},
+			wantErr: "invalid transport",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			err := validateTransport(&tt.server)
+			if tt.wantErr != "" {
+				if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+					t.Fatalf("validateTransport() error = %v, want error containing %q", err, tt.wantErr)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatalf("validateTransport() error = %v", err)
+			}
+			if got := tt.server.EffectiveTransport(); got != tt.wantTransport {
+				t.Errorf("EffectiveTransport() = %q, want %q", got, tt.wantTransport)
+			}
+		})
+	}
+}
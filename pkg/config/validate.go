@@ -0,0 +1,45 @@
+package config
+
+import (
+	"fmt"
+	"regexp"
+	"strings"
+)
+
+// toolNameSeparatorPattern matches separators made only of characters allowed in MCP tool names
+var toolNameSeparatorPattern = regexp.MustCompile(`^[A-Za-z0-9_-]+$`)
+
+// validate checks the tool name separator, the description templates, the servers against each other and their dependencies once each of them is valid on its own
+func (c *Config) validate() error {
+	if c.ToolNameSeparator != "" && !toolNameSeparatorPattern.MatchString(c.ToolNameSeparator) {
+		return fmt.Errorf("tool name separator %q may only contain letters, digits, underscores and dashes", c.ToolNameSeparator)
+	}
+
+	if err := validateDescriptionTemplate(c.DescriptionTemplate); err != nil {
+		return err
+	}
+
+	seen := make(map[string]string, len(c.Servers))
+	for _, server := range c.Servers {
+		if err := validateDescriptionTemplate(server.DescriptionTemplate); err != nil {
+			return fmt.Errorf("server %s: %w", server.Name, err)
+		}
+
+		key := strings.ToLower(server.Name)
+		if other, exists := seen[key]; exists {
+			return fmt.Errorf("server %s has the same name as server %s: names must differ by more than case", server.Name, other)
+		}
+		seen[key] = server.Name
+
+		// With a custom separator, a server name containing it would make prefixed tool names ambiguous
+		if c.ToolNameSeparator != "" && strings.Contains(strings.ReplaceAll(server.Name, "-", "_"), c.ToolNameSeparator) {
+			return fmt.Errorf("server %s contains the tool name separator %q: choose another name or separator", server.Name, c.ToolNameSeparator)
+		}
+	}
+
+	// Every server must be startable after the servers it depends on
+	if _, err := c.StartOrder(); err != nil {
+		return err
+	}
+	return nil
+}
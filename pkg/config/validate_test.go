@@ -0,0 +1,104 @@
+package config
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestValidate(t *testing.T) {
+	tests := []struct {
+		name    string
+		config  Config
+		wantErr string
+	}{
+		{
+			name: "Distinct servers",
+			config: Config{Servers: []ServerConfig{
+				{Name: "github", Command: "npx"},
+				{Name: "shortcut", Command: "npx", Env: map[string]string{"TOKEN": "secret"}},
+			}},
+		},
+		{
+			name: "Names differing only by case",
+			config: Config{Servers: []ServerConfig{
+				{Name: "github", Command: "npx"},
+				{Name: "GitHub", Command: "npx"},
+			}},
+			wantErr: "server GitHub has the same name as server github",
+		},
+		{
+			name: "Name containing a custom separator",
+			config: Config{
+				Servers:           []ServerConfig{{Name: "my--server", Command: "npx"}},
+				ToolNameSeparator: "__",
+			},
+			wantErr: "server my--server contains the tool name separator",
+		},
+		{
+			name: "Separator with characters not allowed in tool names",
+			config: Config{
+				Servers:           []ServerConfig{{Name: "github", Command: "npx"}},
+				ToolNameSeparator: "::",
+			},
+			wantErr: "tool name separator \"::\" may only contain",
+		},
+		{
+			name: "Dash separator",
+			config: Config{
+				Servers:           []ServerConfig{{Name: "my_server", Command: "npx"}},
+				ToolNameSeparator: "-",
+			},
+		},
+		{
+			name:   "Default separator allows underscores",
+			config: Config{Servers: []ServerConfig{{Name: "my_server", Command: "npx"}}},
+		},
+		{
+			name:   "Description template with known placeholders",
+			config: Config{DescriptionTemplate: "[{server}/{tool}] {description}"},
+		},
+		{
+			name:    "Description template with an unknown placeholder",
+			config:  Config{DescriptionTemplate: "[{name}] {description}"},
+			wantErr: "unknown placeholder {name}",
+		},
+		{
+			name: "Server description template with an unknown placeholder",
+			config: Config{Servers: []ServerConfig{
+				{Name: "github", Command: "npx", DescriptionTemplate: "{Description}"},
+			}},
+			wantErr: "server github: description template",
+		},
+		{
+			name: "Dependency on an unknown server",
+			config: Config{Servers: []ServerConfig{
+				{Name: "proxy", Command: "npx", DependsOn: []string{"registry"}},
+			}},
+			wantErr: "server proxy depends on unknown server registry",
+		},
+		{
+			name: "Dependency cycle",
+			config: Config{Servers: []ServerConfig{
+				{Name: "github", Command: "npx"},
+				{Name: "proxy", Command: "npx", DependsOn: []string{"github", "registry"}},
+				{Name: "registry", Command: "npx", DependsOn: []string{"proxy"}},
+			}},
+			wantErr: "servers depend on each other: proxy -> registry -> proxy",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			err := tt.config.validate()
+			if tt.wantErr != "" {
+				if err == nil || !strings.Contains(err.Error(), tt.wantErr) {
+					t.Fatalf("validate() error = %v, want error containing %q", err, tt.wantErr)
+				}
+				return
+			}
+			if err != nil {
+				t.Errorf("validate() error = %v", err)
+			}
+		})
+	}
+}
@@ -0,0 +1,103 @@
+package framing
+
+import (
+	"bufio"
+	"bytes"
+	"fmt"
+	"io"
+	"strconv"
+	"strings"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// maxContentLength bounds the body announced by a Content-Length header, so a corrupt header can't exhaust memory
+const maxContentLength = 64 * 1024 * 1024
+
+// Reader reads JSON-RPC messages framed either as newline-delimited JSON or with LSP-style Content-Length headers
+type Reader struct {
+	reader        *bufio.Reader
+	contentLength bool
+}
+
+// NewReader creates a reader of messages framed as the given mode, newline-delimited JSON when empty
+func NewReader(r io.Reader, mode string) *Reader {
+	return &Reader{
+		reader:        bufio.NewReader(r),
+		contentLength: mode == config.FramingContentLength,
+	}
+}
+
+// ReadMessage returns the next message, skipping blank lines between newline-delimited messages.
+// It returns io.EOF once the input ends between messages.
+func (r *Reader) ReadMessage() ([]byte, error) {
+	if r.contentLength {
+		return r.readContentLength()
+	}
+	for {
+		line, err := r.reader.ReadBytes('\n')
+		if message := bytes.TrimSpace(line); len(message) > 0 {
+			return message, nil
+		}
+		if err != nil {
+			return nil, err
+		}
+	}
+}
+
+// readContentLength reads a header block and then exactly as many bytes as its Content-Length announces
+func (r *Reader) readContentLength() ([]byte, error) {
+	length := -1
+	started := false
+	for {
+		line, err := r.reader.ReadString('\n')
+		if err != nil {
+			if err == io.EOF && (started || line != "") {
+				return nil, io.ErrUnexpectedEOF
+			}
+			return nil, err
+		}
+		line = strings.TrimRight(line, "\r\n")
+		if line == "" {
+			if !started {
+				continue // Tolerate blank lines between messages
+			}
+			break
+		}
+		started = true
+
+		name, value, found := strings.Cut(line, ":")
+		if !found {
+			return nil, fmt.Errorf("invalid header line %q", line)
+		}
+		if !strings.EqualFold(strings.TrimSpace(name), "Content-Length") {
+			continue // Other headers, such as Content-Type, don't matter
+		}
+		length, err = strconv.Atoi(strings.TrimSpace(value))
+		if err != nil || length < 0 || length > maxContentLength {
+			return nil, fmt.Errorf("invalid Content-Length %q", strings.TrimSpace(value))
+		}
+	}
+	if length < 0 {
+		return nil, fmt.Errorf("message header without Content-Length")
+	}
+
+	body := make([]byte, length)
+	if _, err := io.ReadFull(r.reader, body); err != nil {
+		if err == io.EOF {
+			err = io.ErrUnexpectedEOF
+		}
+		return nil, err
+	}
+	return body, nil
+}
+
+// Encode returns a message framed as the given mode, ready to be written with a single call
+func Encode(mode string, message []byte) []byte {
+	if mode == config.FramingContentLength {
+		header := fmt.Sprintf("Content-Length: %d\r\n\r\n", len(message))
+		return append([]byte(header), message...)
+	}
+	framed := make([]byte, 0, len(message)+1)
+	return append(append(framed, message...), '\n')
+}
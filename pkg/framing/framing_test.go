@@ -0,0 +1,106 @@
+package framing
+
+import (
+	"bytes"
+	"io"
+	"strings"
+	"testing"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestReadMessage(t *testing.T) {
+	tests := []struct {
+		name    string
+		mode    string
+		input   string
+		want    []string
+		wantErr error
+	}{
+		{
+			name:  "Newline-delimited skips blank lines",
+			mode:  config.FramingNDJSON,
+			input: "{\"id\":1}\n\n  \r\n{\"id\":2}",
+			want:  []string{`{"id":1}`, `{"id":2}`},
+		},
+		{
+			name: "Content-Length with multi-line JSON",
+			mode: config.FramingContentLength,
+			input: "Content-Length: 13\r\n\r\n{\n  \"id\": 1\n}" +
+				"\r\ncontent-length: 8\r\nContent-Type: application/json\r\n\r\n{\"id\":2}",
+			want: []string{"{\n  \"id\": 1\n}", `{"id":2}`},
+		},
+		{
+			name:    "Content-Length body cut short",
+			mode:    config.FramingContentLength,
+			input:   "Content-Length: 20\r\n\r\n{\"id\":1}",
+			wantErr: io.ErrUnexpectedEOF,
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			reader := NewReader(strings.NewReader(tt.input), tt.mode)
+			var got []string
+			for {
+				message, err := reader.ReadMessage()
+				if err == io.EOF {
+					break
+				}
+				if err != nil {
+					if err != tt.wantErr {
+						t.Fatalf("ReadMessage() error = %v, want %v", err, tt.wantErr)
+					}
+					return
+				}
+				got = append(got, string(message))
+			}
+			if tt.wantErr != nil {
+				t.Fatalf("ReadMessage() error = nil, want %v", tt.wantErr)
+			}
+			if strings.Join(got, "|") != strings.Join(tt.want, "|") {
+				t.Errorf("ReadMessage() = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestReadMessageInvalidHeaders(t *testing.T) {
+	for _, input := range []string{
+		"Content-Type: application/json\r\n\r\n{}",
+		"Content-Length: many\r\n\r\n{}",
+		"not a header\r\n\r\n{}",
+	} {
+		if _, err := NewReader(strings.NewReader(input), config.FramingContentLength).ReadMessage(); err == nil || err == io.EOF {
+			t.Errorf("ReadMessage(%q) error = %v, want a header error", input, err)
+		}
+	}
+}
+
+func TestEncodeRoundTrip(t *testing.T) {
+	tests := map[string][]string{
+		config.FramingNDJSON:        {`{"id":1}`, `{"id":2}`},
+		config.FramingContentLength: {`{"id":1}`, "{\n  \"id\": 2\n}"},
+	}
+
+	for mode, messages := range tests {
+		var stream bytes.Buffer
+		for _, message := range messages {
+			stream.Write(Encode(mode, []byte(message)))
+		}
+
+		reader := NewReader(&stream, mode)
+		for _, want := range messages {
+			message, err := reader.ReadMessage()
+			if err != nil {
+				t.Fatalf("%s: ReadMessage() error = %v", mode, err)
+			}
+			if string(message) != want {
+				t.Errorf("%s: ReadMessage() = %q, want %q", mode, message, want)
+			}
+		}
+		if _, err := reader.ReadMessage(); err != io.EOF {
+			t.Errorf("%s: ReadMessage() at the end error = %v, want %v", mode, err, io.EOF)
+		}
+	}
+}
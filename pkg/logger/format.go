@@ -0,0 +1,58 @@
+package logger
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"log"
+	"strings"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// fileEntry is a log file line in the json format
+type fileEntry struct {
+	Time    string `json:"time"`
+	Level   string `json:"level"`
+	Message string `json:"message"`
+}
+
+// formattedWriter writes each message of one level as a line of the log file in the pretty or json format
+type formattedWriter struct {
+	out    io.Writer
+	level  string
+	format string
+}
+
+// Write formats a message the way the log file's format wants it
+func (w *formattedWriter) Write(p []byte) (int, error) {
+	message := strings.TrimSuffix(string(p), "\n")
+	now := time.Now()
+
+	var line []byte
+	if w.format == config.LogFormatJSON {
+		entry, err := json.Marshal(fileEntry{Time: now.Format(time.RFC3339Nano), Level: strings.ToLower(w.level), Message: message})
+		if err != nil {
+			return 0, err
+		}
+		line = append(entry, '\n')
+	} else {
+		line = []byte(fmt.Sprintf("%s %-5s %s\n", now.Format("2006-01-02 15:04:05.000"), w.level, message))
+	}
+
+	if _, err := w.out.Write(line); err != nil {
+		return 0, err
+	}
+	return len(p), nil
+}
+
+// newFileLogger creates the logger of one level writing to the log file in the given format
+func newFileLogger(out io.Writer, level, format string) *log.Logger {
+	switch format {
+	case config.LogFormatPretty, config.LogFormatJSON:
+		return log.New(&formattedWriter{out: out, level: level, format: format}, "", 0)
+	default:
+		return log.New(out, level+": ", log.Ldate|log.Ltime)
+	}
+}
@@ -0,0 +1,51 @@
+package logger
+
+import (
+	"bytes"
+	"encoding/json"
+	"regexp"
+	"strings"
+	"testing"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestFileFormats(t *testing.T) {
+	tests := []struct {
+		format string
+		want   *regexp.Regexp
+	}{
+		{format: "", want: regexp.MustCompile(`^INFO: \d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2} Server github started\n$`)},
+		{format: config.LogFormatCompact, want: regexp.MustCompile(`^INFO: \d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2} Server github started\n$`)},
+		{format: config.LogFormatPretty, want: regexp.MustCompile(`^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d{3} INFO  Server github started\n$`)},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.format, func(t *testing.T) {
+			var out bytes.Buffer
+			newFileLogger(&out, "INFO", tt.format).Print("Server github started")
+			if !tt.want.MatchString(out.String()) {
+				t.Errorf("log line = %q, want it to match %s", out.String(), tt.want)
+			}
+		})
+	}
+}
+
+func TestJSONFileFormat(t *testing.T) {
+	var out bytes.Buffer
+	errorLog := newFileLogger(&out, "ERROR", config.LogFormatJSON)
+	errorLog.Print("Server github failed:\nexit status 1")
+	errorLog.Print("Server shortcut failed")
+
+	lines := strings.Split(strings.TrimSuffix(out.String(), "\n"), "\n")
+	if len(lines) != 2 {
+		t.Fatalf("log = %q, want a line for each message", out.String())
+	}
+	var entry fileEntry
+	if err := json.Unmarshal([]byte(lines[0]), &entry); err != nil {
+		t.Fatalf("log line %q isn't JSON: %v", lines[0], err)
+	}
+	if entry.Level != "error" || entry.Message != "Server github failed:\nexit status 1" || entry.Time == "" {
+		t.Errorf("log entry = %+v, want the error message with its time", entry)
+	}
+}
@@ -8,28 +8,63 @@ import (
 	"os"
 	"path/filepath"
 	"sync"
+	"sync/atomic"
 	"time"
 
 	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/redact"
 )
 
 var (
-	logFile        *os.File
+	logFile        *rotatingFile
 	errorLog       *log.Logger
 	infoLog        *log.Logger
 	debugLog       *log.Logger
 	traceLog       *log.Logger
 	errorLogStdout *log.Logger
 	infoLogStdout  *log.Logger
-	logLevel       config.LogLevel
+	logLevel       atomic.Int32
+	serverLevels   atomic.Pointer[map[string]config.LogLevel]
 	initOnce       sync.Once
 )
 
-// Init initializes the logger with the specified log level and optional log file
-func Init(level config.LogLevel, logFilePath string) error {
+// currentLevel returns the active log level
+func currentLevel() config.LogLevel {
+	return config.LogLevel(logLevel.Load())
+}
+
+// SetLevel changes the active log level at runtime
+func SetLevel(level config.LogLevel) {
+	logLevel.Store(int32(level))
+}
+
+// GetLevel returns the active log level
+func GetLevel() config.LogLevel {
+	return currentLevel()
+}
+
+// SetServerLevels sets the levels of single servers, which override the active level for the messages
+// logged about them with ServerDebug and ServerTrace
+func SetServerLevels(levels map[string]config.LogLevel) {
+	serverLevels.Store(&levels)
+}
+
+// serverLevel returns the log level of a server, the active level unless it has its own
+func serverLevel(serverName string) config.LogLevel {
+	if levels := serverLevels.Load(); levels != nil {
+		if level, exists := (*levels)[serverName]; exists {
+			return level
+		}
+	}
+	return currentLevel()
+}
+
+// Init initializes the logger with the specified log level and optional log file, written and rotated
+// as the options say
+func Init(level config.LogLevel, logFilePath string, options config.LogFileOptions) error {
 	var err error
 	initOnce.Do(func() {
-		logLevel = level
+		SetLevel(level)
 
 		// Set up stdout writers for essential output only
 		errorLogStdout = log.New(os.Stdout, "ERROR: ", log.Ldate|log.Ltime)
@@ -44,8 +79,8 @@ func Init(level config.LogLevel, logFilePath string) error {
 				return
 			}
 
-			// Open log file
-			logFile, err = os.OpenFile(logFilePath, os.O_CREATE|os.O_WRONLY|os.O_APPEND, 0644)
+			// Open log file, moving it aside first when it's due to be rotated
+			logFile, err = openRotatingFile(logFilePath, options.Rotation, options.MaxFiles)
 			if err != nil {
 				err = fmt.Errorf("failed to open log file: %w", err)
 				return
@@ -57,10 +92,10 @@ func Init(level config.LogLevel, logFilePath string) error {
 		}
 
 		// Create full loggers with appropriate prefixes (file-only)
-		errorLog = log.New(logWriter, "ERROR: ", log.Ldate|log.Ltime)
-		infoLog = log.New(logWriter, "INFO: ", log.Ldate|log.Ltime)
-		debugLog = log.New(logWriter, "DEBUG: ", log.Ldate|log.Ltime)
-		traceLog = log.New(logWriter, "TRACE: ", log.Ldate|log.Ltime)
+		errorLog = newFileLogger(logWriter, "ERROR", options.Format)
+		infoLog = newFileLogger(logWriter, "INFO", options.Format)
+		debugLog = newFileLogger(logWriter, "DEBUG", options.Format)
+		traceLog = newFileLogger(logWriter, "TRACE", options.Format)
 
 		// Log initialization only to file to avoid corrupting JSON
 		if logFile != nil {
@@ -70,6 +105,18 @@ func Init(level config.LogLevel, logFilePath string) error {
 	return err
 }
 
+// scrub formats a log message with registered secrets masked
+func scrub(format string, v ...interface{}) string {
+	return redact.String(fmt.Sprintf(format, v...))
+}
+
+// SetConsoleOutput sends the errors and info messages logged to stdout to w instead, such as to stderr for
+// modes whose output on stdout has to stay machine readable
+func SetConsoleOutput(w io.Writer) {
+	errorLogStdout.SetOutput(w)
+	infoLogStdout.SetOutput(w)
+}
+
 // Close closes the log file if one is open
 func Close() {
 	if logFile != nil {
@@ -79,24 +126,28 @@ func Close() {
 
 // Error logs an error message to both stdout and log file
 func Error(format string, v ...interface{}) {
+	message := scrub(format, v...)
+
 	// Always log errors to the file
-	errorLog.Printf(format, v...)
+	errorLog.Print(message)
 
 	// Only log to stdout if we're not in debug/trace mode, to avoid corrupting JSON
-	if logLevel < config.LogLevelDebug {
-		errorLogStdout.Printf(format, v...)
+	if currentLevel() < config.LogLevelDebug {
+		errorLogStdout.Print(message)
 	}
 }
 
 // Info logs an info message if log level is Info or higher
 func Info(format string, v ...interface{}) {
-	if logLevel >= config.LogLevelInfo {
+	if currentLevel() >= config.LogLevelInfo {
+		message := scrub(format, v...)
+
 		// Always log to file
-		infoLog.Printf(format, v...)
+		infoLog.Print(message)
 
 		// Only log to stdout if we're not in debug/trace mode, to avoid corrupting JSON
-		if logLevel < config.LogLevelDebug {
-			infoLogStdout.Printf(format, v...)
+		if currentLevel() < config.LogLevelDebug {
+			infoLogStdout.Print(message)
 		}
 	}
 }
@@ -104,50 +155,66 @@ func Info(format string, v ...interface{}) {
 // Debug logs a debug message if log level is Debug or higher
 // Debug messages only go to the log file, never stdout
 func Debug(format string, v ...interface{}) {
-	if logLevel >= config.LogLevelDebug {
-		debugLog.Printf(format, v...)
+	if currentLevel() >= config.LogLevelDebug {
+		debugLog.Print(scrub(format, v...))
 	}
 }
 
 // Trace logs a trace message if log level is Trace
 // Trace messages only go to the log file, never stdout
 func Trace(format string, v ...interface{}) {
-	if logLevel >= config.LogLevelTrace {
-		traceLog.Printf(format, v...)
+	if currentLevel() >= config.LogLevelTrace {
+		traceLog.Print(scrub(format, v...))
+	}
+}
+
+// ServerDebug logs a debug message about a server if the server's log level is Debug or higher
+func ServerDebug(serverName string, format string, v ...interface{}) {
+	if serverLevel(serverName) >= config.LogLevelDebug {
+		debugLog.Print(scrub(format, v...))
+	}
+}
+
+// ServerTrace logs a trace message about a server if the server's log level is Trace
+func ServerTrace(serverName string, format string, v ...interface{}) {
+	if serverLevel(serverName) >= config.LogLevelTrace {
+		traceLog.Print(scrub(format, v...))
 	}
 }
 
 // LogRequest logs incoming JSON-RPC requests
 func LogRequest(method string, id interface{}, params interface{}) {
-	if logLevel >= config.LogLevelDebug {
+	if currentLevel() >= config.LogLevelDebug {
 		debugLog.Printf("Request: method=%s, id=%v", method, id)
-		if logLevel >= config.LogLevelTrace {
-			traceLog.Printf("Request params: %+v", params)
+		if currentLevel() >= config.LogLevelTrace {
+			traceLog.Print(scrub("Request params: %+v", params))
 		}
 	}
 }
 
 // LogResponse logs outgoing JSON-RPC responses
 func LogResponse(id interface{}, result interface{}, err error) {
-	if logLevel >= config.LogLevelDebug {
+	if currentLevel() >= config.LogLevelDebug {
 		if err != nil {
-			debugLog.Printf("Response: id=%v, error=%v", id, err)
+			debugLog.Print(scrub("Response: id=%v, error=%v", id, err))
 		} else {
 			debugLog.Printf("Response: id=%v, success=true", id)
-			if logLevel >= config.LogLevelTrace {
-				traceLog.Printf("Response result: %+v", result)
+			if currentLevel() >= config.LogLevelTrace {
+				traceLog.Print(scrub("Response result: %+v", result))
 			}
 		}
 	}
 }
 
-// LogRPC logs the complete JSON-RPC message for maximum visibility
+// LogRPC logs the complete JSON-RPC message for maximum visibility, with the values of secret-like keys masked
 // RPC messages only go to the log file, never stdout
 func LogRPC(direction string, message []byte) {
-	if logLevel >= config.LogLevelTrace {
+	if currentLevel() >= config.LogLevelTrace {
+		message = redact.JSON(message)
+
 		// Add timestamp
 		timestamp := time.Now().Format("2006-01-02 15:04:05.000")
-		traceLog.Printf("%s RPC [%s]: %s", direction, timestamp, string(message))
+		traceLog.Print(scrub("%s RPC [%s]: %s", direction, timestamp, string(message)))
 
 		// Try to parse and log structured information about the message
 		var jsonMsg map[string]interface{}
@@ -155,7 +222,7 @@ func LogRPC(direction string, message []byte) {
 			// Pretty print the parsed JSON for better readability
 			prettyJSON, err := json.MarshalIndent(jsonMsg, "", "  ")
 			if err == nil {
-				traceLog.Printf("%s RPC PARSED [%s]:\n%s", direction, timestamp, string(prettyJSON))
+				traceLog.Print(scrub("%s RPC PARSED [%s]:\n%s", direction, timestamp, string(prettyJSON)))
 			}
 		}
 	}
@@ -165,12 +232,13 @@ func LogRPC(direction string, message []byte) {
 func Fatal(format string, v ...interface{}) {
 	// Log to file if logger is initialized
 	// Do NOT call Error() as it might write to stdout
+	message := scrub(format, v...)
 	if errorLog != nil {
-		errorLog.Printf(format, v...)
+		errorLog.Print(message)
 	}
 
 	// Always write to stderr, never stdout
-	fmt.Fprintf(os.Stderr, "FATAL: "+format+"\n", v...)
+	fmt.Fprintf(os.Stderr, "FATAL: %s\n", message)
 
 	// Close any open log files
 	Close()
@@ -0,0 +1,25 @@
+package logger
+
+import (
+	"testing"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestServerLevels(t *testing.T) {
+	defer SetLevel(GetLevel())
+	SetLevel(config.LogLevelError)
+	SetServerLevels(map[string]config.LogLevel{"github": config.LogLevelTrace})
+	defer SetServerLevels(nil)
+
+	if got := serverLevel("github"); got != config.LogLevelTrace {
+		t.Errorf("serverLevel(github) = %v, want the server's own trace level", got)
+	}
+	if got := serverLevel("shortcut"); got != config.LogLevelError {
+		t.Errorf("serverLevel(shortcut) = %v, want the active error level", got)
+	}
+	SetLevel(config.LogLevelDebug)
+	if got := serverLevel("shortcut"); got != config.LogLevelDebug {
+		t.Errorf("serverLevel(shortcut) after SetLevel = %v, want the new active level", got)
+	}
+}
@@ -0,0 +1,152 @@
+package logger
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// rotationLayouts name the period a rotated log file covers, appended to the log file's name
+var rotationLayouts = map[string]string{
+	config.LogRotationHourly: "2006-01-02T15",
+	config.LogRotationDaily:  "2006-01-02",
+}
+
+// rotatingFile is the log file, which is moved aside to a name ending in its period once a new period
+// begins, e.g. combine-mcp.log.2026-10-13, keeping only the newest rotated files
+type rotatingFile struct {
+	path     string
+	layout   string // Period the log file covers, empty when it isn't rotated
+	maxFiles int
+
+	mu     sync.Mutex
+	file   *os.File
+	period string // Period of the messages in the open file
+}
+
+// openRotatingFile opens the log file for appending. A log file left from an earlier period is
+// rotated right away, and rotated files beyond maxFiles are removed.
+func openRotatingFile(path, rotation string, maxFiles int) (*rotatingFile, error) {
+	r := &rotatingFile{path: path, layout: rotationLayouts[rotation], maxFiles: maxFiles}
+	now := time.Now()
+	if info, err := os.Stat(path); err == nil && r.layout != "" {
+		r.period = info.ModTime().Format(r.layout)
+		if r.period != now.Format(r.layout) {
+			if err := r.moveAside(); err != nil {
+				return nil, err
+			}
+		}
+	}
+	if err := r.open(now); err != nil {
+		return nil, err
+	}
+	r.prune()
+	return r, nil
+}
+
+// open opens the log file for the messages of the period the time is in
+func (r *rotatingFile) open(now time.Time) error {
+	file, err := os.OpenFile(r.path, os.O_CREATE|os.O_WRONLY|os.O_APPEND, 0644)
+	if err != nil {
+		return err
+	}
+	r.file = file
+	if r.layout != "" {
+		r.period = now.Format(r.layout)
+	}
+	return nil
+}
+
+// moveAside renames the log file after the period of its messages, next to files of the same period
+// from earlier runs
+func (r *rotatingFile) moveAside() error {
+	rotated := r.path + "." + r.period
+	for i := 1; ; i++ {
+		if _, err := os.Stat(rotated); os.IsNotExist(err) {
+			break
+		}
+		rotated = fmt.Sprintf("%s.%s.%d", r.path, r.period, i)
+	}
+	if err := os.Rename(r.path, rotated); err != nil {
+		return fmt.Errorf("failed to rotate log file: %w", err)
+	}
+	return nil
+}
+
+// Write appends to the log file, rotating it first when the message belongs to a new period
+func (r *rotatingFile) Write(p []byte) (int, error) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+
+	if now := time.Now(); r.layout != "" && now.Format(r.layout) != r.period {
+		r.file.Close()
+		if err := r.moveAside(); err != nil {
+			fmt.Fprintf(os.Stderr, "%v\n", err)
+		}
+		if err := r.open(now); err != nil {
+			return 0, err
+		}
+		r.prune()
+	}
+	return r.file.Write(p)
+}
+
+// Close closes the log file
+func (r *rotatingFile) Close() error {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	return r.file.Close()
+}
+
+// prune removes the oldest rotated log files beyond maxFiles. Only files named after the log file
+// and a period are rotated files, whatever the rotation is now.
+func (r *rotatingFile) prune() {
+	if r.maxFiles <= 0 {
+		return
+	}
+
+	matches, err := filepath.Glob(r.path + ".*")
+	if err != nil {
+		return
+	}
+	type rotatedFile struct {
+		path    string
+		modTime time.Time
+	}
+	var rotated []rotatedFile
+	for _, match := range matches {
+		if !isRotatedName(strings.TrimPrefix(match, r.path+".")) {
+			continue
+		}
+		if info, err := os.Stat(match); err == nil && info.Mode().IsRegular() {
+			rotated = append(rotated, rotatedFile{path: match, modTime: info.ModTime()})
+		}
+	}
+	if len(rotated) <= r.maxFiles {
+		return
+	}
+
+	sort.Slice(rotated, func(i, j int) bool { return rotated[i].modTime.After(rotated[j].modTime) })
+	for _, old := range rotated[r.maxFiles:] {
+		if err := os.Remove(old.path); err != nil {
+			fmt.Fprintf(os.Stderr, "failed to remove rotated log file: %v\n", err)
+		}
+	}
+}
+
+// isRotatedName reports whether what follows the log file's name is a period, optionally numbered
+func isRotatedName(suffix string) bool {
+	period, _, _ := strings.Cut(suffix, ".")
+	for _, layout := range rotationLayouts {
+		if _, err := time.Parse(layout, period); err == nil {
+			return true
+		}
+	}
+	return false
+}
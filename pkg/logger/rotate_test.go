@@ -0,0 +1,90 @@
+package logger
+
+import (
+	"os"
+	"path/filepath"
+	"sort"
+	"testing"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+func TestRotatingFile(t *testing.T) {
+	dir := t.TempDir()
+	logPath := filepath.Join(dir, "combine-mcp.log")
+	yesterday := time.Now().Add(-24 * time.Hour)
+	writeLog := func(path string, modTime time.Time) {
+		t.Helper()
+		if err := os.WriteFile(path, []byte("old\n"), 0644); err != nil {
+			t.Fatalf("Failed to write %s: %v", path, err)
+		}
+		if err := os.Chtimes(path, modTime, modTime); err != nil {
+			t.Fatalf("Failed to date %s: %v", path, err)
+		}
+	}
+	writeLog(logPath+".2020-01-01", yesterday.Add(-72*time.Hour))
+	writeLog(logPath+".2020-01-02T10", yesterday.Add(-48*time.Hour))
+	writeLog(logPath+".bak", yesterday.Add(-96*time.Hour))
+	writeLog(logPath, yesterday)
+
+	file, err := openRotatingFile(logPath, config.LogRotationDaily, 2)
+	if err != nil {
+		t.Fatalf("openRotatingFile() error = %v", err)
+	}
+	if _, err := file.Write([]byte("new\n")); err != nil {
+		t.Fatalf("Write() error = %v", err)
+	}
+	file.Close()
+
+	// Yesterday's log was rotated, and only the newest two rotated files are kept
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		t.Fatalf("ReadDir() error = %v", err)
+	}
+	var names []string
+	for _, entry := range entries {
+		names = append(names, entry.Name())
+	}
+	sort.Strings(names)
+	want := []string{
+		"combine-mcp.log",
+		"combine-mcp.log." + yesterday.Format("2006-01-02"),
+		"combine-mcp.log.2020-01-02T10",
+		"combine-mcp.log.bak",
+	}
+	sort.Strings(want)
+	if len(names) != len(want) {
+		t.Fatalf("log files = %v, want %v", names, want)
+	}
+	for i := range want {
+		if names[i] != want[i] {
+			t.Fatalf("log files = %v, want %v", names, want)
+		}
+	}
+	if data, _ := os.ReadFile(logPath); string(data) != "new\n" {
+		t.Errorf("log file = %q, want only the new message", data)
+	}
+}
+
+func TestNeverRotatedFile(t *testing.T) {
+	logPath := filepath.Join(t.TempDir(), "combine-mcp.log")
+	if err := os.WriteFile(logPath, []byte("old\n"), 0644); err != nil {
+		t.Fatalf("Failed to write log file: %v", err)
+	}
+	lastWeek := time.Now().Add(-7 * 24 * time.Hour)
+	if err := os.Chtimes(logPath, lastWeek, lastWeek); err != nil {
+		t.Fatalf("Failed to date log file: %v", err)
+	}
+
+	file, err := openRotatingFile(logPath, "", 0)
+	if err != nil {
+		t.Fatalf("openRotatingFile() error = %v", err)
+	}
+	file.Write([]byte("new\n"))
+	file.Close()
+
+	if data, _ := os.ReadFile(logPath); string(data) != "old\nnew\n" {
+		t.Errorf("log file = %q, want the new message appended", data)
+	}
+}
@@ -0,0 +1,173 @@
+package redact
+
+import (
+	"encoding/json"
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+)
+
+// Placeholder replaces values that must not be shown
+const Placeholder = "***"
+
+// minSecretLength is the shortest value scrubbed from text, shorter ones would mangle unrelated text
+const minSecretLength = 4
+
+var (
+	// sensitiveMarkers are substrings that mark a key as holding a secret
+	sensitiveMarkers = []string{"TOKEN", "SECRET", "KEY", "PASSWORD"}
+	// secrets are the values scrubbed from text by String
+	secrets  = make(map[string]struct{})
+	secretMu sync.RWMutex
+)
+
+// AddSensitiveMarkers adds substrings that mark a key as holding a secret, on top of the defaults
+func AddSensitiveMarkers(markers ...string) {
+	secretMu.Lock()
+	defer secretMu.Unlock()
+	for _, marker := range markers {
+		if marker != "" {
+			sensitiveMarkers = append(sensitiveMarkers, strings.ToUpper(marker))
+		}
+	}
+}
+
+// IsSensitiveKey reports whether a key name looks like it holds a secret
+func IsSensitiveKey(key string) bool {
+	secretMu.RLock()
+	defer secretMu.RUnlock()
+
+	upper := strings.ToUpper(key)
+	for _, marker := range sensitiveMarkers {
+		if strings.Contains(upper, marker) {
+			return true
+		}
+	}
+	return false
+}
+
+// RegisterEnv remembers the values of secret-like keys so String scrubs them from text
+func RegisterEnv(env map[string]string) {
+	for key, value := range env {
+		if IsSensitiveKey(key) && len(value) >= minSecretLength {
+			secretMu.Lock()
+			secrets[value] = struct{}{}
+			secretMu.Unlock()
+		}
+	}
+}
+
+// Env returns env as sorted KEY=value pairs with the values of secret-like keys masked
+func Env(env map[string]string) []string {
+	pairs := make([]string, 0, len(env))
+	for key, value := range env {
+		if IsSensitiveKey(key) {
+			value = Placeholder
+		}
+		pairs = append(pairs, key+"="+value)
+	}
+	sort.Strings(pairs)
+	return pairs
+}
+
+// String replaces every registered secret in text with the placeholder
+func String(text string) string {
+	secretMu.RLock()
+	defer secretMu.RUnlock()
+
+	for secret := range secrets {
+		text = strings.ReplaceAll(text, secret, Placeholder)
+	}
+	return text
+}
+
+// redactedError carries the scrubbed message of an error while keeping it inspectable with errors.Is and errors.As
+type redactedError struct {
+	message string
+	err     error
+}
+
+func (e *redactedError) Error() string {
+	return e.message
+}
+
+// Unwrap returns the original error
+func (e *redactedError) Unwrap() error {
+	return e.err
+}
+
+// Error returns err with registered secrets scrubbed from its message
+func Error(err error) error {
+	if err == nil {
+		return nil
+	}
+	message := String(err.Error())
+	if message == err.Error() {
+		return err
+	}
+	return &redactedError{message: message, err: err}
+}
+
+// Arguments returns a redacted snapshot of tool call arguments.
+// Without values only the sorted argument names are returned. With values,
+// secret-like keys are masked and values larger than maxValueBytes once
+// encoded are replaced by their size.
+func Arguments(args map[string]interface{}, includeValues bool, maxValueBytes int) interface{} {
+	if !includeValues {
+		keys := make([]string, 0, len(args))
+		for key := range args {
+			keys = append(keys, key)
+		}
+		sort.Strings(keys)
+		return keys
+	}
+
+	snapshot := make(map[string]interface{}, len(args))
+	for key, value := range args {
+		if IsSensitiveKey(key) {
+			snapshot[key] = Placeholder
+			continue
+		}
+		encoded, err := json.Marshal(value)
+		if err != nil || len(encoded) > maxValueBytes {
+			snapshot[key] = fmt.Sprintf("<%d bytes omitted>", len(encoded))
+			continue
+		}
+		snapshot[key] = value
+	}
+	return snapshot
+}
+
+// JSON returns an encoded JSON message with the values of secret-like keys masked at any depth.
+// Text that isn't JSON is returned as it is.
+func JSON(message []byte) []byte {
+	var decoded interface{}
+	if err := json.Unmarshal(message, &decoded); err != nil {
+		return message
+	}
+	masked, err := json.Marshal(maskSensitiveKeys(decoded))
+	if err != nil {
+		return message
+	}
+	return masked
+}
+
+// maskSensitiveKeys replaces the values of secret-like keys in decoded JSON
+func maskSensitiveKeys(value interface{}) interface{} {
+	switch value := value.(type) {
+	case map[string]interface{}:
+		for key, nested := range value {
+			if IsSensitiveKey(key) {
+				value[key] = Placeholder
+				continue
+			}
+			value[key] = maskSensitiveKeys(nested)
+		}
+	case []interface{}:
+		for i, nested := range value {
+			value[i] = maskSensitiveKeys(nested)
+		}
+	}
+	return value
+}
@@ -0,0 +1,82 @@
+package redact
+
+import (
+	"errors"
+	"reflect"
+	"testing"
+)
+
+func TestString(t *testing.T) {
+	AddSensitiveMarkers("pat")
+	RegisterEnv(map[string]string{
+		"GITHUB_TOKEN": "ghp_secret123",
+		"GH_PAT":       "pat-value-456",
+		"SHORT_KEY":    "abc",
+		"HOST":         "api.github.com",
+	})
+
+	tests := []struct {
+		input string
+		want  string
+	}{
+		{input: "Authorization: Bearer ghp_secret123", want: "Authorization: Bearer ***"},
+		{input: "token pat-value-456 rejected", want: "token *** rejected"},
+		{input: "connecting to api.github.com", want: "connecting to api.github.com"},
+		{input: "abc is too short to be scrubbed", want: "abc is too short to be scrubbed"},
+	}
+
+	for _, tt := range tests {
+		if got := String(tt.input); got != tt.want {
+			t.Errorf("String(%q) = %q, want %q", tt.input, got, tt.want)
+		}
+	}
+}
+
+func TestError(t *testing.T) {
+	RegisterEnv(map[string]string{"API_KEY": "key-789-secret"})
+
+	original := errors.New("request with key-789-secret failed")
+	redacted := Error(original)
+	if got := redacted.Error(); got != "request with *** failed" {
+		t.Errorf("Error() message = %q, want the secret masked", got)
+	}
+	if !errors.Is(redacted, original) {
+		t.Errorf("Redacted error doesn't wrap the original")
+	}
+
+	clean := errors.New("nothing to hide")
+	if Error(clean) != clean {
+		t.Errorf("Error() replaced an error without secrets")
+	}
+}
+
+func TestEnv(t *testing.T) {
+	got := Env(map[string]string{"GITHUB_TOKEN": "ghp_secret123", "HOST": "api.github.com"})
+	want := []string{"GITHUB_TOKEN=***", "HOST=api.github.com"}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("Env() = %v, want %v", got, want)
+	}
+}
+
+func TestJSON(t *testing.T) {
+	tests := []struct {
+		input string
+		want  string
+	}{
+		{
+			input: `{"method":"tools/call","params":{"arguments":{"apiToken":"abc123","query":"hello"}}}`,
+			want:  `{"method":"tools/call","params":{"arguments":{"apiToken":"***","query":"hello"}}}`,
+		},
+		{
+			input: `[{"password":{"nested":true}},{"name":"plain"}]`,
+			want:  `[{"password":"***"},{"name":"plain"}]`,
+		},
+		{input: "not json", want: "not json"},
+	}
+
+	for _, tt := range tests {
+		if got := string(JSON([]byte(tt.input))); got != tt.want {
+			t.Errorf("JSON(%s) = %s, want %s", tt.input, got, tt.want)
+		}
+	}
+}
@@ -0,0 +1,54 @@
+package stdio
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// isBatch reports whether a message is a JSON-RPC batch, an array of requests
+func isBatch(line []byte) bool {
+	trimmed := bytes.TrimLeft(line, " \t\r\n")
+	return len(trimmed) > 0 && trimmed[0] == '['
+}
+
+// handleBatch processes the requests of a batch in order and returns the encoded array of their
+// responses, or nil when the batch held only notifications
+func (s *AggregatorServer) handleBatch(ctx context.Context, line []byte) []byte {
+	var batch []json.RawMessage
+	if err := json.Unmarshal(line, &batch); err != nil {
+		return errorResponse(nil, mcp.PARSE_ERROR, fmt.Sprintf("invalid batch: %v", err), nil)
+	}
+	if len(batch) == 0 {
+		return errorResponse(nil, mcp.INVALID_REQUEST, "invalid request: empty batch", nil)
+	}
+	logger.Debug("Received batch of %d messages", len(batch))
+
+	responses := make([]json.RawMessage, 0, len(batch))
+	for _, message := range batch {
+		var response []byte
+		if trimmed := bytes.TrimSpace(message); len(trimmed) == 0 || trimmed[0] != '{' {
+			// Batches don't nest and every entry must be a request object
+			response = errorResponse(nil, mcp.INVALID_REQUEST, "invalid request: batch entries must be objects", nil)
+		} else {
+			response = s.handleMessage(ctx, message)
+		}
+		if response != nil {
+			responses = append(responses, response)
+		}
+	}
+	if len(responses) == 0 {
+		return nil
+	}
+
+	encoded, err := json.Marshal(responses)
+	if err != nil {
+		logger.Error("Failed to marshal batch response: %v", err)
+		return errorResponse(nil, mcp.INTERNAL_ERROR, "failed to encode batch response", nil)
+	}
+	return encoded
+}
@@ -0,0 +1,53 @@
+package stdio
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"strings"
+	"testing"
+
+	"github.com/mark3labs/mcp-go/mcp"
+)
+
+func TestBatch(t *testing.T) {
+	s := newTestServer(nil)
+
+	var output bytes.Buffer
+	input := `[{"jsonrpc":"2.0","id":1,"method":"ping"},{"jsonrpc":"2.0","method":"notifications/initialized"},{"jsonrpc":"2.0","id":2,"method":"ping"},1]` + "\n"
+	if err := s.serve(context.Background(), strings.NewReader(input), &output); err != nil {
+		t.Fatalf("serve() error = %v", err)
+	}
+
+	var responses []map[string]interface{}
+	if err := json.Unmarshal(output.Bytes(), &responses); err != nil {
+		t.Fatalf("Batch response %s isn't an array: %v", output.Bytes(), err)
+	}
+	if len(responses) != 3 {
+		t.Fatalf("Got %d responses, want one per request and none for the notification", len(responses))
+	}
+	for i, wantID := range []float64{1, 2} {
+		if id, _ := responses[i]["id"].(float64); id != wantID {
+			t.Errorf("Response %d has id %v, want %v", i, responses[i]["id"], wantID)
+		}
+		if _, ok := responses[i]["result"]; !ok {
+			t.Errorf("Response %d = %v, want a result", i, responses[i])
+		}
+	}
+	if rpcErr, ok := responses[2]["error"].(map[string]interface{}); !ok || int(rpcErr["code"].(float64)) != mcp.INVALID_REQUEST {
+		t.Errorf("Response to a non-object entry = %v, want an Invalid Request error", responses[2])
+	}
+}
+
+func TestBatchWithoutResponses(t *testing.T) {
+	s := newTestServer(nil)
+
+	resp := decodeResponse(t, s.handleBatch(context.Background(), []byte(`[]`)))
+	if rpcErr, ok := resp["error"].(map[string]interface{}); !ok || int(rpcErr["code"].(float64)) != mcp.INVALID_REQUEST {
+		t.Errorf("Response to an empty batch = %v, want a single Invalid Request error", resp)
+	}
+
+	if response := s.handleBatch(context.Background(), []byte(`[{"jsonrpc":"2.0","method":"notifications/initialized"}]`)); response != nil {
+		t.Errorf("Response to a batch of notifications = %s, want none", response)
+	}
+}
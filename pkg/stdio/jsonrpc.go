@@ -0,0 +1,65 @@
+package stdio
+
+import (
+	"encoding/json"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// rpcError is a JSON-RPC error object
+type rpcError struct {
+	Code    int         `json:"code"`
+	Message string      `json:"message"`
+	Data    interface{} `json:"data,omitempty"`
+}
+
+// rpcResponse is a JSON-RPC response produced by the aggregator itself rather than the MCP server
+type rpcResponse struct {
+	JSONRPC string          `json:"jsonrpc"`
+	ID      json.RawMessage `json:"id"`
+	Result  interface{}     `json:"result,omitempty"`
+	Error   *rpcError       `json:"error,omitempty"`
+}
+
+// encodeResponse marshals a response, falling back to an internal error if that fails
+func encodeResponse(response rpcResponse) []byte {
+	if len(response.ID) == 0 {
+		response.ID = json.RawMessage("null")
+	}
+	response.JSONRPC = mcp.JSONRPC_VERSION
+
+	data, err := json.Marshal(response)
+	if err != nil {
+		logger.Error("Failed to marshal response: %v", err)
+		data, _ = json.Marshal(rpcResponse{
+			JSONRPC: mcp.JSONRPC_VERSION,
+			ID:      response.ID,
+			Error:   &rpcError{Code: mcp.INTERNAL_ERROR, Message: "failed to encode response"},
+		})
+	}
+	return data
+}
+
+// resultResponse encodes a successful response
+func resultResponse(id json.RawMessage, result interface{}) []byte {
+	return encodeResponse(rpcResponse{ID: id, Result: result})
+}
+
+// errorResponse encodes an error response
+func errorResponse(id json.RawMessage, code int, message string, data interface{}) []byte {
+	return encodeResponse(rpcResponse{ID: id, Error: &rpcError{Code: code, Message: message, Data: data}})
+}
+
+// requestID returns the encoded id of a decoded request, nil when it has none
+func requestID(request map[string]interface{}) json.RawMessage {
+	id, exists := request["id"]
+	if !exists {
+		return nil
+	}
+	encoded, err := json.Marshal(id)
+	if err != nil {
+		return nil
+	}
+	return encoded
+}
@@ -0,0 +1,61 @@
+package stdio
+
+import (
+	"context"
+	"encoding/json"
+	"strings"
+
+	"github.com/mark3labs/mcp-go/mcp"
+)
+
+// legacyResultsKey marks a context whose tools/call request asked for a flattened result
+type legacyResultsKey struct{}
+
+// withLegacyResults marks ctx when the raw request sets params._meta.legacyResults
+func withLegacyResults(ctx context.Context, req map[string]interface{}) context.Context {
+	params, _ := req["params"].(map[string]interface{})
+	meta, _ := params["_meta"].(map[string]interface{})
+	if legacy, _ := meta["legacyResults"].(bool); legacy {
+		return context.WithValue(ctx, legacyResultsKey{}, true)
+	}
+	return ctx
+}
+
+// wantsLegacyResults reports whether the tools/call request in ctx asked for a flattened result
+func wantsLegacyResults(ctx context.Context) bool {
+	legacy, _ := ctx.Value(legacyResultsKey{}).(bool)
+	return legacy
+}
+
+// flattenResult collapses a tool result into a single text block for clients that can't handle
+// structured content. Text blocks are joined by newlines, any other block as its JSON encoding.
+func flattenResult(result *mcp.CallToolResult) *mcp.CallToolResult {
+	if result == nil {
+		return nil
+	}
+
+	parts := make([]string, 0, len(result.Content))
+	for _, content := range result.Content {
+		if text, ok := content.(mcp.TextContent); ok {
+			parts = append(parts, text.Text)
+			continue
+		}
+		encoded, err := json.Marshal(content)
+		if err != nil {
+			continue
+		}
+		parts = append(parts, string(encoded))
+	}
+
+	flattened := &mcp.CallToolResult{
+		Content: []mcp.Content{
+			mcp.TextContent{
+				Type: "text",
+				Text: strings.Join(parts, "\n"),
+			},
+		},
+		IsError: result.IsError,
+	}
+	flattened.Meta = result.Meta
+	return flattened
+}
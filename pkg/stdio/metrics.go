@@ -0,0 +1,32 @@
+package stdio
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/mark3labs/mcp-go/server"
+)
+
+// MetricsToolName is the name of the built-in tool reporting the tool call metrics of every server
+const MetricsToolName = "combine_mcp_metrics"
+
+// metricsTool returns the built-in tool reporting the tool call metrics of every server
+func (s *AggregatorServer) metricsTool() server.ServerTool {
+	return server.ServerTool{
+		Tool: mcp.NewTool(MetricsToolName,
+			mcp.WithDescription("Report the tool calls dispatched to every MCP server combined by the aggregator since it started: how many succeeded and failed, their average and maximum latency and a latency histogram"),
+		),
+		Handler: s.handleMetrics,
+	}
+}
+
+// handleMetrics answers a call of the metrics tool with the servers' metrics as JSON
+func (s *AggregatorServer) handleMetrics(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	metrics, err := json.MarshalIndent(s.aggregator.Metrics(), "", "  ")
+	if err != nil {
+		return nil, fmt.Errorf("failed to encode server metrics: %w", err)
+	}
+	return mcp.NewToolResultText(string(metrics)), nil
+}
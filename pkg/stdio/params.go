@@ -0,0 +1,134 @@
+package stdio
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"reflect"
+)
+
+// requestParams are the decoded params of a method, which can tell whether a required field is missing
+type requestParams interface {
+	check() error
+}
+
+// callToolParams are the params of a tools/call request
+type callToolParams struct {
+	Name      string                 `json:"name"`
+	Arguments map[string]interface{} `json:"arguments,omitempty"`
+	Meta      map[string]interface{} `json:"_meta,omitempty"`
+}
+
+// check requires the name of the tool to call
+func (p *callToolParams) check() error {
+	if p.Name == "" {
+		return errors.New("field name is required")
+	}
+	return nil
+}
+
+// listToolsParams are the params of a tools/list request
+type listToolsParams struct {
+	Cursor string   `json:"cursor,omitempty"`
+	Tags   []string `json:"tags,omitempty"` // Only list the tools of servers carrying any of these tags
+}
+
+// check accepts any params, as every field is optional
+func (p *listToolsParams) check() error {
+	return nil
+}
+
+// readResourceParams are the params of a resources/read request
+type readResourceParams struct {
+	URI       string                 `json:"uri"`
+	Arguments map[string]interface{} `json:"arguments,omitempty"`
+}
+
+// check requires the uri of the resource to read
+func (p *readResourceParams) check() error {
+	if p.URI == "" {
+		return errors.New("field uri is required")
+	}
+	return nil
+}
+
+// getPromptParams are the params of a prompts/get request
+type getPromptParams struct {
+	Name      string            `json:"name"`
+	Arguments map[string]string `json:"arguments,omitempty"`
+}
+
+// check requires the name of the prompt to get
+func (p *getPromptParams) check() error {
+	if p.Name == "" {
+		return errors.New("field name is required")
+	}
+	return nil
+}
+
+// methodParams creates the params of the methods whose params are checked before they are handled
+var methodParams = map[string]func() requestParams{
+	"tools/call":     func() requestParams { return &callToolParams{} },
+	"tools/list":     func() requestParams { return &listToolsParams{} },
+	"resources/read": func() requestParams { return &readResourceParams{} },
+	"prompts/get":    func() requestParams { return &getPromptParams{} },
+}
+
+// decodeParams decodes the params of a request into the typed params of its method, or returns nil for
+// methods whose params aren't checked. The error names the offending field, ready for an invalid params response.
+func decodeParams(method string, line []byte) (requestParams, error) {
+	newParams, checked := methodParams[method]
+	if !checked {
+		return nil, nil
+	}
+
+	var request struct {
+		Params json.RawMessage `json:"params"`
+	}
+	if err := json.Unmarshal(line, &request); err != nil {
+		return nil, fmt.Errorf("invalid %s params: %v", method, err)
+	}
+
+	params := newParams()
+	if len(request.Params) > 0 && string(request.Params) != "null" {
+		if err := json.Unmarshal(request.Params, params); err != nil {
+			return nil, fmt.Errorf("invalid %s params: %s", method, describeParamsError(err))
+		}
+	}
+	if err := params.check(); err != nil {
+		return nil, fmt.Errorf("invalid %s params: %v", method, err)
+	}
+	return params, nil
+}
+
+// describeParamsError turns a decoding error into a message naming the field and the type it must have
+func describeParamsError(err error) string {
+	var typeErr *json.UnmarshalTypeError
+	if !errors.As(err, &typeErr) {
+		return err.Error()
+	}
+	if typeErr.Field == "" {
+		return fmt.Sprintf("params must be %s, got %s", jsonTypeName(typeErr.Type), typeErr.Value)
+	}
+	return fmt.Sprintf("field %s must be %s, got %s", typeErr.Field, jsonTypeName(typeErr.Type), typeErr.Value)
+}
+
+// jsonTypeName names the JSON type a Go type is decoded from
+func jsonTypeName(t reflect.Type) string {
+	switch t.Kind() {
+	case reflect.Map, reflect.Struct:
+		return "object"
+	case reflect.Slice, reflect.Array:
+		return "array"
+	case reflect.String:
+		return "string"
+	case reflect.Bool:
+		return "boolean"
+	case reflect.Int, reflect.Int8, reflect.Int16, reflect.Int32, reflect.Int64,
+		reflect.Uint, reflect.Uint8, reflect.Uint16, reflect.Uint32, reflect.Uint64,
+		reflect.Float32, reflect.Float64:
+		return "number"
+	default:
+		return t.String()
+	}
+}
@@ -0,0 +1,36 @@
+package stdio
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/mark3labs/mcp-go/server"
+)
+
+// ReloadToolName is the name of the built-in tool reloading the config, exposed with exposeReloadTool
+const ReloadToolName = "combine_mcp_reload"
+
+// reloadTool returns the built-in tool reloading the config the way SIGHUP does
+func (s *AggregatorServer) reloadTool() server.ServerTool {
+	return server.ServerTool{
+		Tool: mcp.NewTool(ReloadToolName,
+			mcp.WithDescription("Reload the aggregator's config file: start the MCP servers added to it, shut down the removed ones and restart the ones whose entry changed. Reports the servers added, removed and restarted"),
+		),
+		Handler: s.handleReload,
+	}
+}
+
+// handleReload answers a call of the reload tool with the servers the reload changed as JSON
+func (s *AggregatorServer) handleReload(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	summary, err := s.ReloadConfig(ctx)
+	if err != nil {
+		return nil, err
+	}
+	data, err := json.MarshalIndent(summary, "", "  ")
+	if err != nil {
+		return nil, fmt.Errorf("failed to encode reload summary: %w", err)
+	}
+	return mcp.NewToolResultText(string(data)), nil
+}
@@ -1,26 +1,55 @@
 package stdio
 
 import (
-	"bufio"
 	"context"
 	"encoding/json"
+	"errors"
 	"fmt"
+	"io"
 	"os"
+	"sync"
+	"sync/atomic"
 
 	"github.com/mark3labs/mcp-go/mcp"
 	"github.com/mark3labs/mcp-go/server"
 	"github.com/nazar256/combine-mcp/pkg/aggregator"
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/framing"
 	"github.com/nazar256/combine-mcp/pkg/logger"
+	"github.com/nazar256/combine-mcp/pkg/redact"
 )
 
+// maxErrorArgumentBytes is the largest encoded argument value echoed back in error data
+const maxErrorArgumentBytes = 256
+
 // AggregatorServer represents the MCP server that aggregates tools from multiple MCP servers
 type AggregatorServer struct {
 	mcpServer  *server.MCPServer
 	aggregator *aggregator.MCPAggregator
+	cfg        *config.Config
+
+	writerMu sync.Mutex
+	writer   *outputWriter // The stdio client's output, nil while it isn't served
+	sessions *sseSessions  // Clients connected over HTTP, nil unless MCP is served over HTTP
+	syncMu   sync.Mutex
+
+	toolsMu   sync.RWMutex
+	toolNames map[string]bool // Tools registered with the MCP server, built-in ones included
+
+	loaderMu   sync.Mutex
+	loadConfig func() (*config.Config, error) // Reads the config again for ReloadConfig, nil until it's set
+
+	nextRequestID atomic.Int64 // Ids of the requests the aggregator sends the client
+	pendingMu     sync.Mutex
+	pending       map[int64]chan *clientMessage // Requests waiting for the client's response, nil while none can arrive
 }
 
 // NewAggregatorServer creates a new AggregatorServer
-func NewAggregatorServer(serverName, version string, aggregator *aggregator.MCPAggregator) *AggregatorServer {
+func NewAggregatorServer(serverName, version string, aggregator *aggregator.MCPAggregator, cfg *config.Config) *AggregatorServer {
+	if cfg == nil {
+		cfg = &config.Config{}
+	}
+
 	// Add debug hooks
 	hooks := &server.Hooks{}
 
@@ -40,6 +69,9 @@ func NewAggregatorServer(serverName, version string, aggregator *aggregator.MCPA
 		logger.Info("Initialize request from: %s %s", message.Params.ClientInfo.Name, message.Params.ClientInfo.Version)
 		logger.Debug("Initialize params: %+v", message.Params)
 
+		// Servers started from now on are initialized on behalf of this client
+		aggregator.SetUpstreamClient(message.Params.ClientInfo, message.Params.Capabilities)
+
 		// Check if we have a custom protocol version to use (for compatibility)
 		if protocolVersion := os.Getenv("MCP_PROTOCOL_VERSION"); protocolVersion != "" {
 			logger.Info("Overriding protocol version to %s for compatibility", protocolVersion)
@@ -50,6 +82,20 @@ func NewAggregatorServer(serverName, version string, aggregator *aggregator.MCPA
 	hooks.AddAfterInitialize(func(id any, message *mcp.InitializeRequest, result *mcp.InitializeResult) {
 		logger.Info("Initialize response: server %s %s", result.ServerInfo.Name, result.ServerInfo.Version)
 
+		result.ProtocolVersion = answerProtocolVersion(message.Params.ProtocolVersion)
+		if result.ProtocolVersion != message.Params.ProtocolVersion {
+			logger.Info("Client requested unsupported protocol version %s, answering with %s", message.Params.ProtocolVersion, result.ProtocolVersion)
+		}
+
+		// Only offer resources and prompts when a server provides them; tools and logging are the aggregator's own
+		provided := aggregator.Capabilities()
+		if !provided.Resources {
+			result.Capabilities.Resources = nil
+		}
+		if !provided.Prompts {
+			result.Capabilities.Prompts = nil
+		}
+
 		// Check if we're in Cursor mode
 		if os.Getenv("MCP_CURSOR_MODE") != "" {
 			logger.Info("Cursor compatibility mode enabled - customizing response")
@@ -75,37 +121,251 @@ func NewAggregatorServer(serverName, version string, aggregator *aggregator.MCPA
 		serverName,
 		version,
 		server.WithLogging(),
+		server.WithToolCapabilities(true),
+		server.WithResourceCapabilities(false, false),
+		server.WithPromptCapabilities(false),
 		server.WithHooks(hooks),
 	)
 
-	return &AggregatorServer{
+	s := &AggregatorServer{
 		mcpServer:  mcpServer,
 		aggregator: aggregator,
+		cfg:        cfg,
+	}
+
+	// Tool changes may be reported from inside a tool handler, so re-sync asynchronously
+	aggregator.OnToolsChanged(func() { go s.handleToolsChanged() })
+	// Progress of long-running tool calls is passed through to the client, which knows its request by the token
+	aggregator.OnProgress(func(params json.RawMessage) { s.notify("notifications/progress", params) })
+	// Sampling requests of servers are sent on to the client, whose answer goes back to the server
+	aggregator.OnSampling(s.relaySampling)
+
+	return s
+}
+
+// answerProtocolVersion picks the protocol version to answer the client's initialize with.
+// A version forced with MCP_PROTOCOL_VERSION is passed through as is.
+func answerProtocolVersion(requested string) string {
+	if os.Getenv("MCP_PROTOCOL_VERSION") != "" {
+		return requested
 	}
+	return aggregator.NegotiateProtocolVersion(requested)
 }
 
 // RegisterTools registers all tools from the aggregator to the MCP server
 func (s *AggregatorServer) RegisterTools() error {
+	var builtins []server.ServerTool
+	if s.cfg.ExposesBuiltinTools() {
+		builtins = []server.ServerTool{s.statusTool(), s.metricsTool()}
+	}
+	if s.cfg.ExposeReloadTool {
+		builtins = append(builtins, s.reloadTool())
+	}
+	// Built-in tools taking precedence keep the server tools of their names out of everything the aggregator lists
+	var reserved []string
+	if s.cfg.ToolPrecedence == config.ToolPrecedenceBuiltinFirst {
+		reserved = BuiltinToolNames(s.cfg)
+	}
+	s.aggregator.ReserveToolNames(reserved)
+
 	// Get tools from aggregator
 	tools := s.aggregator.GetTools()
 	logger.Info("Registering %d tools from aggregator", len(tools))
 
-	// Register each tool with the MCP server
+	// Register each tool with the MCP server, replacing any previously registered set
+	serverTools := make([]server.ServerTool, 0, len(tools)+len(builtins))
+	names := make(map[string]bool, len(tools))
 	for _, tool := range tools {
 		logger.Debug("Registering tool: %s", tool.Name)
-		s.mcpServer.AddTool(
-			mcp.Tool{
+		names[tool.Name] = true
+		serverTools = append(serverTools, server.ServerTool{
+			Tool: mcp.Tool{
 				Name:        tool.Name,
 				Description: tool.Description,
 				InputSchema: tool.InputSchema,
 			},
-			s.createToolHandler(tool.Name),
-		)
+			Handler: s.createToolHandler(tool.Name),
+		})
+	}
+
+	// A server tool or route of the same name takes precedence over a built-in tool, unless toolPrecedence
+	// is builtinFirst, which kept it out of the aggregator's tools
+	for _, builtin := range builtins {
+		if names[builtin.Tool.Name] {
+			logger.Error("Warning: tool %s shadows a built-in tool", builtin.Tool.Name)
+			continue
+		}
+		serverTools = append(serverTools, builtin)
+		names[builtin.Tool.Name] = true
 	}
+	s.mcpServer.SetTools(serverTools...)
+
+	s.toolsMu.Lock()
+	s.toolNames = names
+	s.toolsMu.Unlock()
 
 	return nil
 }
 
+// BuiltinToolNames returns the names of the built-in tools the config exposes
+func BuiltinToolNames(cfg *config.Config) []string {
+	var names []string
+	if cfg.ExposesBuiltinTools() {
+		names = append(names, StatusToolName, MetricsToolName)
+	}
+	if cfg.ExposeReloadTool {
+		names = append(names, ReloadToolName)
+	}
+	return names
+}
+
+// RegisterResources registers the resources of all servers with the MCP server
+func (s *AggregatorServer) RegisterResources() error {
+	resources := s.aggregator.GetResources()
+	logger.Info("Registering %d resources from aggregator", len(resources))
+
+	for _, resource := range resources {
+		logger.Debug("Registering resource: %s", resource.URI)
+		s.mcpServer.AddResource(resource, s.readResource)
+	}
+
+	return nil
+}
+
+// readResource forwards a resources/read request to the aggregator
+func (s *AggregatorServer) readResource(ctx context.Context, request mcp.ReadResourceRequest) ([]mcp.ResourceContents, error) {
+	result, err := s.aggregator.ReadResource(ctx, request)
+	if err != nil {
+		logger.Error("Resource read failed: %s, error: %v", request.Params.URI, err)
+		return nil, err
+	}
+	return result.Contents, nil
+}
+
+// RegisterPrompts registers the prompts of all servers with the MCP server
+func (s *AggregatorServer) RegisterPrompts() error {
+	prompts := s.aggregator.GetPrompts()
+	logger.Info("Registering %d prompts from aggregator", len(prompts))
+
+	for _, prompt := range prompts {
+		logger.Debug("Registering prompt: %s", prompt.Name)
+		s.mcpServer.AddPrompt(prompt, s.getPrompt)
+	}
+
+	return nil
+}
+
+// getPrompt forwards a prompts/get request to the aggregator
+func (s *AggregatorServer) getPrompt(ctx context.Context, request mcp.GetPromptRequest) (*mcp.GetPromptResult, error) {
+	result, err := s.aggregator.GetPrompt(ctx, request)
+	if err != nil {
+		logger.Error("Prompt request failed: %s, error: %v", request.Params.Name, err)
+	}
+	return result, err
+}
+
+// handleToolsChanged re-registers the exposed tools and tells the client the list changed
+func (s *AggregatorServer) handleToolsChanged() {
+	s.syncMu.Lock()
+	defer s.syncMu.Unlock()
+
+	if err := s.RegisterTools(); err != nil {
+		logger.Error("Failed to re-register tools: %v", err)
+		return
+	}
+	s.notify("notifications/tools/list_changed", nil)
+}
+
+// startLazyServers starts the lazy servers when something is first listed, registering what they provide
+func (s *AggregatorServer) startLazyServers(ctx context.Context) {
+	if !s.aggregator.StartLazyServers(ctx) {
+		return
+	}
+
+	s.syncMu.Lock()
+	defer s.syncMu.Unlock()
+	if err := s.RegisterTools(); err != nil {
+		logger.Error("Failed to register tools of lazy servers: %v", err)
+	}
+	if err := s.RegisterResources(); err != nil {
+		logger.Error("Failed to register resources of lazy servers: %v", err)
+	}
+	if err := s.RegisterPrompts(); err != nil {
+		logger.Error("Failed to register prompts of lazy servers: %v", err)
+	}
+}
+
+// Reload applies the servers of a reloaded config and registers the resources and prompts the added servers
+// provide; their tools are registered, and the client notified of them, once the aggregator reports the change
+func (s *AggregatorServer) Reload(ctx context.Context, cfg *config.Config) (aggregator.ReloadSummary, error) {
+	summary, err := s.aggregator.Reload(ctx, cfg)
+	if err != nil {
+		return summary, fmt.Errorf("ignoring the reloaded config: %w", err)
+	}
+	if summary.Empty() {
+		return summary, nil
+	}
+
+	s.syncMu.Lock()
+	defer s.syncMu.Unlock()
+	if err := s.RegisterResources(); err != nil {
+		logger.Error("Failed to register resources after reloading the config: %v", err)
+	}
+	if err := s.RegisterPrompts(); err != nil {
+		logger.Error("Failed to register prompts after reloading the config: %v", err)
+	}
+	return summary, nil
+}
+
+// SetConfigLoader sets how the config is read again by ReloadConfig
+func (s *AggregatorServer) SetConfigLoader(load func() (*config.Config, error)) {
+	s.loaderMu.Lock()
+	defer s.loaderMu.Unlock()
+	s.loadConfig = load
+}
+
+// ReloadConfig reads the config again with the loader set by SetConfigLoader and applies it, see Reload.
+// A config that fails to load leaves the current one in effect.
+func (s *AggregatorServer) ReloadConfig(ctx context.Context) (aggregator.ReloadSummary, error) {
+	s.loaderMu.Lock()
+	load := s.loadConfig
+	s.loaderMu.Unlock()
+	if load == nil {
+		return aggregator.ReloadSummary{}, errors.New("the config can't be reloaded: it wasn't loaded from a file")
+	}
+
+	cfg, err := load()
+	if err != nil {
+		return aggregator.ReloadSummary{}, fmt.Errorf("failed to reload the config, keeping the current one: %w", err)
+	}
+	return s.Reload(ctx, cfg)
+}
+
+// notify sends a server-initiated notification to the client, if one is connected
+func (s *AggregatorServer) notify(method string, params interface{}) {
+	writer := s.clientWriter(false)
+	if writer == nil {
+		logger.Debug("Dropping notification %s: no client connected", method)
+		return
+	}
+
+	notification := map[string]interface{}{
+		"jsonrpc": "2.0",
+		"method":  method,
+	}
+	if params != nil {
+		notification["params"] = params
+	}
+	message, err := json.Marshal(notification)
+	if err != nil {
+		logger.Error("Failed to marshal notification %s: %v", method, err)
+		return
+	}
+
+	logger.LogRPC("OUT", message)
+	writer.Write(message)
+}
+
 // createToolHandler creates a handler function for a specific tool
 func (s *AggregatorServer) createToolHandler(toolName string) server.ToolHandlerFunc {
 	return func(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
@@ -113,88 +373,409 @@ func (s *AggregatorServer) createToolHandler(toolName string) server.ToolHandler
 		logger.Debug("Handling tool call: %s", toolName)
 		result, err := s.aggregator.CallTool(ctx, request)
 		if err != nil {
+			// Servers may echo the secrets they were given back in their errors
+			err = redact.Error(err)
 			logger.Error("Tool call failed: %s, error: %v", toolName, err)
-		} else {
-			logger.Debug("Tool call succeeded: %s", toolName)
+			if s.cfg.ToolErrorsAsContent {
+				return errorResult(err), nil
+			}
+			recordToolCallError(ctx, err)
+			return result, err
+		}
+
+		logger.Debug("Tool call succeeded: %s", toolName)
+		if s.cfg.LegacyResults || wantsLegacyResults(ctx) {
+			result = flattenResult(result)
 		}
-		return result, err
+		return result, nil
+	}
+}
+
+// errorResult converts a tool call failure into an isError result the model can read
+func errorResult(err error) *mcp.CallToolResult {
+	return &mcp.CallToolResult{
+		Content: []mcp.Content{
+			mcp.TextContent{
+				Type: "text",
+				Text: fmt.Sprintf("Tool call failed: %v", err),
+			},
+		},
+		IsError: true,
+	}
+}
+
+// ServeStdio serves the MCP server over stdio with message logging until stdin is closed or ctx is done
+func (s *AggregatorServer) ServeStdio(ctx context.Context) error {
+	return s.serveUntilDone(ctx, os.Stdin, os.Stdout)
+}
+
+// serveUntilDone serves in and out like serve, but returns as soon as ctx is done, as on SIGTERM, without
+// waiting for the client to close in. A pending read of in can't be interrupted, so it's left behind for
+// the process exit to end.
+func (s *AggregatorServer) serveUntilDone(ctx context.Context, in io.Reader, out io.Writer) error {
+	served := make(chan error, 1)
+	go func() { served <- s.serve(ctx, in, out) }()
+	select {
+	case err := <-served:
+		return err
+	case <-ctx.Done():
+		logger.Debug("Stopped serving stdio before stdin was closed")
+		return nil
 	}
 }
 
-// ServeStdio serves the MCP server over stdio with message logging
-func (s *AggregatorServer) ServeStdio() error {
+// serve reads JSON-RPC messages, framed as configured, from in and writes the responses to out
+func (s *AggregatorServer) serve(ctx context.Context, in io.Reader, out io.Writer) error {
 	logger.Debug("Starting stdio server")
 
-	scanner := bufio.NewScanner(os.Stdin)
-	scanner.Buffer(make([]byte, 1024*1024), 1024*1024) // Increase scanner buffer size
-	ctx := context.Background()
+	writer := newOutputWriter(out, s.cfg.PipelineOutput, s.cfg.CoalesceOutput, s.cfg.Framing)
+	s.writerMu.Lock()
+	s.writer = writer
+	s.writerMu.Unlock()
+	defer func() {
+		s.writerMu.Lock()
+		s.writer = nil
+		s.writerMu.Unlock()
+		writer.Close()
+	}()
+
+	// Responses written by the read loop are held back until it would wait for the client
+	if s.cfg.CoalesceOutput {
+		in = &flushBeforeRead{in: in, flush: writer.Flush}
+	}
+	reader := framing.NewReader(in, s.cfg.Framing)
+	responses := newResponseSequence(writer, s.cfg.PipelineOutput)
+
+	// Tool calls are handled concurrently, so responses to the requests they make the aggregator
+	// send the client, such as sampling, are read while they wait. Once the client is gone, the calls
+	// still running are cancelled on their servers, as nobody is left to get their results.
+	ctx, cancel := context.WithCancel(ctx)
+	s.acceptResponses()
+	var toolCalls sync.WaitGroup
+	defer toolCalls.Wait()
+	defer cancel()
+	defer s.failPendingRequests()
+
+	for {
+		line, err := reader.ReadMessage()
+		if err == io.EOF {
+			return nil
+		}
+		if err != nil {
+			logger.Error("Error reading from client: %v", err)
+			return err
+		}
 
-	for scanner.Scan() {
-		line := scanner.Bytes()
-		if len(line) == 0 {
-			continue // Skip empty lines
+		var msg clientMessage
+		if !isBatch(line) && json.Unmarshal(line, &msg) == nil {
+			if msg.isResponse() {
+				logger.LogRPC("IN", line)
+				s.handleResponse(&msg)
+				continue
+			}
+			if msg.Method == "tools/call" {
+				respond := responses.reserve(true)
+				toolCalls.Add(1)
+				go func() {
+					defer toolCalls.Done()
+					respond(s.handleMessage(ctx, line))
+				}()
+				continue
+			}
 		}
 
-		// Log incoming message to file only with extra detail
-		logger.LogRPC("IN", line)
+		// Write response - this must be the only thing written to stdout
+		// No logging, no extra output, just the pure JSON response
+		respond := responses.reserve(false)
+		if isBatch(line) {
+			respond(s.handleBatch(ctx, line))
+		} else {
+			respond(s.handleMessage(ctx, line))
+		}
+	}
+}
 
-		// Try to parse the incoming message for better logging
-		var req map[string]interface{}
-		if err := json.Unmarshal(line, &req); err == nil {
-			if method, ok := req["method"].(string); ok {
-				id := "null"
-				if reqID, exists := req["id"]; exists {
-					id = fmt.Sprintf("%v", reqID)
-				}
-				logger.Debug("Received request: method=%s, id=%s", method, id)
+// handleMessage processes a single incoming JSON-RPC message and returns the encoded response, if any
+func (s *AggregatorServer) handleMessage(ctx context.Context, line []byte) []byte {
+	// Log incoming message to file only with extra detail
+	logger.LogRPC("IN", line)
+
+	// Try to parse the incoming message for better logging
+	var req map[string]interface{}
+	method := ""
+	isNotification := false
+	if err := json.Unmarshal(line, &req); err == nil {
+		if m, ok := req["method"].(string); ok {
+			method = m
+			id := "null"
+			reqID, exists := req["id"]
+			if exists {
+				id = fmt.Sprintf("%v", reqID)
 			}
+			// Messages without an id are notifications, which must never be answered
+			isNotification = !exists
+			logger.Debug("Received request: method=%s, id=%s", method, id)
 		}
+	}
 
-		// Handle message
-		response := s.mcpServer.HandleMessage(ctx, line)
-		if response != nil {
-			responseBytes, err := json.Marshal(response)
-			if err != nil {
-				logger.Error("Failed to marshal response: %v", err)
-				continue
-			}
+	// Anything else than JSON-RPC 2.0 is rejected, even without an id, as the spec asks
+	if req != nil {
+		if version, _ := req["jsonrpc"].(string); version != mcp.JSONRPC_VERSION {
+			logger.Error("Rejecting message with jsonrpc version %v", req["jsonrpc"])
+			responseBytes := errorResponse(requestID(req), mcp.INVALID_REQUEST, fmt.Sprintf("invalid request: jsonrpc must be %q", mcp.JSONRPC_VERSION), nil)
+			logger.LogRPC("OUT", responseBytes)
+			return responseBytes
+		}
+	}
+
+	// Params the MCP server would reject without saying which field is wrong are checked first
+	params, err := decodeParams(method, line)
+	if err != nil {
+		if isNotification {
+			return nil
+		}
+		responseBytes := errorResponse(requestID(req), mcp.INVALID_PARAMS, err.Error(), nil)
+		logger.LogRPC("OUT", responseBytes)
+		return responseBytes
+	}
+
+	// Methods the aggregator answers itself rather than the MCP server
+	if method == "logging/setLevel" {
+		responseBytes := s.handleSetLevel(ctx, line)
+		if isNotification {
+			return nil
+		}
+		logger.LogRPC("OUT", responseBytes)
+		return responseBytes
+	}
+
+	var toolCall *toolCallOutcome
+	if method == "tools/call" {
+		ctx = withLegacyResults(ctx, req)
+		var correlationID string
+		ctx, correlationID = aggregator.WithCorrelationID(ctx)
+		logger.Trace("Tool call with id %v has correlation id %s", req["id"], correlationID)
 
-			// Log outgoing message to file only with extra detail
+		// The MCP server would answer calls of tools it doesn't know with a bare "not found"
+		if call := params.(*callToolParams); !s.isRegisteredTool(call.Name) && !isNotification {
+			responseBytes := s.withErrorArguments(req, s.callUnregisteredTool(ctx, req, line))
 			logger.LogRPC("OUT", responseBytes)
+			return responseBytes
+		}
+		ctx, toolCall = withToolCallOutcome(ctx)
+	}
+	if method == "tools/list" || method == "resources/list" || method == "prompts/list" {
+		s.startLazyServers(ctx)
+	}
 
-			// Try to parse the response for better logging
-			var resp map[string]interface{}
-			if err := json.Unmarshal(responseBytes, &resp); err == nil {
-				id := "null"
-				if respID, exists := resp["id"]; exists {
-					id = fmt.Sprintf("%v", respID)
-				}
+	// Handle message
+	response := s.mcpServer.HandleMessage(ctx, line)
+	if response == nil {
+		return nil
+	}
+	if isNotification {
+		logger.Debug("Not answering notification %s", method)
+		return nil
+	}
+
+	responseBytes, err := json.Marshal(response)
+	if err != nil {
+		logger.Error("Failed to marshal response: %v", err)
+		return nil
+	}
+
+	if method == "tools/list" {
+		origins := s.aggregator.ToolOrigins()
+		if tags := params.(*listToolsParams).Tags; len(tags) > 0 {
+			responseBytes = withTaggedToolsOnly(responseBytes, origins, s.aggregator.TaggedTools(tags))
+		}
+		responseBytes = withToolOrigins(responseBytes, origins, s.aggregator.StaleTools())
+	}
+	if method == "tools/call" {
+		if toolCall.err != nil {
+			responseBytes = toolCallErrorResponse(requestID(req), toolCall.err)
+		}
+		responseBytes = s.withErrorArguments(req, responseBytes)
+	}
+
+	// Log outgoing message to file only with extra detail
+	logger.LogRPC("OUT", responseBytes)
+
+	// Try to parse the response for better logging
+	var resp map[string]interface{}
+	if err := json.Unmarshal(responseBytes, &resp); err == nil {
+		id := "null"
+		if respID, exists := resp["id"]; exists {
+			id = fmt.Sprintf("%v", respID)
+		}
+
+		if result, exists := resp["result"]; exists {
+			logger.Debug("Sending response: id=%s, success=true", id)
 
-				if result, exists := resp["result"]; exists {
-					logger.Debug("Sending response: id=%s, success=true", id)
-
-					// For tools/list specifically, log the count of tools
-					if toolsResult, ok := result.(map[string]interface{}); ok {
-						if tools, exists := toolsResult["tools"].([]interface{}); exists {
-							logger.Debug("Response includes %d tools", len(tools))
-						}
-					}
-				} else if _, exists := resp["error"]; exists {
-					logger.Debug("Sending response: id=%s, error=true", id)
+			// For tools/list specifically, log the count of tools
+			if toolsResult, ok := result.(map[string]interface{}); ok {
+				if tools, exists := toolsResult["tools"].([]interface{}); exists {
+					logger.Debug("Response includes %d tools", len(tools))
 				}
 			}
+		} else if _, exists := resp["error"]; exists {
+			logger.Debug("Sending response: id=%s, error=true", id)
+		}
+	}
+
+	return responseBytes
+}
+
+// handleSetLevel changes the aggregator's log level and optionally propagates it to the servers
+func (s *AggregatorServer) handleSetLevel(ctx context.Context, line []byte) []byte {
+	var request struct {
+		ID     json.RawMessage `json:"id"`
+		Params struct {
+			Level mcp.LoggingLevel `json:"level"`
+		} `json:"params"`
+	}
+	if err := json.Unmarshal(line, &request); err != nil {
+		return errorResponse(nil, mcp.INVALID_PARAMS, fmt.Sprintf("invalid logging/setLevel params: %v", err), nil)
+	}
+
+	level, ok := logLevelFromMCP(request.Params.Level)
+	if !ok {
+		return errorResponse(request.ID, mcp.INVALID_PARAMS, fmt.Sprintf("unknown log level %q", request.Params.Level), nil)
+	}
+
+	logger.SetLevel(level)
+	logger.Info("Log level set to %s by client", request.Params.Level)
+
+	if s.cfg.PropagateLogLevel {
+		s.aggregator.SetLogLevel(ctx, request.Params.Level)
+	}
+
+	return resultResponse(request.ID, struct{}{})
+}
+
+// logLevelFromMCP maps an MCP logging level onto the aggregator's coarser log levels
+func logLevelFromMCP(level mcp.LoggingLevel) (config.LogLevel, bool) {
+	switch level {
+	case mcp.LoggingLevelDebug:
+		return config.LogLevelDebug, true
+	case mcp.LoggingLevelInfo, mcp.LoggingLevelNotice:
+		return config.LogLevelInfo, true
+	case mcp.LoggingLevelWarning, mcp.LoggingLevelError, mcp.LoggingLevelCritical, mcp.LoggingLevelAlert, mcp.LoggingLevelEmergency:
+		return config.LogLevelError, true
+	default:
+		return config.LogLevelInfo, false
+	}
+}
+
+// withErrorArguments attaches a redacted snapshot of the call arguments to a failed tools/call response
+func (s *AggregatorServer) withErrorArguments(req map[string]interface{}, responseBytes []byte) []byte {
+	mode := s.cfg.ErrorArguments
+	if mode != config.ErrorArgumentsKeys && mode != config.ErrorArgumentsValues {
+		return responseBytes
+	}
+
+	var resp map[string]interface{}
+	if err := json.Unmarshal(responseBytes, &resp); err != nil {
+		return responseBytes
+	}
+	rpcErr, ok := resp["error"].(map[string]interface{})
+	if !ok {
+		return responseBytes
+	}
+
+	var args map[string]interface{}
+	if params, ok := req["params"].(map[string]interface{}); ok {
+		args, _ = params["arguments"].(map[string]interface{})
+	}
 
-			// Write response - this must be the only thing written to stdout
-			// No logging, no extra output, just the pure JSON response
-			// We explicitly use os.Stdout to ensure we're writing to the original stdout
-			fmt.Fprintln(os.Stdout, string(responseBytes))
+	data, ok := rpcErr["data"].(map[string]interface{})
+	if !ok {
+		data = make(map[string]interface{})
+		if existing, exists := rpcErr["data"]; exists && existing != nil {
+			data["detail"] = existing
 		}
 	}
+	data["arguments"] = redact.Arguments(args, mode == config.ErrorArgumentsValues, maxErrorArgumentBytes)
+	rpcErr["data"] = data
 
-	if err := scanner.Err(); err != nil {
-		logger.Error("Scanner error: %v", err)
-		return err
+	updated, err := json.Marshal(resp)
+	if err != nil {
+		logger.Error("Failed to attach arguments to error response: %v", err)
+		return responseBytes
 	}
+	return updated
+}
 
-	return nil
+// withToolOrigins names the server each tool of a tools/list response comes from in the tool's _meta, and
+// marks the stale tools of restarting servers, leaving built-in tools, which don't come from any server, as they are
+func withToolOrigins(responseBytes []byte, origins map[string]string, stale map[string]bool) []byte {
+	var resp map[string]interface{}
+	if err := json.Unmarshal(responseBytes, &resp); err != nil {
+		return responseBytes
+	}
+	result, _ := resp["result"].(map[string]interface{})
+	tools, ok := result["tools"].([]interface{})
+	if !ok {
+		return responseBytes
+	}
+
+	for _, entry := range tools {
+		tool, ok := entry.(map[string]interface{})
+		if !ok {
+			continue
+		}
+		name, _ := tool["name"].(string)
+		serverName, exists := origins[name]
+		if !exists {
+			continue
+		}
+		meta, ok := tool["_meta"].(map[string]interface{})
+		if !ok {
+			meta = make(map[string]interface{})
+		}
+		meta["server"] = serverName
+		if stale[name] {
+			meta["stale"] = true
+		}
+		tool["_meta"] = meta
+	}
+
+	updated, err := json.Marshal(resp)
+	if err != nil {
+		logger.Error("Failed to attach tool origins to tools/list response: %v", err)
+		return responseBytes
+	}
+	return updated
+}
+
+// withTaggedToolsOnly removes the tools of servers without any of the requested tags from a tools/list
+// response, keeping built-in tools, which don't come from any server
+func withTaggedToolsOnly(responseBytes []byte, origins map[string]string, tagged map[string]bool) []byte {
+	var resp map[string]interface{}
+	if err := json.Unmarshal(responseBytes, &resp); err != nil {
+		return responseBytes
+	}
+	result, _ := resp["result"].(map[string]interface{})
+	tools, ok := result["tools"].([]interface{})
+	if !ok {
+		return responseBytes
+	}
+
+	kept := make([]interface{}, 0, len(tools))
+	for _, entry := range tools {
+		tool, _ := entry.(map[string]interface{})
+		name, _ := tool["name"].(string)
+		if _, fromServer := origins[name]; fromServer && !tagged[name] {
+			continue
+		}
+		kept = append(kept, entry)
+	}
+	result["tools"] = kept
+
+	updated, err := json.Marshal(resp)
+	if err != nil {
+		logger.Error("Failed to filter tools/list response by tags: %v", err)
+		return responseBytes
+	}
+	return updated
 }
@@ -0,0 +1,666 @@
+package stdio
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/aggregator"
+	"github.com/nazar256/combine-mcp/pkg/config"
+	"github.com/nazar256/combine-mcp/pkg/framing"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+func TestMain(m *testing.M) {
+	// Hooks log errors, so the logger has to be set up before any message is handled
+	if err := logger.Init(config.LogLevelError, "", config.LogFileOptions{}); err != nil {
+		panic(err)
+	}
+	os.Exit(m.Run())
+}
+
+// newTestServer creates a server backed by an aggregator without any downstream servers
+func newTestServer(cfg *config.Config) *AggregatorServer {
+	return NewAggregatorServer("test-aggregator", "1.0.0", aggregator.NewMCPAggregator(), cfg)
+}
+
+// decodeResponse decodes a JSON-RPC response into a generic map
+func decodeResponse(t *testing.T, response []byte) map[string]interface{} {
+	t.Helper()
+	var decoded map[string]interface{}
+	if err := json.Unmarshal(response, &decoded); err != nil {
+		t.Fatalf("Failed to decode response %s: %v", response, err)
+	}
+	return decoded
+}
+
+func TestErrorArguments(t *testing.T) {
+	request := []byte(`{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"missing_tool","arguments":{"query":"hello","apiToken":"secret-value"}}}`)
+
+	tests := []struct {
+		name     string
+		mode     string
+		wantData interface{}
+	}{
+		{
+			name:     "Disabled by default",
+			mode:     "",
+			wantData: nil,
+		},
+		{
+			name:     "Keys only",
+			mode:     config.ErrorArgumentsKeys,
+			wantData: []interface{}{"apiToken", "query"},
+		},
+		{
+			name:     "Values with secrets redacted",
+			mode:     config.ErrorArgumentsValues,
+			wantData: map[string]interface{}{"apiToken": "***", "query": "hello"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			s := newTestServer(&config.Config{ErrorArguments: tt.mode})
+			resp := decodeResponse(t, s.handleMessage(context.Background(), request))
+
+			rpcErr, ok := resp["error"].(map[string]interface{})
+			if !ok {
+				t.Fatalf("Expected an error response, got %v", resp)
+			}
+
+			data, _ := rpcErr["data"].(map[string]interface{})
+			if tt.wantData == nil {
+				if _, exists := data["arguments"]; exists {
+					t.Errorf("Expected no arguments in the error data, got %v", data)
+				}
+				return
+			}
+
+			gotJSON, _ := json.Marshal(data["arguments"])
+			wantJSON, _ := json.Marshal(tt.wantData)
+			if string(gotJSON) != string(wantJSON) {
+				t.Errorf("Error data arguments = %s, want %s", gotJSON, wantJSON)
+			}
+		})
+	}
+}
+
+func TestPipelinedOutputPreservesOrder(t *testing.T) {
+	const requestCount = 500
+
+	var input strings.Builder
+	for i := 1; i <= requestCount; i++ {
+		fmt.Fprintf(&input, `{"jsonrpc":"2.0","id":%d,"method":"ping"}`+"\n", i)
+	}
+
+	for _, pipelined := range []bool{false, true} {
+		t.Run(fmt.Sprintf("pipelined=%v", pipelined), func(t *testing.T) {
+			s := newTestServer(&config.Config{PipelineOutput: pipelined})
+
+			var output bytes.Buffer
+			if err := s.serve(context.Background(), strings.NewReader(input.String()), &output); err != nil {
+				t.Fatalf("serve() error = %v", err)
+			}
+
+			scanner := bufio.NewScanner(&output)
+			count := 0
+			for scanner.Scan() {
+				count++
+				resp := decodeResponse(t, scanner.Bytes())
+				if id, _ := resp["id"].(float64); int(id) != count {
+					t.Fatalf("Response %d has id %v, responses are out of order", count, resp["id"])
+				}
+			}
+			if count != requestCount {
+				t.Errorf("Got %d responses, want %d", count, requestCount)
+			}
+		})
+	}
+}
+
+func TestCoalescedOutputFlushesBeforeWaiting(t *testing.T) {
+	s := newTestServer(&config.Config{CoalesceOutput: true})
+	inReader, inWriter := io.Pipe()
+	outReader, outWriter := io.Pipe()
+	served := make(chan error, 1)
+	go func() { served <- s.serve(context.Background(), inReader, outWriter) }()
+
+	// The response has to arrive while the server waits for the next request
+	if _, err := inWriter.Write([]byte(`{"jsonrpc":"2.0","id":1,"method":"ping"}` + "\n")); err != nil {
+		t.Fatalf("Write() error = %v", err)
+	}
+	line, err := bufio.NewReader(outReader).ReadBytes('\n')
+	if err != nil {
+		t.Fatalf("ReadBytes() error = %v", err)
+	}
+	if id, _ := decodeResponse(t, line)["id"].(float64); id != 1 {
+		t.Errorf("Response %s has id %v, want 1", line, id)
+	}
+
+	inWriter.Close()
+	if err := <-served; err != nil {
+		t.Errorf("serve() error = %v", err)
+	}
+}
+
+func TestServeUntilDoneStopsOnCancel(t *testing.T) {
+	s := newTestServer(&config.Config{})
+	inReader, inWriter := io.Pipe()
+	defer inWriter.Close()
+	ctx, cancel := context.WithCancel(context.Background())
+	served := make(chan error, 1)
+	go func() { served <- s.serveUntilDone(ctx, inReader, io.Discard) }()
+
+	// Cancelling stands for SIGTERM, while the client still holds stdin open
+	cancel()
+	select {
+	case err := <-served:
+		if err != nil {
+			t.Errorf("serveUntilDone() error = %v", err)
+		}
+	case <-time.After(5 * time.Second):
+		t.Fatal("serveUntilDone() kept serving after its context was cancelled")
+	}
+}
+
+// BenchmarkServeBurst measures answering a burst of 1000 requests written to a pipe, as on stdout
+func BenchmarkServeBurst(b *testing.B) {
+	const requestCount = 1000
+
+	var input strings.Builder
+	for i := 1; i <= requestCount; i++ {
+		fmt.Fprintf(&input, `{"jsonrpc":"2.0","id":%d,"method":"ping"}`+"\n", i)
+	}
+
+	for _, coalesce := range []bool{false, true} {
+		b.Run(fmt.Sprintf("coalesce=%v", coalesce), func(b *testing.B) {
+			s := newTestServer(&config.Config{CoalesceOutput: coalesce})
+			for i := 0; i < b.N; i++ {
+				outReader, outWriter, err := os.Pipe()
+				if err != nil {
+					b.Fatalf("Pipe() error = %v", err)
+				}
+				drained := make(chan struct{})
+				go func() {
+					io.Copy(io.Discard, outReader)
+					close(drained)
+				}()
+
+				if err := s.serve(context.Background(), strings.NewReader(input.String()), outWriter); err != nil {
+					b.Fatalf("serve() error = %v", err)
+				}
+				outWriter.Close()
+				<-drained
+				outReader.Close()
+			}
+		})
+	}
+}
+
+func TestContentLengthFraming(t *testing.T) {
+	var input bytes.Buffer
+	for i := 1; i <= 3; i++ {
+		input.Write(framing.Encode(config.FramingContentLength, []byte(fmt.Sprintf("{\n  \"jsonrpc\": \"2.0\",\n  \"id\": %d,\n  \"method\": \"ping\"\n}", i))))
+	}
+
+	s := newTestServer(&config.Config{Framing: config.FramingContentLength})
+	var output bytes.Buffer
+	if err := s.serve(context.Background(), &input, &output); err != nil {
+		t.Fatalf("serve() error = %v", err)
+	}
+
+	reader := framing.NewReader(&output, config.FramingContentLength)
+	for i := 1; i <= 3; i++ {
+		message, err := reader.ReadMessage()
+		if err != nil {
+			t.Fatalf("Reading response %d: %v", i, err)
+		}
+		if id, _ := decodeResponse(t, message)["id"].(float64); int(id) != i {
+			t.Errorf("Response %d has id %v, want %d", i, id, i)
+		}
+	}
+}
+
+func TestToolErrorsAsContent(t *testing.T) {
+	request := mcp.CallToolRequest{}
+	request.Params.Name = "missing_tool"
+
+	t.Run("Disabled returns an error", func(t *testing.T) {
+		s := newTestServer(&config.Config{})
+		result, err := s.createToolHandler("missing_tool")(context.Background(), request)
+		if err == nil {
+			t.Fatalf("Expected an error, got result %+v", result)
+		}
+	})
+
+	t.Run("Enabled returns an isError result", func(t *testing.T) {
+		s := newTestServer(&config.Config{ToolErrorsAsContent: true})
+		result, err := s.createToolHandler("missing_tool")(context.Background(), request)
+		if err != nil {
+			t.Fatalf("Expected no error, got %v", err)
+		}
+		if result == nil || !result.IsError {
+			t.Fatalf("Expected an isError result, got %+v", result)
+		}
+		if len(result.Content) != 1 {
+			t.Fatalf("Expected one content block, got %d", len(result.Content))
+		}
+		text, ok := result.Content[0].(mcp.TextContent)
+		if !ok || !strings.Contains(text.Text, "missing_tool") {
+			t.Errorf("Expected text content describing the failure, got %+v", result.Content[0])
+		}
+	})
+}
+
+func TestSetLevel(t *testing.T) {
+	defer logger.SetLevel(logger.GetLevel())
+
+	s := newTestServer(&config.Config{PropagateLogLevel: true})
+
+	resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":7,"method":"logging/setLevel","params":{"level":"debug"}}`)))
+	if _, ok := resp["result"]; !ok {
+		t.Fatalf("Expected a result, got %v", resp)
+	}
+	if got := logger.GetLevel(); got != config.LogLevelDebug {
+		t.Errorf("Log level = %v, want %v", got, config.LogLevelDebug)
+	}
+
+	resp = decodeResponse(t, s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":8,"method":"logging/setLevel","params":{"level":"verbose"}}`)))
+	rpcErr, ok := resp["error"].(map[string]interface{})
+	if !ok {
+		t.Fatalf("Expected an error for an unknown level, got %v", resp)
+	}
+	if code, _ := rpcErr["code"].(float64); int(code) != mcp.INVALID_PARAMS {
+		t.Errorf("Error code = %v, want %d", rpcErr["code"], mcp.INVALID_PARAMS)
+	}
+}
+
+func TestProtocolVersionNegotiation(t *testing.T) {
+	tests := []struct {
+		name      string
+		requested string
+		want      string
+	}{
+		{name: "supported version is echoed", requested: mcp.LATEST_PROTOCOL_VERSION, want: mcp.LATEST_PROTOCOL_VERSION},
+		{name: "unknown version gets the newest supported", requested: "1999-01-01", want: aggregator.SupportedProtocolVersions[0]},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			s := newTestServer(nil)
+			request := fmt.Sprintf(`{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":%q,"capabilities":{},"clientInfo":{"name":"test","version":"1.0.0"}}}`, tt.requested)
+
+			resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(request)))
+			result, ok := resp["result"].(map[string]interface{})
+			if !ok {
+				t.Fatalf("Expected a result, got %v", resp)
+			}
+			if got := result["protocolVersion"]; got != tt.want {
+				t.Errorf("protocolVersion = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestInitializeCapabilities(t *testing.T) {
+	s := newTestServer(nil)
+	request := `{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"1.0.0"}}}`
+
+	resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(request)))
+	result, _ := resp["result"].(map[string]interface{})
+	capabilities, ok := result["capabilities"].(map[string]interface{})
+	if !ok {
+		t.Fatalf("Expected capabilities in the result, got %v", resp)
+	}
+
+	// Without servers providing them, only the aggregator's own capabilities are offered
+	for _, name := range []string{"tools", "logging"} {
+		if _, exists := capabilities[name]; !exists {
+			t.Errorf("Capability %s is missing from %v", name, capabilities)
+		}
+	}
+	for _, name := range []string{"resources", "prompts"} {
+		if _, exists := capabilities[name]; exists {
+			t.Errorf("Capability %s is offered without a server providing it", name)
+		}
+	}
+}
+
+func TestStatusTool(t *testing.T) {
+	s := newTestServer(nil)
+	if err := s.RegisterTools(); err != nil {
+		t.Fatalf("RegisterTools() error = %v", err)
+	}
+
+	resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"combine_mcp_status","arguments":{}}}`)))
+	result, ok := resp["result"].(map[string]interface{})
+	if !ok {
+		t.Fatalf("Expected a result, got %v", resp)
+	}
+	content, _ := result["content"].([]interface{})
+	if len(content) != 1 {
+		t.Fatalf("Got %d content blocks, want 1", len(content))
+	}
+	text, _ := content[0].(map[string]interface{})["text"].(string)
+	var statuses []aggregator.ServerStatus
+	if err := json.Unmarshal([]byte(text), &statuses); err != nil {
+		t.Errorf("Status text %q isn't a JSON list of server statuses: %v", text, err)
+	}
+}
+
+func TestBuiltinToolsDisabled(t *testing.T) {
+	exposed := false
+	s := newTestServer(&config.Config{ExposeBuiltinTools: &exposed})
+	if err := s.RegisterTools(); err != nil {
+		t.Fatalf("RegisterTools() error = %v", err)
+	}
+
+	resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":1,"method":"tools/list"}`)))
+	result, _ := resp["result"].(map[string]interface{})
+	if tools, _ := result["tools"].([]interface{}); len(tools) != 0 {
+		t.Errorf("tools/list = %v, want no tools", tools)
+	}
+
+	resp = decodeResponse(t, s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"combine_mcp_status","arguments":{}}}`)))
+	if _, failed := resp["error"]; !failed {
+		t.Errorf("Calling a disabled built-in tool gave %v, want an error", resp)
+	}
+}
+
+func TestReloadTool(t *testing.T) {
+	call := []byte(`{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"combine_mcp_reload","arguments":{}}}`)
+
+	// Reloading is privileged, so the tool is only there when the config asks for it
+	s := newTestServer(nil)
+	if err := s.RegisterTools(); err != nil {
+		t.Fatalf("RegisterTools() error = %v", err)
+	}
+	if _, failed := decodeResponse(t, s.handleMessage(context.Background(), call))["error"]; !failed {
+		t.Errorf("The reload tool can be called without exposeReloadTool")
+	}
+
+	s = newTestServer(&config.Config{ExposeReloadTool: true})
+	defer s.aggregator.Close()
+	s.SetConfigLoader(func() (*config.Config, error) {
+		return &config.Config{Servers: []config.ServerConfig{{
+			Name:    "helper",
+			Command: os.Args[0],
+			Args:    []string{"-test.run=TestHelperProcess", "--"},
+			Env:     map[string]string{"GO_WANT_HELPER_PROCESS": "1"},
+		}}}, nil
+	})
+	if err := s.RegisterTools(); err != nil {
+		t.Fatalf("RegisterTools() error = %v", err)
+	}
+
+	resp := decodeResponse(t, s.handleMessage(context.Background(), call))
+	result, _ := resp["result"].(map[string]interface{})
+	content, _ := result["content"].([]interface{})
+	if len(content) != 1 {
+		t.Fatalf("Unexpected response %v, want a single content block", resp)
+	}
+	text, _ := content[0].(map[string]interface{})["text"].(string)
+	var summary aggregator.ReloadSummary
+	if err := json.Unmarshal([]byte(text), &summary); err != nil || len(summary.Added) != 1 || summary.Added[0] != "helper" {
+		t.Errorf("Reload summary = %q, want the helper server added", text)
+	}
+}
+
+func TestMetricsTool(t *testing.T) {
+	s := newTestServer(nil)
+	if err := s.RegisterTools(); err != nil {
+		t.Fatalf("RegisterTools() error = %v", err)
+	}
+
+	resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"combine_mcp_metrics","arguments":{}}}`)))
+	result, ok := resp["result"].(map[string]interface{})
+	if !ok {
+		t.Fatalf("Expected a result, got %v", resp)
+	}
+	content, _ := result["content"].([]interface{})
+	if len(content) != 1 {
+		t.Fatalf("Got %d content blocks, want 1", len(content))
+	}
+	text, _ := content[0].(map[string]interface{})["text"].(string)
+	var metrics []aggregator.ServerMetrics
+	if err := json.Unmarshal([]byte(text), &metrics); err != nil {
+		t.Errorf("Metrics text %q isn't a JSON list of server metrics: %v", text, err)
+	}
+}
+
+func TestNotificationsGetNoResponse(t *testing.T) {
+	defer logger.SetLevel(logger.GetLevel())
+
+	s := newTestServer(nil)
+	notifications := []string{
+		`{"jsonrpc":"2.0","method":"notifications/initialized"}`,
+		`{"jsonrpc":"2.0","method":"$/exit"}`,
+		`{"jsonrpc":"2.0","method":"tools/list"}`,
+		`{"jsonrpc":"2.0","method":"logging/setLevel","params":{"level":"debug"}}`,
+	}
+	for _, notification := range notifications {
+		if response := s.handleMessage(context.Background(), []byte(notification)); response != nil {
+			t.Errorf("handleMessage(%s) = %s, want no response", notification, response)
+		}
+	}
+	if got := logger.GetLevel(); got != config.LogLevelDebug {
+		t.Errorf("Log level = %v, want the level set by the notification", got)
+	}
+
+	// The same methods sent as requests are answered
+	if response := s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":1,"method":"$/exit"}`)); response == nil {
+		t.Errorf("handleMessage() gave no response to a request")
+	}
+}
+
+func TestInvalidJSONRPCVersion(t *testing.T) {
+	s := newTestServer(nil)
+	tests := []struct {
+		name    string
+		message string
+		wantID  interface{}
+	}{
+		{name: "version 1.0", message: `{"jsonrpc":"1.0","id":1,"method":"tools/list"}`, wantID: float64(1)},
+		{name: "missing version", message: `{"id":"a","method":"tools/list"}`, wantID: "a"},
+		{name: "notification with version 1.0", message: `{"jsonrpc":"1.0","method":"notifications/initialized"}`, wantID: nil},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(tt.message)))
+			rpcErr, ok := resp["error"].(map[string]interface{})
+			if !ok {
+				t.Fatalf("Expected an error, got %v", resp)
+			}
+			if code, _ := rpcErr["code"].(float64); int(code) != mcp.INVALID_REQUEST {
+				t.Errorf("Error code = %v, want %d", rpcErr["code"], mcp.INVALID_REQUEST)
+			}
+			if resp["id"] != tt.wantID {
+				t.Errorf("Response id = %v, want %v", resp["id"], tt.wantID)
+			}
+		})
+	}
+}
+
+func TestFlattenResult(t *testing.T) {
+	result := &mcp.CallToolResult{
+		Content: []mcp.Content{
+			mcp.TextContent{Type: "text", Text: "first"},
+			mcp.ImageContent{Type: "image", Data: "aGVsbG8=", MIMEType: "image/png"},
+			mcp.TextContent{Type: "text", Text: "second"},
+		},
+		IsError: true,
+	}
+
+	want := "first\n" + `{"type":"image","data":"aGVsbG8=","mimeType":"image/png"}` + "\nsecond"
+	for i := 0; i < 3; i++ {
+		flattened := flattenResult(result)
+		if len(flattened.Content) != 1 {
+			t.Fatalf("Got %d content blocks, want 1", len(flattened.Content))
+		}
+		text, ok := flattened.Content[0].(mcp.TextContent)
+		if !ok {
+			t.Fatalf("Content[0] is %T, want mcp.TextContent", flattened.Content[0])
+		}
+		if text.Text != want {
+			t.Errorf("Flattened text = %q, want %q", text.Text, want)
+		}
+		if !flattened.IsError {
+			t.Errorf("Flattened result lost isError")
+		}
+	}
+}
+
+func TestLegacyResultsRequestFlag(t *testing.T) {
+	flagged := withLegacyResults(context.Background(), map[string]interface{}{
+		"params": map[string]interface{}{"_meta": map[string]interface{}{"legacyResults": true}},
+	})
+	if !wantsLegacyResults(flagged) {
+		t.Errorf("Request with _meta.legacyResults was not flagged")
+	}
+
+	plain := withLegacyResults(context.Background(), map[string]interface{}{"params": map[string]interface{}{}})
+	if wantsLegacyResults(plain) {
+		t.Errorf("Request without _meta.legacyResults was flagged")
+	}
+}
+
+func TestToolCallErrorCodes(t *testing.T) {
+	tests := []struct {
+		name string
+		err  error
+		want int
+	}{
+		{name: "Unknown tool", err: &aggregator.ToolNotFoundError{Tool: "missing"}, want: mcp.INVALID_PARAMS},
+		{name: "Server down", err: fmt.Errorf("call failed: %w", &aggregator.ServerError{Server: "github", Tool: "github_search", Err: errors.New("crashed")}), want: serverUnavailableCode},
+		{name: "Tool failure", err: errors.New("bad input"), want: mcp.INTERNAL_ERROR},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := toolCallErrorCode(tt.err); got != tt.want {
+				t.Errorf("toolCallErrorCode() = %d, want %d", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestUnknownToolError(t *testing.T) {
+	s := newTestServer(nil)
+	if err := s.RegisterTools(); err != nil {
+		t.Fatalf("RegisterTools() error = %v", err)
+	}
+
+	resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"missing_tool","arguments":{}}}`)))
+	rpcErr, ok := resp["error"].(map[string]interface{})
+	if !ok {
+		t.Fatalf("Expected an error response, got %v", resp)
+	}
+	if code, _ := rpcErr["code"].(float64); int(code) != mcp.INVALID_PARAMS {
+		t.Errorf("Error code = %v, want %d", rpcErr["code"], mcp.INVALID_PARAMS)
+	}
+	if message, _ := rpcErr["message"].(string); !strings.Contains(message, "no server lists it") {
+		t.Errorf("Error message = %q, want it to say no server lists the tool", message)
+	}
+	if data, _ := rpcErr["data"].(map[string]interface{}); data["kind"] != aggregator.ErrorKindToolNotFound {
+		t.Errorf("Error data = %v, want kind %s", rpcErr["data"], aggregator.ErrorKindToolNotFound)
+	}
+}
+
+func TestToolOrigins(t *testing.T) {
+	response := []byte(`{"jsonrpc":"2.0","id":1,"result":{"tools":[{"name":"github_search","inputSchema":{"type":"object"}},{"name":"combine_mcp_status","inputSchema":{"type":"object"}}]}}`)
+
+	resp := decodeResponse(t, withToolOrigins(response, map[string]string{"github_search": "github"}, map[string]bool{"github_search": true}))
+	tools, _ := resp["result"].(map[string]interface{})["tools"].([]interface{})
+	if len(tools) != 2 {
+		t.Fatalf("Got %d tools, want 2", len(tools))
+	}
+	meta, _ := tools[0].(map[string]interface{})["_meta"].(map[string]interface{})
+	if meta["server"] != "github" || meta["stale"] != true {
+		t.Errorf("github_search _meta = %v, want server github and stale", meta)
+	}
+	if _, exists := tools[1].(map[string]interface{})["_meta"]; exists {
+		t.Errorf("Built-in tool got _meta %v, want none", tools[1])
+	}
+}
+
+func TestTaggedToolsOnly(t *testing.T) {
+	response := []byte(`{"jsonrpc":"2.0","id":1,"result":{"tools":[{"name":"github_search","inputSchema":{"type":"object"}},{"name":"slack_post","inputSchema":{"type":"object"}},{"name":"combine_mcp_status","inputSchema":{"type":"object"}}]}}`)
+	origins := map[string]string{"github_search": "github", "slack_post": "slack"}
+
+	resp := decodeResponse(t, withTaggedToolsOnly(response, origins, map[string]bool{"github_search": true}))
+	tools, _ := resp["result"].(map[string]interface{})["tools"].([]interface{})
+	var names []string
+	for _, tool := range tools {
+		names = append(names, tool.(map[string]interface{})["name"].(string))
+	}
+	if strings.Join(names, ",") != "github_search,combine_mcp_status" {
+		t.Errorf("tools = %v, want the tagged server's tool and the built-in one", names)
+	}
+}
+
+func TestInvalidParams(t *testing.T) {
+	tests := []struct {
+		name        string
+		message     string
+		wantMessage string
+	}{
+		{
+			name:        "Arguments not an object",
+			message:     `{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"github_search","arguments":"query"}}`,
+			wantMessage: "invalid tools/call params: field arguments must be object, got string",
+		},
+		{
+			name:        "Missing tool name",
+			message:     `{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"arguments":{}}}`,
+			wantMessage: "invalid tools/call params: field name is required",
+		},
+		{
+			name:        "Params not an object",
+			message:     `{"jsonrpc":"2.0","id":1,"method":"tools/call","params":["github_search"]}`,
+			wantMessage: "invalid tools/call params: params must be object, got array",
+		},
+		{
+			name:        "Tags not a list",
+			message:     `{"jsonrpc":"2.0","id":1,"method":"tools/list","params":{"tags":"code"}}`,
+			wantMessage: "invalid tools/list params: field tags must be array, got string",
+		},
+		{
+			name:        "Resource uri not a string",
+			message:     `{"jsonrpc":"2.0","id":1,"method":"resources/read","params":{"uri":42}}`,
+			wantMessage: "invalid resources/read params: field uri must be string, got number",
+		},
+		{
+			name:        "Missing prompt name",
+			message:     `{"jsonrpc":"2.0","id":1,"method":"prompts/get"}`,
+			wantMessage: "invalid prompts/get params: field name is required",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			s := newTestServer(nil)
+			resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(tt.message)))
+			rpcErr, ok := resp["error"].(map[string]interface{})
+			if !ok {
+				t.Fatalf("Expected an error response, got %v", resp)
+			}
+			if code, _ := rpcErr["code"].(float64); int(code) != mcp.INVALID_PARAMS {
+				t.Errorf("Error code = %v, want %d", rpcErr["code"], mcp.INVALID_PARAMS)
+			}
+			if message, _ := rpcErr["message"].(string); message != tt.wantMessage {
+				t.Errorf("Error message = %q, want %q", message, tt.wantMessage)
+			}
+		})
+	}
+}
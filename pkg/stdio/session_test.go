@@ -0,0 +1,322 @@
+package stdio
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"sync"
+	"testing"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/aggregator"
+	"github.com/nazar256/combine-mcp/pkg/config"
+)
+
+// TestHelperProcess isn't a real test, it's the echo server started by TestStdioSession
+func TestHelperProcess(t *testing.T) {
+	if os.Getenv("GO_WANT_HELPER_PROCESS") != "1" {
+		return
+	}
+	runEchoServer()
+	os.Exit(0)
+}
+
+// runEchoServer answers MCP requests on stdin until it closes, providing a single echo tool, which answers
+// after delayMs milliseconds while other requests are answered
+func runEchoServer() {
+	encoder := json.NewEncoder(os.Stdout)
+	var encoderMu sync.Mutex
+	respond := func(id json.RawMessage, result interface{}) {
+		encoderMu.Lock()
+		defer encoderMu.Unlock()
+		encoder.Encode(map[string]interface{}{"jsonrpc": "2.0", "id": id, "result": result})
+	}
+	scanner := bufio.NewScanner(os.Stdin)
+	for scanner.Scan() {
+		var request struct {
+			ID     json.RawMessage `json:"id"`
+			Method string          `json:"method"`
+			Params struct {
+				Arguments map[string]interface{} `json:"arguments"`
+			} `json:"params"`
+		}
+		if err := json.Unmarshal(scanner.Bytes(), &request); err != nil || len(request.ID) == 0 {
+			continue // Notifications need no answer
+		}
+
+		var result interface{}
+		switch request.Method {
+		case "initialize":
+			result = map[string]interface{}{
+				"protocolVersion": "2024-11-05",
+				"capabilities":    map[string]interface{}{"tools": map[string]interface{}{}},
+				"serverInfo":      map[string]interface{}{"name": "echo", "version": "1.0.0"},
+			}
+		case "tools/list":
+			result = map[string]interface{}{"tools": []interface{}{
+				map[string]interface{}{
+					"name":        "echo",
+					"description": "Echo the text argument",
+					"inputSchema": map[string]interface{}{
+						"type":       "object",
+						"properties": map[string]interface{}{"text": map[string]interface{}{"type": "string"}},
+					},
+				},
+			}}
+		case "tools/call":
+			result = map[string]interface{}{
+				"content": []interface{}{map[string]interface{}{"type": "text", "text": request.Params.Arguments["text"]}},
+			}
+			if delay, _ := request.Params.Arguments["delayMs"].(float64); delay > 0 {
+				go func(id json.RawMessage) {
+					time.Sleep(time.Duration(delay) * time.Millisecond)
+					respond(id, result)
+				}(request.ID)
+				continue
+			}
+		default:
+			result = map[string]interface{}{}
+		}
+		respond(request.ID, result)
+	}
+}
+
+// TestStdioSession drives a whole client session through the stdio loop to a real server process
+func TestStdioSession(t *testing.T) {
+	agg := aggregator.NewMCPAggregator()
+	defer agg.Close()
+
+	cfg := &config.Config{Servers: []config.ServerConfig{{
+		Name:    "helper",
+		Command: os.Args[0],
+		Args:    []string{"-test.run=TestHelperProcess", "--"},
+		Env:     map[string]string{"GO_WANT_HELPER_PROCESS": "1"},
+	}}}
+	if err := agg.Initialize(context.Background(), cfg); err != nil {
+		t.Fatalf("Initialize() error = %v", err)
+	}
+	s := NewAggregatorServer("test-aggregator", "1.0.0", agg, cfg)
+	if err := s.RegisterTools(); err != nil {
+		t.Fatalf("RegisterTools() error = %v", err)
+	}
+
+	inReader, inWriter := io.Pipe()
+	outReader, outWriter := io.Pipe()
+	served := make(chan error, 1)
+	go func() { served <- s.serve(context.Background(), inReader, outWriter) }()
+
+	session := []struct {
+		request string
+		check   func(result map[string]interface{}) bool
+	}{
+		{
+			request: `{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"1.0.0"}}}`,
+			check: func(result map[string]interface{}) bool {
+				serverInfo, _ := result["serverInfo"].(map[string]interface{})
+				return result["protocolVersion"] == "2024-11-05" && serverInfo["name"] == "test-aggregator"
+			},
+		},
+		{
+			request: `{"jsonrpc":"2.0","id":2,"method":"tools/list"}`,
+			check: func(result map[string]interface{}) bool {
+				tools, _ := result["tools"].([]interface{})
+				for _, tool := range tools {
+					// The parameters are only seen by clients under the key the spec names
+					if tool, _ := tool.(map[string]interface{}); tool["name"] == "helper_echo" {
+						schema, _ := tool["inputSchema"].(map[string]interface{})
+						properties, _ := schema["properties"].(map[string]interface{})
+						return properties["text"] != nil
+					}
+				}
+				return false
+			},
+		},
+		{
+			request: `{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"helper_echo","arguments":{"text":"hello"}}}`,
+			check: func(result map[string]interface{}) bool {
+				content, _ := result["content"].([]interface{})
+				if len(content) != 1 {
+					return false
+				}
+				text, _ := content[0].(map[string]interface{})
+				return text["type"] == "text" && text["text"] == "hello" && result["isError"] != true
+			},
+		},
+	}
+
+	output := bufio.NewReader(outReader)
+	for i, step := range session {
+		if _, err := inWriter.Write([]byte(step.request + "\n")); err != nil {
+			t.Fatalf("Write() error = %v", err)
+		}
+		if i == 0 {
+			if _, err := inWriter.Write([]byte(`{"jsonrpc":"2.0","method":"notifications/initialized"}` + "\n")); err != nil {
+				t.Fatalf("Write() error = %v", err)
+			}
+		}
+
+		line, err := output.ReadBytes('\n')
+		if err != nil {
+			t.Fatalf("ReadBytes() error = %v", err)
+		}
+		resp := decodeResponse(t, line)
+		if id, _ := resp["id"].(float64); int(id) != i+1 {
+			t.Fatalf("Response %s has id %v, want %d", line, resp["id"], i+1)
+		}
+		result, _ := resp["result"].(map[string]interface{})
+		if !step.check(result) {
+			t.Errorf("Unexpected response to %s: %s", step.request, line)
+		}
+	}
+
+	inWriter.Close()
+	if err := <-served; err != nil {
+		t.Errorf("serve() error = %v", err)
+	}
+}
+
+// TestToolPrecedence routes a tool under the status tool's name and checks which of the two toolPrecedence exposes,
+// and that the listing of the tool, its _meta and its tags, matches the tool that is called
+func TestToolPrecedence(t *testing.T) {
+	tests := []struct {
+		precedence string
+		wantRouted bool
+	}{
+		{precedence: "", wantRouted: true},
+		{precedence: config.ToolPrecedenceRoutedFirst, wantRouted: true},
+		{precedence: config.ToolPrecedenceBuiltinFirst, wantRouted: false},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.precedence, func(t *testing.T) {
+			agg := aggregator.NewMCPAggregator()
+			defer agg.Close()
+
+			cfg := &config.Config{
+				Servers: []config.ServerConfig{{
+					Name:    "helper",
+					Command: os.Args[0],
+					Args:    []string{"-test.run=TestHelperProcess", "--"},
+					Env:     map[string]string{"GO_WANT_HELPER_PROCESS": "1"},
+				}},
+				Routes:         map[string]config.RouteConfig{StatusToolName: {Server: "helper", Tool: "echo"}},
+				ToolPrecedence: tt.precedence,
+			}
+			if err := agg.Initialize(context.Background(), cfg); err != nil {
+				t.Fatalf("Initialize() error = %v", err)
+			}
+			s := NewAggregatorServer("test-aggregator", "1.0.0", agg, cfg)
+			if err := s.RegisterTools(); err != nil {
+				t.Fatalf("RegisterTools() error = %v", err)
+			}
+
+			// listStatus returns how often a tools/list request lists the status tool's name, and the server it names
+			listStatus := func(request string) (int, interface{}) {
+				resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(request)))
+				result, _ := resp["result"].(map[string]interface{})
+				tools, _ := result["tools"].([]interface{})
+				listed := 0
+				var server interface{}
+				for _, tool := range tools {
+					if tool, _ := tool.(map[string]interface{}); tool["name"] == StatusToolName {
+						listed++
+						meta, _ := tool["_meta"].(map[string]interface{})
+						server = meta["server"]
+					}
+				}
+				return listed, server
+			}
+
+			var wantServer interface{}
+			if tt.wantRouted {
+				wantServer = "helper"
+			}
+			listed, server := listStatus(`{"jsonrpc":"2.0","id":1,"method":"tools/list"}`)
+			if listed != 1 || server != wantServer {
+				t.Errorf("%s is listed %d times with server %v, want once with server %v", StatusToolName, listed, server, wantServer)
+			}
+
+			// A tags filter drops the routed tool of the untagged server, but never the built-in tool
+			wantListed := 1
+			if tt.wantRouted {
+				wantListed = 0
+			}
+			if listed, _ := listStatus(`{"jsonrpc":"2.0","id":3,"method":"tools/list","params":{"tags":["work"]}}`); listed != wantListed {
+				t.Errorf("%s is listed %d times for a tags filter, want %d", StatusToolName, listed, wantListed)
+			}
+
+			resp := decodeResponse(t, s.handleMessage(context.Background(), []byte(`{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"combine_mcp_status","arguments":{"text":"routed"}}}`)))
+			result, _ := resp["result"].(map[string]interface{})
+			content, _ := result["content"].([]interface{})
+			if len(content) != 1 {
+				t.Fatalf("Got %d content blocks in %v, want 1", len(content), resp)
+			}
+			text, _ := content[0].(map[string]interface{})["text"].(string)
+			if routed := text == "routed"; routed != tt.wantRouted {
+				t.Errorf("Call answered with %q, routed = %v, want %v", text, routed, tt.wantRouted)
+			}
+		})
+	}
+}
+
+// TestToolCallResponseOrder has the first of two tool calls finish last and checks the responses keep the order of
+// the calls with pipelineOutput, and come in the order the calls finish without it
+func TestToolCallResponseOrder(t *testing.T) {
+	for _, pipelined := range []bool{false, true} {
+		t.Run(fmt.Sprintf("pipelined=%v", pipelined), func(t *testing.T) {
+			agg := aggregator.NewMCPAggregator()
+			defer agg.Close()
+
+			cfg := &config.Config{
+				Servers: []config.ServerConfig{{
+					Name:    "helper",
+					Command: os.Args[0],
+					Args:    []string{"-test.run=TestHelperProcess", "--"},
+					Env:     map[string]string{"GO_WANT_HELPER_PROCESS": "1"},
+				}},
+				PipelineOutput: pipelined,
+			}
+			if err := agg.Initialize(context.Background(), cfg); err != nil {
+				t.Fatalf("Initialize() error = %v", err)
+			}
+			s := NewAggregatorServer("test-aggregator", "1.0.0", agg, cfg)
+			if err := s.RegisterTools(); err != nil {
+				t.Fatalf("RegisterTools() error = %v", err)
+			}
+
+			inReader, inWriter := io.Pipe()
+			outReader, outWriter := io.Pipe()
+			served := make(chan error, 1)
+			go func() { served <- s.serve(context.Background(), inReader, outWriter) }()
+
+			requests := `{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"helper_echo","arguments":{"text":"slow","delayMs":300}}}` + "\n" +
+				`{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"helper_echo","arguments":{"text":"fast"}}}` + "\n"
+			if _, err := inWriter.Write([]byte(requests)); err != nil {
+				t.Fatalf("Write() error = %v", err)
+			}
+
+			want := []int{2, 1}
+			if pipelined {
+				want = []int{1, 2}
+			}
+			output := bufio.NewReader(outReader)
+			for _, wantID := range want {
+				line, err := output.ReadBytes('\n')
+				if err != nil {
+					t.Fatalf("ReadBytes() error = %v", err)
+				}
+				if id, _ := decodeResponse(t, line)["id"].(float64); int(id) != wantID {
+					t.Errorf("Got response %s, want the response to call %d", line, wantID)
+				}
+			}
+
+			inWriter.Close()
+			if err := <-served; err != nil {
+				t.Errorf("serve() error = %v", err)
+			}
+		})
+	}
+}
@@ -0,0 +1,261 @@
+package stdio
+
+import (
+	"context"
+	"crypto/rand"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/http"
+	"sync"
+	"time"
+
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+const (
+	// ssePath is where clients open their event stream
+	ssePath = "/sse"
+	// sseMessagePath is where clients post their messages, naming their session in the sessionId query parameter
+	sseMessagePath = "/message"
+	// sseQueueSize is how many messages may wait to be sent on a client's event stream
+	sseQueueSize = 64
+	// maxSSEMessageBytes is the largest message a client may post
+	maxSSEMessageBytes = 16 << 20
+)
+
+// messageWriter writes messages to a client
+type messageWriter interface {
+	Write(message []byte)
+}
+
+// sseSession is the event stream of a client connected over HTTP, on which it gets every message
+// the aggregator sends it
+type sseSession struct {
+	id       string
+	messages chan []byte
+	done     chan struct{}
+	ctx      context.Context // Cancelled once the client disconnects, along with its tool calls
+}
+
+// Write sends a message on the client's event stream; messages sent after it disconnected are dropped
+func (session *sseSession) Write(message []byte) {
+	select {
+	case session.messages <- message:
+	case <-session.done:
+	}
+}
+
+// sseSessions are the clients connected over HTTP, in the order they connected
+type sseSessions struct {
+	mu       sync.Mutex
+	sessions []*sseSession
+}
+
+// Write sends a notification to every connected client
+func (sessions *sseSessions) Write(message []byte) {
+	sessions.mu.Lock()
+	connected := append([]*sseSession(nil), sessions.sessions...)
+	sessions.mu.Unlock()
+	for _, session := range connected {
+		session.Write(message)
+	}
+}
+
+// add registers a newly connected client
+func (sessions *sseSessions) add(session *sseSession) {
+	sessions.mu.Lock()
+	defer sessions.mu.Unlock()
+	sessions.sessions = append(sessions.sessions, session)
+}
+
+// remove forgets a disconnected client and reports how many remain connected
+func (sessions *sseSessions) remove(session *sseSession) int {
+	sessions.mu.Lock()
+	defer sessions.mu.Unlock()
+	for i, connected := range sessions.sessions {
+		if connected == session {
+			sessions.sessions = append(sessions.sessions[:i:i], sessions.sessions[i+1:]...)
+			break
+		}
+	}
+	return len(sessions.sessions)
+}
+
+// find returns the connected client with the session id, nil if there is none
+func (sessions *sseSessions) find(id string) *sseSession {
+	sessions.mu.Lock()
+	defer sessions.mu.Unlock()
+	for _, session := range sessions.sessions {
+		if session.id == id {
+			return session
+		}
+	}
+	return nil
+}
+
+// latest returns the client that connected last, which is asked when a server needs something of
+// the client such as sampling, nil while none is connected
+func (sessions *sseSessions) latest() *sseSession {
+	sessions.mu.Lock()
+	defer sessions.mu.Unlock()
+	if len(sessions.sessions) == 0 {
+		return nil
+	}
+	return sessions.sessions[len(sessions.sessions)-1]
+}
+
+// clientWriter returns where a message to the client is written: the stdio client, or with clients
+// connected over HTTP all of them for notifications and the latest one for requests. It's nil while
+// no client is connected.
+func (s *AggregatorServer) clientWriter(request bool) messageWriter {
+	s.writerMu.Lock()
+	defer s.writerMu.Unlock()
+	switch {
+	case s.writer != nil:
+		return s.writer
+	case s.sessions == nil:
+		return nil
+	case request:
+		if session := s.sessions.latest(); session != nil {
+			return session
+		}
+		return nil
+	default:
+		return s.sessions
+	}
+}
+
+// ServeSSE serves MCP over HTTP with server-sent events on addr until ctx is done, to any number of
+// clients: each opens an event stream at /sse, is told where to post its messages and gets the
+// responses on its stream
+func (s *AggregatorServer) ServeSSE(ctx context.Context, addr string) error {
+	listener, err := net.Listen("tcp", addr)
+	if err != nil {
+		return fmt.Errorf("failed to listen on %s: %w", addr, err)
+	}
+	return s.serveSSE(ctx, listener)
+}
+
+// serveSSE serves MCP over HTTP with server-sent events on the listener until ctx is done
+func (s *AggregatorServer) serveSSE(ctx context.Context, listener net.Listener) error {
+	logger.Info("Serving MCP over HTTP on %s", listener.Addr())
+
+	s.writerMu.Lock()
+	s.sessions = &sseSessions{}
+	s.writerMu.Unlock()
+	s.acceptResponses()
+	defer s.failPendingRequests()
+
+	mux := http.NewServeMux()
+	mux.HandleFunc(ssePath, func(w http.ResponseWriter, r *http.Request) { s.handleEventStream(ctx, w, r) })
+	mux.HandleFunc(sseMessagePath, s.handlePostedMessage)
+	httpServer := &http.Server{Handler: mux, ReadHeaderTimeout: 10 * time.Second}
+
+	go func() {
+		<-ctx.Done()
+		shutdownCtx, cancel := context.WithTimeout(context.Background(), 5*time.Second)
+		defer cancel()
+		httpServer.Shutdown(shutdownCtx)
+	}()
+
+	if err := httpServer.Serve(listener); err != nil && !errors.Is(err, http.ErrServerClosed) {
+		return err
+	}
+	return nil
+}
+
+// handleEventStream streams the messages for a newly connected client until it disconnects
+func (s *AggregatorServer) handleEventStream(ctx context.Context, w http.ResponseWriter, r *http.Request) {
+	if r.Method != http.MethodGet {
+		http.Error(w, "method not allowed", http.StatusMethodNotAllowed)
+		return
+	}
+	flusher, ok := w.(http.Flusher)
+	if !ok {
+		http.Error(w, "streaming not supported", http.StatusInternalServerError)
+		return
+	}
+
+	sessionCtx, cancel := context.WithCancel(ctx)
+	session := &sseSession{id: newSessionID(), messages: make(chan []byte, sseQueueSize), done: make(chan struct{}), ctx: sessionCtx}
+	s.sessions.add(session)
+	logger.Info("Client connected over HTTP with session %s", session.id)
+	defer func() {
+		cancel()
+		close(session.done)
+		if s.sessions.remove(session) == 0 {
+			// Nobody is left to answer what servers asked of the client
+			s.failPendingRequests()
+			s.acceptResponses()
+		}
+		logger.Info("Client with session %s disconnected", session.id)
+	}()
+
+	w.Header().Set("Content-Type", "text/event-stream")
+	w.Header().Set("Cache-Control", "no-cache")
+	w.Header().Set("Connection", "keep-alive")
+	fmt.Fprintf(w, "event: endpoint\ndata: %s?sessionId=%s\n\n", sseMessagePath, session.id)
+	flusher.Flush()
+
+	for {
+		select {
+		case message := <-session.messages:
+			fmt.Fprintf(w, "event: message\ndata: %s\n\n", message)
+			flusher.Flush()
+		case <-r.Context().Done():
+			return
+		case <-ctx.Done():
+			return
+		}
+	}
+}
+
+// handlePostedMessage accepts a message of a connected client and answers it on the client's event stream
+func (s *AggregatorServer) handlePostedMessage(w http.ResponseWriter, r *http.Request) {
+	if r.Method != http.MethodPost {
+		http.Error(w, "method not allowed", http.StatusMethodNotAllowed)
+		return
+	}
+	session := s.sessions.find(r.URL.Query().Get("sessionId"))
+	if session == nil {
+		http.Error(w, "unknown session", http.StatusNotFound)
+		return
+	}
+	line, err := io.ReadAll(http.MaxBytesReader(w, r.Body, maxSSEMessageBytes))
+	if err != nil {
+		http.Error(w, fmt.Sprintf("failed to read message: %v", err), http.StatusBadRequest)
+		return
+	}
+	w.WriteHeader(http.StatusAccepted)
+
+	var msg clientMessage
+	if !isBatch(line) && json.Unmarshal(line, &msg) == nil && msg.isResponse() {
+		logger.LogRPC("IN", line)
+		s.handleResponse(&msg)
+		return
+	}
+
+	// The client may post its next message before this one is answered, as tool calls take a while
+	go func() {
+		var responseBytes []byte
+		if isBatch(line) {
+			responseBytes = s.handleBatch(session.ctx, line)
+		} else {
+			responseBytes = s.handleMessage(session.ctx, line)
+		}
+		if responseBytes != nil {
+			session.Write(responseBytes)
+		}
+	}()
+}
+
+// newSessionID returns a random id that tells apart the clients connected over HTTP
+func newSessionID() string {
+	id := make([]byte, 16)
+	rand.Read(id)
+	return hex.EncodeToString(id)
+}
@@ -0,0 +1,119 @@
+package stdio
+
+import (
+	"bufio"
+	"context"
+	"net"
+	"net/http"
+	"strings"
+	"testing"
+)
+
+// sseClient is a client connected to the aggregator over HTTP
+type sseClient struct {
+	events   *bufio.Reader
+	endpoint string
+	close    func()
+}
+
+// connectSSE opens an event stream and reads where the client is to post its messages
+func connectSSE(t *testing.T, baseURL string) *sseClient {
+	t.Helper()
+	response, err := http.Get(baseURL + ssePath)
+	if err != nil {
+		t.Fatalf("GET %s error = %v", ssePath, err)
+	}
+	client := &sseClient{events: bufio.NewReader(response.Body), close: func() { response.Body.Close() }}
+	event, data := client.next(t)
+	if event != "endpoint" || !strings.HasPrefix(data, sseMessagePath+"?sessionId=") {
+		t.Fatalf("first event = %s %q, want the endpoint to post messages to", event, data)
+	}
+	client.endpoint = baseURL + data
+	return client
+}
+
+// next reads the next event from the client's stream
+func (c *sseClient) next(t *testing.T) (event, data string) {
+	t.Helper()
+	for {
+		line, err := c.events.ReadString('\n')
+		if err != nil {
+			t.Fatalf("Failed to read event: %v", err)
+		}
+		line = strings.TrimSuffix(line, "\n")
+		switch {
+		case line == "":
+			return event, data
+		case strings.HasPrefix(line, "event: "):
+			event = strings.TrimPrefix(line, "event: ")
+		case strings.HasPrefix(line, "data: "):
+			data = strings.TrimPrefix(line, "data: ")
+		}
+	}
+}
+
+// post sends a message and checks that it was accepted
+func (c *sseClient) post(t *testing.T, message string) {
+	t.Helper()
+	response, err := http.Post(c.endpoint, "application/json", strings.NewReader(message))
+	if err != nil {
+		t.Fatalf("POST error = %v", err)
+	}
+	response.Body.Close()
+	if response.StatusCode != http.StatusAccepted {
+		t.Fatalf("POST status = %d, want %d", response.StatusCode, http.StatusAccepted)
+	}
+}
+
+func TestServeSSE(t *testing.T) {
+	s := newTestServer(nil)
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatalf("Listen() error = %v", err)
+	}
+	ctx, cancel := context.WithCancel(context.Background())
+	served := make(chan error, 1)
+	go func() { served <- s.serveSSE(ctx, listener) }()
+	defer func() {
+		cancel()
+		if err := <-served; err != nil {
+			t.Errorf("serveSSE() error = %v", err)
+		}
+	}()
+	baseURL := "http://" + listener.Addr().String()
+
+	first := connectSSE(t, baseURL)
+	defer first.close()
+	second := connectSSE(t, baseURL)
+	defer second.close()
+	if first.endpoint == second.endpoint {
+		t.Fatalf("both clients got endpoint %s, want a session each", first.endpoint)
+	}
+
+	// Each client gets the answers to its own requests
+	first.post(t, `{"jsonrpc":"2.0","id":1,"method":"ping"}`)
+	second.post(t, `{"jsonrpc":"2.0","id":"second","method":"ping"}`)
+	if event, data := first.next(t); event != "message" || !strings.Contains(data, `"id":1`) {
+		t.Errorf("first client got %s %s, want the answer to its ping", event, data)
+	}
+	if event, data := second.next(t); event != "message" || !strings.Contains(data, `"id":"second"`) {
+		t.Errorf("second client got %s %s, want the answer to its ping", event, data)
+	}
+
+	// Notifications go to every client
+	s.notify("notifications/tools/list_changed", nil)
+	for _, client := range []*sseClient{first, second} {
+		if _, data := client.next(t); !strings.Contains(data, "notifications/tools/list_changed") {
+			t.Errorf("client got %s, want the notification", data)
+		}
+	}
+
+	response, err := http.Post(baseURL+sseMessagePath+"?sessionId=unknown", "application/json", strings.NewReader(`{"jsonrpc":"2.0","id":1,"method":"ping"}`))
+	if err != nil {
+		t.Fatalf("POST error = %v", err)
+	}
+	response.Body.Close()
+	if response.StatusCode != http.StatusNotFound {
+		t.Errorf("POST with an unknown session status = %d, want %d", response.StatusCode, http.StatusNotFound)
+	}
+}
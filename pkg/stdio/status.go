@@ -0,0 +1,32 @@
+package stdio
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/mark3labs/mcp-go/server"
+)
+
+// StatusToolName is the name of the built-in tool reporting the state of every server
+const StatusToolName = "combine_mcp_status"
+
+// statusTool returns the built-in tool reporting the state of every server
+func (s *AggregatorServer) statusTool() server.ServerTool {
+	return server.ServerTool{
+		Tool: mcp.NewTool(StatusToolName,
+			mcp.WithDescription("Report the state of every MCP server combined by the aggregator: whether it is running and healthy, how many tools it provides, its process id and its last error"),
+		),
+		Handler: s.handleStatus,
+	}
+}
+
+// handleStatus answers a call of the status tool with the servers' states as JSON
+func (s *AggregatorServer) handleStatus(ctx context.Context, request mcp.CallToolRequest) (*mcp.CallToolResult, error) {
+	status, err := json.MarshalIndent(s.aggregator.Status(), "", "  ")
+	if err != nil {
+		return nil, fmt.Errorf("failed to encode server status: %w", err)
+	}
+	return mcp.NewToolResultText(string(status)), nil
+}
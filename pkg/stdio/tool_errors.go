@@ -0,0 +1,84 @@
+package stdio
+
+import (
+	"context"
+	"encoding/json"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/aggregator"
+)
+
+// serverUnavailableCode is the JSON-RPC error code of tool calls whose server crashed, isn't running or
+// can't be reached, taken from the range JSON-RPC leaves to implementations
+const serverUnavailableCode = -32001
+
+// toolCallOutcomeKey marks a context whose tools/call handler records the error it failed with
+type toolCallOutcomeKey struct{}
+
+// toolCallOutcome holds the error a tool handler failed with, as the MCP server only passes on its message
+type toolCallOutcome struct {
+	err error
+}
+
+// withToolCallOutcome returns a context in which the tool handler records its error in the returned outcome
+func withToolCallOutcome(ctx context.Context) (context.Context, *toolCallOutcome) {
+	outcome := &toolCallOutcome{}
+	return context.WithValue(ctx, toolCallOutcomeKey{}, outcome), outcome
+}
+
+// recordToolCallError remembers the error of a failed tool call in the outcome of ctx, if any
+func recordToolCallError(ctx context.Context, err error) {
+	if outcome, ok := ctx.Value(toolCallOutcomeKey{}).(*toolCallOutcome); ok {
+		outcome.err = err
+	}
+}
+
+// toolCallErrorCode picks the JSON-RPC error code for a failed tool call: invalid params for a tool that
+// isn't exposed, a server error for a tool whose server is down, an internal error otherwise
+func toolCallErrorCode(err error) int {
+	switch aggregator.ErrorKind(err) {
+	case aggregator.ErrorKindToolNotFound:
+		return mcp.INVALID_PARAMS
+	case aggregator.ErrorKindServerUnavailable:
+		return serverUnavailableCode
+	default:
+		return mcp.INTERNAL_ERROR
+	}
+}
+
+// toolCallErrorData describes a failed tool call in the error data: the kind of failure, one of the
+// aggregator's stable error kinds, and the server it failed on when it is known
+func toolCallErrorData(err error) map[string]interface{} {
+	data := map[string]interface{}{"kind": aggregator.ErrorKind(err)}
+	if serverName := aggregator.ErrorServer(err); serverName != "" {
+		data["server"] = serverName
+	}
+	return data
+}
+
+// toolCallErrorResponse encodes the response to a failed tool call with the code and data matching its error
+func toolCallErrorResponse(id json.RawMessage, err error) []byte {
+	return errorResponse(id, toolCallErrorCode(err), err.Error(), toolCallErrorData(err))
+}
+
+// isRegisteredTool reports whether the MCP server knows a tool of the given name
+func (s *AggregatorServer) isRegisteredTool(name string) bool {
+	s.toolsMu.RLock()
+	defer s.toolsMu.RUnlock()
+	return s.toolNames[name]
+}
+
+// callUnregisteredTool hands a call of a tool the MCP server doesn't know to the aggregator, which starts
+// lazy servers that may provide it or explains why no server does
+func (s *AggregatorServer) callUnregisteredTool(ctx context.Context, req map[string]interface{}, line []byte) []byte {
+	var request mcp.CallToolRequest
+	if err := json.Unmarshal(line, &request); err != nil {
+		return errorResponse(requestID(req), mcp.INVALID_PARAMS, "invalid tools/call params", nil)
+	}
+
+	result, err := s.createToolHandler(request.Params.Name)(ctx, request)
+	if err != nil {
+		return toolCallErrorResponse(requestID(req), err)
+	}
+	return resultResponse(requestID(req), result)
+}
@@ -0,0 +1,126 @@
+package stdio
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+
+	"github.com/mark3labs/mcp-go/mcp"
+	"github.com/nazar256/combine-mcp/pkg/aggregator"
+	"github.com/nazar256/combine-mcp/pkg/logger"
+)
+
+// errNoClient is returned for requests to the client while none is connected
+var errNoClient = errors.New("no client is connected")
+
+// clientMessage holds what tells apart the messages a client sends: requests, notifications and responses
+type clientMessage struct {
+	ID     json.RawMessage `json:"id"`
+	Method string          `json:"method"`
+	Result json.RawMessage `json:"result"`
+	Error  *clientError    `json:"error"`
+}
+
+// clientError is the error object of a client's response
+type clientError struct {
+	Code    int             `json:"code"`
+	Message string          `json:"message"`
+	Data    json.RawMessage `json:"data,omitempty"`
+}
+
+// isResponse reports whether the message answers a request the aggregator sent the client
+func (m *clientMessage) isResponse() bool {
+	return m.Method == "" && len(m.ID) > 0 && (m.Result != nil || m.Error != nil)
+}
+
+// request sends a server-initiated request to the client and waits for its response
+func (s *AggregatorServer) request(ctx context.Context, method string, params json.RawMessage) (json.RawMessage, error) {
+	writer := s.clientWriter(true)
+
+	id := s.nextRequestID.Add(1)
+	ch := make(chan *clientMessage, 1)
+	s.pendingMu.Lock()
+	if writer == nil || s.pending == nil {
+		s.pendingMu.Unlock()
+		return nil, errNoClient
+	}
+	s.pending[id] = ch
+	s.pendingMu.Unlock()
+
+	defer func() {
+		s.pendingMu.Lock()
+		delete(s.pending, id)
+		s.pendingMu.Unlock()
+	}()
+
+	request := map[string]interface{}{
+		"jsonrpc": mcp.JSONRPC_VERSION,
+		"id":      id,
+		"method":  method,
+	}
+	if params != nil {
+		request["params"] = params
+	}
+	message, err := json.Marshal(request)
+	if err != nil {
+		return nil, fmt.Errorf("failed to marshal %s request: %w", method, err)
+	}
+	logger.LogRPC("OUT", message)
+	writer.Write(message)
+
+	select {
+	case response, ok := <-ch:
+		if !ok {
+			return nil, errNoClient
+		}
+		if response.Error != nil {
+			return nil, &aggregator.ClientError{Code: response.Error.Code, Message: response.Error.Message, Data: response.Error.Data}
+		}
+		return response.Result, nil
+	case <-ctx.Done():
+		return nil, ctx.Err()
+	}
+}
+
+// handleResponse hands a client's response to the request waiting for it
+func (s *AggregatorServer) handleResponse(response *clientMessage) {
+	var id int64
+	if err := json.Unmarshal(response.ID, &id); err != nil {
+		logger.Debug("Ignoring response with unexpected id %s from the client", response.ID)
+		return
+	}
+
+	s.pendingMu.Lock()
+	ch, exists := s.pending[id]
+	delete(s.pending, id)
+	s.pendingMu.Unlock()
+
+	if !exists {
+		logger.Debug("Ignoring response to unknown request %d from the client", id)
+		return
+	}
+	ch <- response
+}
+
+// acceptResponses starts matching client responses to requests, until failPendingRequests
+func (s *AggregatorServer) acceptResponses() {
+	s.pendingMu.Lock()
+	defer s.pendingMu.Unlock()
+	s.pending = make(map[int64]chan *clientMessage)
+}
+
+// failPendingRequests fails every request still waiting once the client can no longer answer
+func (s *AggregatorServer) failPendingRequests() {
+	s.pendingMu.Lock()
+	defer s.pendingMu.Unlock()
+	for _, ch := range s.pending {
+		close(ch)
+	}
+	s.pending = nil
+}
+
+// relaySampling passes a server's sampling request on to the client
+func (s *AggregatorServer) relaySampling(ctx context.Context, params json.RawMessage) (json.RawMessage, error) {
+	return s.request(ctx, aggregator.SamplingMethod, params)
+}
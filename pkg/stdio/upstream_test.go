@@ -0,0 +1,83 @@
+package stdio
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"testing"
+
+	"github.com/nazar256/combine-mcp/pkg/aggregator"
+)
+
+func TestRelaySampling(t *testing.T) {
+	s := newTestServer(nil)
+	clientIn, serverOut := io.Pipe()
+	serverIn, clientOut := io.Pipe()
+	served := make(chan error, 1)
+	go func() { served <- s.serve(context.Background(), serverIn, serverOut) }()
+	client := bufio.NewReader(clientIn)
+
+	// readRequest reads the next message the aggregator sends the client
+	readRequest := func() map[string]interface{} {
+		t.Helper()
+		line, err := client.ReadBytes('\n')
+		if err != nil {
+			t.Fatalf("Failed to read from the aggregator: %v", err)
+		}
+		return decodeResponse(t, line)
+	}
+
+	type relayed struct {
+		result json.RawMessage
+		err    error
+	}
+	relay := func() <-chan relayed {
+		done := make(chan relayed, 1)
+		go func() {
+			result, err := s.relaySampling(context.Background(), json.RawMessage(`{"maxTokens":10}`))
+			done <- relayed{result, err}
+		}()
+		return done
+	}
+
+	// Once the ping is answered the aggregator is serving
+	fmt.Fprintln(clientOut, `{"jsonrpc":"2.0","id":"ping","method":"ping"}`)
+	readRequest()
+
+	done := relay()
+	request := readRequest()
+	if request["method"] != aggregator.SamplingMethod {
+		t.Fatalf("Client got %v, want a %s request", request, aggregator.SamplingMethod)
+	}
+	fmt.Fprintf(clientOut, `{"jsonrpc":"2.0","id":%v,"result":{"model":"test-model"}}`+"\n", request["id"])
+	if got := <-done; got.err != nil || string(got.result) != `{"model":"test-model"}` {
+		t.Errorf("relaySampling() = %s, %v, want the client's result", got.result, got.err)
+	}
+
+	// An error response of the client is passed back as is
+	done = relay()
+	request = readRequest()
+	fmt.Fprintf(clientOut, `{"jsonrpc":"2.0","id":%v,"error":{"code":-1,"message":"user rejected sampling"}}`+"\n", request["id"])
+	var clientErr *aggregator.ClientError
+	if got := <-done; !errors.As(got.err, &clientErr) || clientErr.Code != -1 {
+		t.Errorf("relaySampling() error = %v, want the client's error", got.err)
+	}
+
+	// Requests still waiting when the client disconnects fail
+	done = relay()
+	readRequest()
+	clientOut.Close()
+	if got := <-done; !errors.Is(got.err, errNoClient) {
+		t.Errorf("relaySampling() error = %v, want %v", got.err, errNoClient)
+	}
+	if err := <-served; err != nil {
+		t.Errorf("serve() error = %v", err)
+	}
+
+	if _, err := s.relaySampling(context.Background(), nil); !errors.Is(err, errNoClient) {
+		t.Errorf("relaySampling() without a client error = %v, want %v", err, errNoClient)
+	}
+}
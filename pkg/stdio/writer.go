@@ -0,0 +1,191 @@
+package stdio
+
+import (
+	"bufio"
+	"io"
+	"sync"
+
+	"github.com/nazar256/combine-mcp/pkg/framing"
+)
+
+const (
+	// pipelineQueueSize is how many encoded messages may wait for the writer in pipelined mode
+	pipelineQueueSize = 64
+	// coalesceBufferSize is how many bytes of messages are held back before they are written out in coalescing mode
+	coalesceBufferSize = 64 * 1024
+)
+
+// outputWriter serializes writes of JSON-RPC messages to the client.
+// In pipelined mode messages are handed to a dedicated goroutine so writing
+// a response overlaps with reading the next request, while still preserving order.
+// In coalescing mode messages written with WriteBuffered are held in a buffer until
+// Flush, so a burst of responses reaches the client in as few writes as possible.
+type outputWriter struct {
+	out      io.Writer
+	buffered *bufio.Writer // Buffer over out in coalescing mode, nil otherwise
+	framing  string
+	writeMu  sync.Mutex
+
+	sendMu sync.Mutex
+	queue  chan []byte
+	done   chan struct{}
+	closed bool
+}
+
+// newOutputWriter creates a writer of messages framed as the given mode to out, optionally backed by a writer
+// goroutine and optionally coalescing messages
+func newOutputWriter(out io.Writer, pipelined, coalesce bool, mode string) *outputWriter {
+	w := &outputWriter{out: out, framing: mode}
+	if coalesce {
+		w.buffered = bufio.NewWriterSize(out, coalesceBufferSize)
+		w.out = w.buffered
+	}
+	if pipelined {
+		w.queue = make(chan []byte, pipelineQueueSize)
+		w.done = make(chan struct{})
+		go w.run()
+	}
+	return w
+}
+
+// run writes queued messages until the queue is closed, flushing whenever no more messages are waiting
+func (w *outputWriter) run() {
+	defer close(w.done)
+	for message := range w.queue {
+		w.writeMessage(message, len(w.queue) == 0)
+	}
+}
+
+// writeMessage writes a single framed message, flushing it and any held back before it if flush is set
+func (w *outputWriter) writeMessage(message []byte, flush bool) {
+	w.writeMu.Lock()
+	defer w.writeMu.Unlock()
+	w.out.Write(framing.Encode(w.framing, message))
+	if flush && w.buffered != nil {
+		w.buffered.Flush()
+	}
+}
+
+// Write sends a message to the client right away; messages written after Close are dropped
+func (w *outputWriter) Write(message []byte) {
+	w.send(message, true)
+}
+
+// WriteBuffered sends a message to the client with the next Flush in coalescing mode, right away otherwise
+func (w *outputWriter) WriteBuffered(message []byte) {
+	w.send(message, false)
+}
+
+// send writes a message directly or hands it to the writer goroutine, which flushes once its queue drains
+func (w *outputWriter) send(message []byte, flush bool) {
+	w.sendMu.Lock()
+	defer w.sendMu.Unlock()
+	if w.closed {
+		return
+	}
+	if w.queue == nil {
+		w.writeMessage(message, flush)
+		return
+	}
+	w.queue <- message
+}
+
+// Flush writes out the messages held back in coalescing mode
+func (w *outputWriter) Flush() {
+	w.writeMu.Lock()
+	defer w.writeMu.Unlock()
+	if w.buffered != nil {
+		w.buffered.Flush()
+	}
+}
+
+// Close flushes any queued messages and stops the writer goroutine
+func (w *outputWriter) Close() {
+	w.sendMu.Lock()
+	if w.closed {
+		w.sendMu.Unlock()
+		return
+	}
+	w.closed = true
+	if w.queue != nil {
+		close(w.queue)
+	}
+	w.sendMu.Unlock()
+
+	if w.done != nil {
+		<-w.done
+	}
+	w.Flush()
+}
+
+// responseSequence writes the responses to the requests read from the client. When responses keep their
+// order, each one is held back until the responses to every request read before it are written, so tool
+// calls still run concurrently but the client gets their results in the order it asked.
+type responseSequence struct {
+	writer  *outputWriter
+	ordered bool
+
+	mu      sync.Mutex
+	pending []*sequencedResponse
+}
+
+// sequencedResponse is the place of a response in the sequence, filled once the request is handled
+type sequencedResponse struct {
+	message []byte
+	done    bool
+}
+
+// newResponseSequence creates a sequence of responses written to writer, in request order if ordered is set
+func newResponseSequence(writer *outputWriter, ordered bool) *responseSequence {
+	return &responseSequence{writer: writer, ordered: ordered}
+}
+
+// reserve takes the next place in the sequence for the request read last and returns the function writing its
+// response, nil for none; flush writes the response right away when responses don't keep their order
+func (q *responseSequence) reserve(flush bool) func(message []byte) {
+	if !q.ordered {
+		return func(message []byte) {
+			if message == nil {
+				return
+			}
+			if flush {
+				q.writer.Write(message)
+			} else {
+				q.writer.WriteBuffered(message)
+			}
+		}
+	}
+
+	response := &sequencedResponse{}
+	q.mu.Lock()
+	q.pending = append(q.pending, response)
+	q.mu.Unlock()
+	return func(message []byte) { q.complete(response, message) }
+}
+
+// complete fills a place in the sequence and writes every response at the head of the sequence that's done
+func (q *responseSequence) complete(response *sequencedResponse, message []byte) {
+	q.mu.Lock()
+	defer q.mu.Unlock()
+	response.message, response.done = message, true
+	for len(q.pending) > 0 && q.pending[0].done {
+		if message := q.pending[0].message; message != nil {
+			q.writer.WriteBuffered(message)
+		}
+		q.pending[0] = nil
+		q.pending = q.pending[1:]
+	}
+}
+
+// flushBeforeRead flushes the output whenever reading input would have to wait for the client, so no
+// response is held back while the client waits for it
+type flushBeforeRead struct {
+	in    io.Reader
+	flush func()
+}
+
+// Read flushes the output and reads from the input; it's only called once buffered input runs out
+func (r *flushBeforeRead) Read(p []byte) (int, error) {
+	r.flush()
+	return r.in.Read(p)
+}